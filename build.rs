@@ -166,6 +166,8 @@ fn dynamic_linking(major: usize, minor: usize) {
     println!("cargo:rustc-link-lib=dylib=cudnn");
     #[cfg(feature = "runtime")]
     println!("cargo:rustc-link-lib=dylib=cudart");
+    #[cfg(feature = "nvtx")]
+    println!("cargo:rustc-link-lib=dylib=nvToolsExt");
 }
 
 #[allow(unused)]
@@ -240,6 +242,9 @@ fn static_linking(major: usize, minor: usize) {
     println!("cargo:rustc-link-lib=static:+whole-archive=cusolver_static");
     #[cfg(feature = "cudnn")]
     println!("cargo:rustc-link-lib=static:+whole-archive=cudnn");
+    // NVTX has no static library
+    #[cfg(feature = "nvtx")]
+    println!("cargo:rustc-link-lib=dylib=nvToolsExt");
 }
 
 #[allow(unused)]
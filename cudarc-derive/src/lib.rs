@@ -0,0 +1,71 @@
+//! Derive macros for [cudarc](https://docs.rs/cudarc). Use these through the `derive` feature of
+//! cudarc, e.g. `cudarc::driver::DeviceRepr`, instead of depending on this crate directly.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Error};
+
+/// Implements `cudarc::driver::DeviceRepr` for a struct, so it can be passed to kernels by value.
+///
+/// The struct must:
+/// 1. Be `#[repr(C)]` or `#[repr(transparent)]`, so its layout matches the equivalent C struct.
+/// 2. Be [Copy], which rules out types with a [Drop] impl.
+/// 3. Only have fields that implement `DeviceRepr` themselves, which rules out pointers, references
+///    and heap allocated types like `String` or `Vec`.
+#[proc_macro_derive(DeviceRepr)]
+pub fn derive_device_repr(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(mut input: DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "DeviceRepr can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut has_c_layout = false;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("repr")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("C") || meta.path.is_ident("transparent") {
+                has_c_layout = true;
+            }
+            Ok(())
+        })?;
+    }
+    if !has_c_layout {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "DeviceRepr requires #[repr(C)] or #[repr(transparent)]",
+        ));
+    }
+
+    let field_tys: Vec<_> = fields.iter().map(|f| f.ty.clone()).collect();
+    for param in input.generics.type_params_mut() {
+        param
+            .bounds
+            .push(parse_quote!(::cudarc::driver::DeviceRepr));
+        param.bounds.push(parse_quote!(::core::marker::Copy));
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        const _: () = {
+            fn assert_copy<T: ::core::marker::Copy>() {}
+            fn assert_device_repr<T: ::cudarc::driver::DeviceRepr>() {}
+            fn assert_fields #impl_generics () #where_clause {
+                assert_copy::<#ident #ty_generics>();
+                #(assert_device_repr::<#field_tys>();)*
+            }
+        };
+        unsafe impl #impl_generics ::cudarc::driver::DeviceRepr for #ident #ty_generics #where_clause {}
+    })
+}
@@ -0,0 +1,24 @@
+use cudarc::driver::{sys::CUevent_flags, CudaContext, DriverError};
+
+fn main() -> Result<(), DriverError> {
+    let ctx = CudaContext::new(0)?;
+    let stream = ctx.default_stream();
+
+    let host = vec![1.0f32; 1 << 24];
+    let mut dev = stream.alloc_zeros::<f32>(host.len())?;
+
+    // events are created with timing disabled by default, so we need to opt in.
+    let start = stream.record_event(Some(CUevent_flags::CU_EVENT_DEFAULT))?;
+    stream.memcpy_htod(&host, &mut dev)?;
+    let end = stream.record_event(Some(CUevent_flags::CU_EVENT_DEFAULT))?;
+
+    // this waits for both events to complete.
+    let ms = start.elapsed_ms(&end)?;
+    let gb = (host.len() * std::mem::size_of::<f32>()) as f32 / 1e9;
+    println!(
+        "Copied {gb:.3} GB in {ms:.3} ms ({:.3} GB/s)",
+        gb / (ms / 1e3)
+    );
+
+    Ok(())
+}
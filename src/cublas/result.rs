@@ -109,6 +109,46 @@ pub unsafe fn dgemv(
     sys::cublasDgemv_v2(handle, trans, m, n, alpha, a, lda, x, incx, beta, y, incy).result()
 }
 
+/// Single precision `y = alpha * x + y`. See
+/// [nvidia docs](https://docs.nvidia.com/cuda/cublas/index.html#cublas-t-axpy)
+///
+/// # Safety
+///
+/// - `x` and `y` must be valid device pointers that have not been freed.
+/// - `alpha` can be a pointer to host memory, but must be not null
+/// - the increments and sizes must be sized correctly
+pub unsafe fn saxpy(
+    handle: sys::cublasHandle_t,
+    n: c_int,
+    alpha: *const f32,
+    x: *const f32,
+    incx: c_int,
+    y: *mut f32,
+    incy: c_int,
+) -> Result<(), CublasError> {
+    sys::cublasSaxpy_v2(handle, n, alpha, x, incx, y, incy).result()
+}
+
+/// Double precision `y = alpha * x + y`. See
+/// [nvidia docs](https://docs.nvidia.com/cuda/cublas/index.html#cublas-t-axpy)
+///
+/// # Safety
+///
+/// - `x` and `y` must be valid device pointers that have not been freed.
+/// - `alpha` can be a pointer to host memory, but must be not null
+/// - the increments and sizes must be sized correctly
+pub unsafe fn daxpy(
+    handle: sys::cublasHandle_t,
+    n: c_int,
+    alpha: *const f64,
+    x: *const f64,
+    incx: c_int,
+    y: *mut f64,
+    incy: c_int,
+) -> Result<(), CublasError> {
+    sys::cublasDaxpy_v2(handle, n, alpha, x, incx, y, incy).result()
+}
+
 #[cfg(feature = "f16")]
 /// Half precision matmul. See
 /// [nvidia docs](https://docs.nvidia.com/cuda/cublas/index.html#cublas-t-gemm)
@@ -1,4 +1,4 @@
-//! Safe abstractions around [crate::cublas::result] for doing gemm and gemv.
+//! Safe abstractions around [crate::cublas::result] for doing gemm, gemv and axpy.
 #![allow(clippy::too_many_arguments)]
 
 use super::{result, result::CublasError, sys};
@@ -9,8 +9,8 @@ use std::sync::Arc;
 /// Wrapper around [sys::cublasHandle_t]
 ///
 /// 1. Create with [CudaBlas::new()]
-/// 2. Execute gemm/gemv kernels with [Gemv] and [Gemm]. Both f32 and f64 are supported
-///    for both
+/// 2. Execute gemm/gemv/axpy kernels with [Gemm], [Gemv] and [Axpy]. Both f32 and f64 are
+///    supported for all of them
 ///
 /// Note: This maintains a instance of [`Arc<CudaDevice>`], so will prevent the device
 /// from being dropped.
@@ -40,7 +40,7 @@ impl CudaBlas {
     }
 
     /// Sets the handle's current to either the stream specified, or the device's default work
-    /// stream.
+    /// stream. The handle keeps `stream` alive until it is replaced or the handle is dropped.
     ///
     /// # Safety
     /// This is unsafe because you can end up scheduling multiple concurrent kernels that all
@@ -76,6 +76,49 @@ impl CudaBlas {
             Ok(mode.assume_init())
         }
     }
+
+    /// Set the handle's math mode.
+    /// ref: <https://docs.nvidia.com/cuda/cublas/#cublassetmathmode>
+    ///
+    /// With [MathMode::TF32TensorOp], f32 gemms on Ampere and newer run on tensor cores
+    /// using TF32 internally, which is faster at the cost of a few bits of mantissa.
+    pub fn set_math_mode(&self, mode: MathMode) -> Result<(), CublasError> {
+        unsafe {
+            sys::cublasSetMathMode(self.handle, mode.into()).result()?;
+        }
+        Ok(())
+    }
+
+    /// Get the handle's current math mode.
+    /// ref: <https://docs.nvidia.com/cuda/cublas/#cublasgetmathmode>
+    pub fn get_math_mode(&self) -> Result<sys::cublasMath_t, CublasError> {
+        unsafe {
+            let mut mode = ::core::mem::MaybeUninit::uninit();
+            sys::cublasGetMathMode(self.handle, mode.as_mut_ptr()).result()?;
+            Ok(mode.assume_init())
+        }
+    }
+}
+
+/// Math mode of a [CudaBlas] handle, see [CudaBlas::set_math_mode()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MathMode {
+    /// Tensor cores are used whenever they don't reduce precision.
+    Default,
+    /// Allow f32 operations to use TF32 tensor cores.
+    TF32TensorOp,
+    /// Use the prescribed compute precision everywhere, for numerical robustness testing.
+    PedanticMath,
+}
+
+impl From<MathMode> for sys::cublasMath_t {
+    fn from(mode: MathMode) -> Self {
+        match mode {
+            MathMode::Default => sys::cublasMath_t::CUBLAS_DEFAULT_MATH,
+            MathMode::TF32TensorOp => sys::cublasMath_t::CUBLAS_TF32_TENSOR_OP_MATH,
+            MathMode::PedanticMath => sys::cublasMath_t::CUBLAS_PEDANTIC_MATH,
+        }
+    }
 }
 
 impl Drop for CudaBlas {
@@ -87,6 +130,43 @@ impl Drop for CudaBlas {
     }
 }
 
+/// Number of elements spanned by a column major `rows` x `cols` matrix with leading dimension `ld`,
+/// or `None` if `ld < rows`.
+fn matrix_len(rows: c_int, cols: c_int, ld: c_int) -> Option<usize> {
+    if rows <= 0 || cols <= 0 {
+        return Some(0);
+    }
+    if ld < rows {
+        return None;
+    }
+    (cols as usize - 1)
+        .checked_mul(ld as usize)?
+        .checked_add(rows as usize)
+}
+
+/// Number of elements spanned by a vector of `n` elements spaced `inc` apart,
+/// or `None` if `inc` is 0.
+fn vector_len(n: c_int, inc: c_int) -> Option<usize> {
+    if n <= 0 {
+        return Some(0);
+    }
+    if inc == 0 {
+        return None;
+    }
+    (n as usize - 1)
+        .checked_mul(inc.unsigned_abs() as usize)?
+        .checked_add(1)
+}
+
+fn check_len(len: usize, required: Option<usize>) -> Result<(), CublasError> {
+    match required {
+        Some(required) if len >= required => Ok(()),
+        _ => Err(CublasError(
+            sys::cublasStatus_t::CUBLAS_STATUS_INVALID_VALUE,
+        )),
+    }
+}
+
 /// Configuration for [Gemv]
 #[derive(Debug, Copy, Clone)]
 pub struct GemvConfig<T> {
@@ -100,9 +180,27 @@ pub struct GemvConfig<T> {
     pub incy: c_int,
 }
 
+impl<T> GemvConfig<T> {
+    /// Returns [sys::cublasStatus_t::CUBLAS_STATUS_INVALID_VALUE] if `a`, `x`, or `y`
+    /// are too short for the dimensions and increments.
+    fn check_lens(&self, a: usize, x: usize, y: usize) -> Result<(), CublasError> {
+        let (x_n, y_n) = match self.trans {
+            sys::cublasOperation_t::CUBLAS_OP_N => (self.n, self.m),
+            _ => (self.m, self.n),
+        };
+        check_len(a, matrix_len(self.m, self.n, self.lda))?;
+        check_len(x, vector_len(x_n, self.incx))?;
+        check_len(y, vector_len(y_n, self.incy))
+    }
+}
+
 /// Matrix vector multiplication with elements of type `T`
 pub trait Gemv<T> {
-    /// Matrix vector multiplication.
+    /// Matrix vector multiplication. See
+    /// [nvidia docs](https://docs.nvidia.com/cuda/cublas/index.html#cublas-t-gemv)
+    ///
+    /// Returns [sys::cublasStatus_t::CUBLAS_STATUS_INVALID_VALUE] if `a`, `x`, or `y` are too
+    /// short for `cfg`, or if an increment is 0.
     ///
     /// # Safety
     /// This is unsafe because improper arguments may lead to invalid
@@ -124,6 +222,7 @@ impl Gemv<f32> for CudaBlas {
         x: &X,
         y: &mut Y,
     ) -> Result<(), CublasError> {
+        cfg.check_lens(a.len(), x.len(), y.len())?;
         let (a, _record_a) = a.device_ptr(&self.stream);
         let (x, _record_x) = x.device_ptr(&self.stream);
         let (y, _record_y) = y.device_ptr_mut(&self.stream);
@@ -152,6 +251,7 @@ impl Gemv<f64> for CudaBlas {
         x: &X,
         y: &mut Y,
     ) -> Result<(), CublasError> {
+        cfg.check_lens(a.len(), x.len(), y.len())?;
         let (a, _record_a) = a.device_ptr(&self.stream);
         let (x, _record_x) = x.device_ptr(&self.stream);
         let (y, _record_y) = y.device_ptr_mut(&self.stream);
@@ -172,6 +272,80 @@ impl Gemv<f64> for CudaBlas {
     }
 }
 
+/// Configuration for [Axpy]
+#[derive(Debug, Copy, Clone)]
+pub struct AxpyConfig<T> {
+    pub n: c_int,
+    pub alpha: T,
+    pub incx: c_int,
+    pub incy: c_int,
+}
+
+/// Vector scale and add with elements of type `T`
+pub trait Axpy<T> {
+    /// Computes `y = alpha * x + y`. See
+    /// [nvidia docs](https://docs.nvidia.com/cuda/cublas/index.html#cublas-t-axpy)
+    ///
+    /// Returns [sys::cublasStatus_t::CUBLAS_STATUS_INVALID_VALUE] if `x` or `y` are too
+    /// short for `cfg`, or if an increment is 0.
+    ///
+    /// # Safety
+    /// This is unsafe because improper arguments may lead to invalid
+    /// memory accesses.
+    unsafe fn axpy<X: DevicePtr<T>, Y: DevicePtrMut<T>>(
+        &self,
+        cfg: AxpyConfig<T>,
+        x: &X,
+        y: &mut Y,
+    ) -> Result<(), CublasError>;
+}
+
+impl Axpy<f32> for CudaBlas {
+    unsafe fn axpy<X: DevicePtr<f32>, Y: DevicePtrMut<f32>>(
+        &self,
+        cfg: AxpyConfig<f32>,
+        x: &X,
+        y: &mut Y,
+    ) -> Result<(), CublasError> {
+        check_len(x.len(), vector_len(cfg.n, cfg.incx))?;
+        check_len(y.len(), vector_len(cfg.n, cfg.incy))?;
+        let (x, _record_x) = x.device_ptr(&self.stream);
+        let (y, _record_y) = y.device_ptr_mut(&self.stream);
+        result::saxpy(
+            self.handle,
+            cfg.n,
+            (&cfg.alpha) as *const _,
+            x as *const _,
+            cfg.incx,
+            y as *mut _,
+            cfg.incy,
+        )
+    }
+}
+
+impl Axpy<f64> for CudaBlas {
+    unsafe fn axpy<X: DevicePtr<f64>, Y: DevicePtrMut<f64>>(
+        &self,
+        cfg: AxpyConfig<f64>,
+        x: &X,
+        y: &mut Y,
+    ) -> Result<(), CublasError> {
+        check_len(x.len(), vector_len(cfg.n, cfg.incx))?;
+        check_len(y.len(), vector_len(cfg.n, cfg.incy))?;
+        let (x, _record_x) = x.device_ptr(&self.stream);
+        let (y, _record_y) = y.device_ptr_mut(&self.stream);
+        result::daxpy(
+            self.handle,
+            cfg.n,
+            (&cfg.alpha) as *const _,
+            x as *const _,
+            cfg.incx,
+            y as *mut _,
+            cfg.incy,
+        )
+    }
+}
+
 /// Configuration for [Gemm]
 #[derive(Debug, Copy, Clone)]
 pub struct GemmConfig<T> {
@@ -197,6 +371,35 @@ pub struct StridedBatchedConfig<T> {
     pub stride_c: c_longlong,
 }
 
+impl<T> StridedBatchedConfig<T> {
+    /// Returns [sys::cublasStatus_t::CUBLAS_STATUS_INVALID_VALUE] if any of `a`, `b`, or `c`
+    /// is too short to hold `batch_size` matrices spaced `stride` elements apart.
+    fn check_lens(&self, a: usize, b: usize, c: usize) -> Result<(), CublasError> {
+        let g = &self.gemm;
+        let (a_rows, a_cols) = match g.transa {
+            sys::cublasOperation_t::CUBLAS_OP_N => (g.m, g.k),
+            _ => (g.k, g.m),
+        };
+        let (b_rows, b_cols) = match g.transb {
+            sys::cublasOperation_t::CUBLAS_OP_N => (g.k, g.n),
+            _ => (g.n, g.k),
+        };
+        let batched = |matrix: Option<usize>, stride: c_longlong| {
+            let matrix = matrix?;
+            if self.batch_size <= 0 || matrix == 0 {
+                return Some(0);
+            }
+            let stride = usize::try_from(stride).ok()?;
+            (self.batch_size as usize - 1)
+                .checked_mul(stride)?
+                .checked_add(matrix)
+        };
+        check_len(a, batched(matrix_len(a_rows, a_cols, g.lda), self.stride_a))?;
+        check_len(b, batched(matrix_len(b_rows, b_cols, g.ldb), self.stride_b))?;
+        check_len(c, batched(matrix_len(g.m, g.n, g.ldc), self.stride_c))
+    }
+}
+
 /// Matrix matrix multiplication with elements of type `T`.
 pub trait Gemm<T> {
     /// Matrix matrix multiplication. See
@@ -216,6 +419,9 @@ pub trait Gemm<T> {
     /// Batched matrix multiplication with stride support on batch dimension. See
     /// [nvidia docs](https://docs.nvidia.com/cuda/cublas/index.html#cublas-t-gemmstridedbatched)
     ///
+    /// Returns [sys::cublasStatus_t::CUBLAS_STATUS_INVALID_VALUE] if `a`, `b`, or `c` are too
+    /// short for `cfg.batch_size` matrices spaced by their respective strides.
+    ///
     /// # Safety
     /// This is unsafe because improper arguments may lead to invalid
     /// memory accesses.
@@ -229,16 +435,45 @@ pub trait Gemm<T> {
 }
 
 #[cfg(feature = "f16")]
-impl Gemm<half::f16> for CudaBlas {
-    unsafe fn gemm<A: DevicePtr<half::f16>, B: DevicePtr<half::f16>, C: DevicePtrMut<half::f16>>(
+impl CudaBlas {
+    /// Half precision matmul with an explicit `compute_type` and `algo`, e.g.
+    /// [sys::cublasComputeType_t::CUBLAS_COMPUTE_32F] to accumulate in f32.
+    /// [Gemm::gemm()] for [half::f16] uses `CUBLAS_COMPUTE_32F` with `CUBLAS_GEMM_DEFAULT`.
+    ///
+    /// `cfg.alpha` and `cfg.beta` are converted to f32 unless `compute_type` is one of the
+    /// `CUBLAS_COMPUTE_16F` variants. See
+    /// [nvidia docs](https://docs.nvidia.com/cuda/cublas/index.html#cublasgemmex)
+    ///
+    /// # Safety
+    /// This is unsafe because improper arguments may lead to invalid
+    /// memory accesses.
+    pub unsafe fn gemm_f16_ex<
+        A: DevicePtr<half::f16>,
+        B: DevicePtr<half::f16>,
+        C: DevicePtrMut<half::f16>,
+    >(
         &self,
         cfg: GemmConfig<half::f16>,
+        compute_type: sys::cublasComputeType_t,
+        algo: sys::cublasGemmAlgo_t,
         a: &A,
         b: &B,
         c: &mut C,
     ) -> Result<(), CublasError> {
-        let alpha: f32 = cfg.alpha.to_f32();
-        let beta: f32 = cfg.beta.to_f32();
+        let alpha_f32: f32 = cfg.alpha.to_f32();
+        let beta_f32: f32 = cfg.beta.to_f32();
+        let (alpha, beta): (*const core::ffi::c_void, *const core::ffi::c_void) = match compute_type
+        {
+            sys::cublasComputeType_t::CUBLAS_COMPUTE_16F
+            | sys::cublasComputeType_t::CUBLAS_COMPUTE_16F_PEDANTIC => (
+                (&cfg.alpha) as *const half::f16 as *const _,
+                (&cfg.beta) as *const half::f16 as *const _,
+            ),
+            _ => (
+                (&alpha_f32) as *const f32 as *const _,
+                (&beta_f32) as *const f32 as *const _,
+            ),
+        };
         let (a, _record_a) = a.device_ptr(&self.stream);
         let (b, _record_b) = b.device_ptr(&self.stream);
         let (c, _record_c) = c.device_ptr_mut(&self.stream);
@@ -249,19 +484,39 @@ impl Gemm<half::f16> for CudaBlas {
             cfg.m,
             cfg.n,
             cfg.k,
-            (&alpha) as *const f32 as *const _,
+            alpha,
             a as *const _,
             sys::cudaDataType_t::CUDA_R_16F,
             cfg.lda,
             b as *const _,
             sys::cudaDataType_t::CUDA_R_16F,
             cfg.ldb,
-            (&beta) as *const f32 as *const _,
+            beta,
             c as *mut _,
             sys::cudaDataType_t::CUDA_R_16F,
             cfg.ldc,
+            compute_type,
+            algo,
+        )
+    }
+}
+
+#[cfg(feature = "f16")]
+impl Gemm<half::f16> for CudaBlas {
+    unsafe fn gemm<A: DevicePtr<half::f16>, B: DevicePtr<half::f16>, C: DevicePtrMut<half::f16>>(
+        &self,
+        cfg: GemmConfig<half::f16>,
+        a: &A,
+        b: &B,
+        c: &mut C,
+    ) -> Result<(), CublasError> {
+        self.gemm_f16_ex(
+            cfg,
             sys::cublasComputeType_t::CUBLAS_COMPUTE_32F,
             sys::cublasGemmAlgo_t::CUBLAS_GEMM_DEFAULT,
+            a,
+            b,
+            c,
         )
     }
     unsafe fn gemm_strided_batched<
@@ -275,6 +530,7 @@ impl Gemm<half::f16> for CudaBlas {
         b: &B,
         c: &mut C,
     ) -> Result<(), CublasError> {
+        cfg.check_lens(a.len(), b.len(), c.len())?;
         let alpha: f32 = cfg.gemm.alpha.to_f32();
         let beta: f32 = cfg.gemm.beta.to_f32();
         let (a, _record_a) = a.device_ptr(&self.stream);
@@ -359,6 +615,7 @@ impl Gemm<half::bf16> for CudaBlas {
         b: &B,
         c: &mut C,
     ) -> Result<(), CublasError> {
+        cfg.check_lens(a.len(), b.len(), c.len())?;
         let alpha: f32 = cfg.gemm.alpha.to_f32();
         let beta: f32 = cfg.gemm.beta.to_f32();
         let (a, _record_a) = a.device_ptr(&self.stream);
@@ -428,6 +685,7 @@ impl Gemm<f32> for CudaBlas {
         b: &B,
         c: &mut C,
     ) -> Result<(), CublasError> {
+        cfg.check_lens(a.len(), b.len(), c.len())?;
         let (a, _record_a) = a.device_ptr(&self.stream);
         let (b, _record_b) = b.device_ptr(&self.stream);
         let (c, _record_c) = c.device_ptr_mut(&self.stream);
@@ -490,6 +748,7 @@ impl Gemm<f64> for CudaBlas {
         b: &B,
         c: &mut C,
     ) -> Result<(), CublasError> {
+        cfg.check_lens(a.len(), b.len(), c.len())?;
         let (a, _record_a) = a.device_ptr(&self.stream);
         let (b, _record_b) = b.device_ptr(&self.stream);
         let (c, _record_c) = c.device_ptr_mut(&self.stream);
@@ -614,6 +873,106 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_gemv_invalid_lens() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let blas = CudaBlas::new(stream.clone()).unwrap();
+        let a_dev = stream.alloc_zeros::<f32>(6).unwrap();
+        let x_dev = stream.alloc_zeros::<f32>(3).unwrap();
+        let mut y_dev = stream.alloc_zeros::<f32>(2).unwrap();
+        let cfg = GemvConfig {
+            trans: sys::cublasOperation_t::CUBLAS_OP_N,
+            m: 2,
+            n: 3,
+            alpha: 1.0,
+            lda: 2,
+            incx: 1,
+            beta: 0.0,
+            incy: 1,
+        };
+        unsafe { blas.gemv(cfg, &a_dev, &x_dev, &mut y_dev) }.unwrap();
+        let invalid = Err(CublasError(
+            sys::cublasStatus_t::CUBLAS_STATUS_INVALID_VALUE,
+        ));
+        let cfg_t = GemvConfig {
+            trans: sys::cublasOperation_t::CUBLAS_OP_T,
+            ..cfg
+        };
+        assert_eq!(
+            unsafe { blas.gemv(cfg_t, &a_dev, &x_dev, &mut y_dev) },
+            invalid
+        );
+        let cfg_inc = GemvConfig { incx: 2, ..cfg };
+        assert_eq!(
+            unsafe { blas.gemv(cfg_inc, &a_dev, &x_dev, &mut y_dev) },
+            invalid
+        );
+        let cfg_lda = GemvConfig { lda: 1, ..cfg };
+        assert_eq!(
+            unsafe { blas.gemv(cfg_lda, &a_dev, &x_dev, &mut y_dev) },
+            invalid
+        );
+    }
+
+    #[test]
+    fn test_saxpy() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let blas = CudaBlas::new(stream.clone()).unwrap();
+        let x_dev = stream.memcpy_stod(&[1.0f32, 2.0, 3.0, 4.0]).unwrap();
+        let mut y_dev = stream.memcpy_stod(&[1.0f32, 1.0, 1.0, 1.0]).unwrap();
+        let cfg = AxpyConfig {
+            n: 4,
+            alpha: 2.0,
+            incx: 1,
+            incy: 1,
+        };
+        unsafe { blas.axpy(cfg, &x_dev, &mut y_dev) }.unwrap();
+        assert_eq!(stream.memcpy_dtov(&y_dev).unwrap(), [3.0, 5.0, 7.0, 9.0]);
+
+        let cfg = AxpyConfig {
+            n: 2,
+            incx: 2,
+            ..cfg
+        };
+        unsafe { blas.axpy(cfg, &x_dev, &mut y_dev) }.unwrap();
+        assert_eq!(stream.memcpy_dtov(&y_dev).unwrap(), [5.0, 11.0, 7.0, 9.0]);
+
+        let cfg = AxpyConfig { n: 3, ..cfg };
+        assert_eq!(
+            unsafe { blas.axpy(cfg, &x_dev, &mut y_dev) },
+            Err(CublasError(
+                sys::cublasStatus_t::CUBLAS_STATUS_INVALID_VALUE
+            ))
+        );
+    }
+
+    #[test]
+    fn test_daxpy() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let blas = CudaBlas::new(stream.clone()).unwrap();
+        let x_dev = stream.memcpy_stod(&[1.0f64, -2.0, 0.5]).unwrap();
+        let mut y_dev = stream.memcpy_stod(&[0.25f64, 0.5, 1.0]).unwrap();
+        let cfg = AxpyConfig {
+            n: 3,
+            alpha: -1.0,
+            incx: 1,
+            incy: 1,
+        };
+        unsafe { blas.axpy(cfg, &x_dev, &mut y_dev) }.unwrap();
+        assert_eq!(stream.memcpy_dtov(&y_dev).unwrap(), [-0.75, 2.5, 0.5]);
+
+        let cfg = AxpyConfig { incy: 0, ..cfg };
+        assert_eq!(
+            unsafe { blas.axpy(cfg, &x_dev, &mut y_dev) },
+            Err(CublasError(
+                sys::cublasStatus_t::CUBLAS_STATUS_INVALID_VALUE
+            ))
+        );
+    }
+
     #[test]
     fn test_dgemv() {
         let ctx = CudaContext::new(0).unwrap();
@@ -799,6 +1158,84 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "f16")]
+    #[test]
+    fn test_gemm_f16_ex() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let blas = CudaBlas::new(stream.clone()).unwrap();
+        const M: usize = 3;
+        const K: usize = 4;
+        const N: usize = 5;
+        let a: [[f32; K]; M] = [
+            [-0.5944882, 1.8055636, 0.52204555, -0.00397902],
+            [-0.38346434, -0.38013917, 0.4198623, -0.22479166],
+            [-1.6661372, -0.4568837, -0.9043474, 0.39125723],
+        ]
+        .map(|r| r.map(|x| half::f16::from_f32(x).to_f32()));
+        let b: [[f32; N]; K] = [
+            [1.1292169, -0.13450263, 0.62789696, -0.5685516, 0.21946938],
+            [1.0585804, -0.39789402, 0.90205914, 0.989318, -0.3443096],
+            [1.3412506, 0.3059701, -0.9714474, -0.36113533, -1.6809629],
+            [3.4746711, -1.0930681, 0.16502666, -0.59988785, 0.41375792],
+        ]
+        .map(|r| r.map(|x| half::f16::from_f32(x).to_f32()));
+        let mut c: [[f32; N]; M] = [[0.0; N]; M];
+        gemm_truth(1.0, &a, &b, 0.0, &mut c);
+
+        let a_dev = stream
+            .memcpy_stod(
+                &a.concat()
+                    .into_iter()
+                    .map(half::f16::from_f32)
+                    .collect::<Vec<_>>(),
+            )
+            .unwrap();
+        let b_dev = stream
+            .memcpy_stod(
+                &b.concat()
+                    .into_iter()
+                    .map(half::f16::from_f32)
+                    .collect::<Vec<_>>(),
+            )
+            .unwrap();
+        let mut c_dev = stream.alloc_zeros::<half::f16>(M * N).unwrap();
+        unsafe {
+            blas.gemm_f16_ex(
+                GemmConfig {
+                    transa: sys::cublasOperation_t::CUBLAS_OP_N,
+                    transb: sys::cublasOperation_t::CUBLAS_OP_N,
+                    m: N as i32,
+                    n: M as i32,
+                    k: K as i32,
+                    alpha: half::f16::ONE,
+                    lda: N as i32,
+                    ldb: K as i32,
+                    beta: half::f16::ZERO,
+                    ldc: N as i32,
+                },
+                sys::cublasComputeType_t::CUBLAS_COMPUTE_32F,
+                sys::cublasGemmAlgo_t::CUBLAS_GEMM_DEFAULT,
+                &b_dev,
+                &a_dev,
+                &mut c_dev,
+            )
+        }
+        .unwrap();
+
+        let c_host = stream.memcpy_dtov(&c_dev).unwrap();
+        for m in 0..M {
+            for n in 0..N {
+                let found = c_host[m * N + n].to_f32();
+                let expected = c[m][n];
+                assert!(
+                    (found - expected).abs() <= 1e-2,
+                    "found={found:?}, expected={expected:?}"
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_sgemm() {
         let ctx = CudaContext::new(0).unwrap();
@@ -864,6 +1301,61 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_sgemm_strided_batched() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let blas = CudaBlas::new(stream.clone()).unwrap();
+        // two 2x2 column major matrices per operand
+        let a_dev = stream
+            .memcpy_stod(&[1.0f32, 0.0, 0.0, 1.0, 2.0, 0.0, 0.0, 2.0])
+            .unwrap();
+        let b_dev = stream
+            .memcpy_stod(&[1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0])
+            .unwrap();
+        let mut c_dev = stream.alloc_zeros::<f32>(8).unwrap();
+        let cfg = StridedBatchedConfig {
+            gemm: GemmConfig {
+                transa: sys::cublasOperation_t::CUBLAS_OP_N,
+                transb: sys::cublasOperation_t::CUBLAS_OP_N,
+                m: 2,
+                n: 2,
+                k: 2,
+                alpha: 1.0,
+                lda: 2,
+                ldb: 2,
+                beta: 0.0,
+                ldc: 2,
+            },
+            batch_size: 2,
+            stride_a: 4,
+            stride_b: 4,
+            stride_c: 4,
+        };
+        unsafe { blas.gemm_strided_batched(cfg, &a_dev, &b_dev, &mut c_dev) }.unwrap();
+        assert_eq!(
+            stream.memcpy_dtov(&c_dev).unwrap(),
+            [1.0, 2.0, 3.0, 4.0, 10.0, 12.0, 14.0, 16.0]
+        );
+
+        let mut short = stream.alloc_zeros::<f32>(7).unwrap();
+        assert_eq!(
+            unsafe { blas.gemm_strided_batched(cfg, &a_dev, &b_dev, &mut short) },
+            Err(CublasError(
+                sys::cublasStatus_t::CUBLAS_STATUS_INVALID_VALUE
+            ))
+        );
+
+        // a stride of 0 reuses the same `a` for every batch
+        let a_single = stream.memcpy_stod(&[2.0f32, 0.0, 0.0, 2.0]).unwrap();
+        let cfg = StridedBatchedConfig { stride_a: 0, ..cfg };
+        unsafe { blas.gemm_strided_batched(cfg, &a_single, &b_dev, &mut c_dev) }.unwrap();
+        assert_eq!(
+            stream.memcpy_dtov(&c_dev).unwrap(),
+            [2.0, 4.0, 6.0, 8.0, 10.0, 12.0, 14.0, 16.0]
+        );
+    }
+
     #[test]
     fn test_dgemm() {
         let ctx = CudaContext::new(0).unwrap();
@@ -948,4 +1440,27 @@ mod tests {
             "We have set the mode to use device pointers"
         );
     }
+
+    #[test]
+    fn cublas_math_mode() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let blas = CudaBlas::new(stream.clone()).unwrap();
+        assert_eq!(
+            blas.get_math_mode().unwrap(),
+            sys::cublasMath_t::CUBLAS_DEFAULT_MATH
+        );
+
+        blas.set_math_mode(MathMode::TF32TensorOp).unwrap();
+        assert_eq!(
+            blas.get_math_mode().unwrap(),
+            sys::cublasMath_t::CUBLAS_TF32_TENSOR_OP_MATH
+        );
+
+        blas.set_math_mode(MathMode::PedanticMath).unwrap();
+        assert_eq!(
+            blas.get_math_mode().unwrap(),
+            sys::cublasMath_t::CUBLAS_PEDANTIC_MATH
+        );
+    }
 }
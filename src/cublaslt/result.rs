@@ -1,6 +1,6 @@
 use super::sys::{self};
 use crate::cublaslt::sys::cublasLtMatmulAlgo_t;
-use core::ffi::c_void;
+use core::ffi::{c_int, c_void};
 use core::mem::MaybeUninit;
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -211,6 +211,51 @@ pub unsafe fn get_matmul_algo_heuristic(
     Ok(matmul_heuristic)
 }
 
+/// Retrieves up to `requested` algorithms for the matrix multiply operation function
+/// given input matrices A, B and C and the output matrix D, ordered by estimated compute time. See
+/// [nvidia docs](https://docs.nvidia.com/cuda/cublas/index.html#cublasltmatmulalgogetheuristic)
+///
+/// # Safety
+/// All the parameters must not have been freed already & must be valid layouts for allocations.
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn get_matmul_algo_heuristics(
+    handle: sys::cublasLtHandle_t,
+    matmul_desc: sys::cublasLtMatmulDesc_t,
+    a_layout: sys::cublasLtMatrixLayout_t,
+    b_layout: sys::cublasLtMatrixLayout_t,
+    c_layout: sys::cublasLtMatrixLayout_t,
+    d_layout: sys::cublasLtMatrixLayout_t,
+    matmul_pref: sys::cublasLtMatmulPreference_t,
+    requested: usize,
+) -> Result<Vec<sys::cublasLtMatmulHeuristicResult_t>, CublasError> {
+    let mut heuristics = Vec::with_capacity(requested);
+    let mut algo_count = 0;
+
+    sys::cublasLtMatmulAlgoGetHeuristic(
+        handle,
+        matmul_desc,
+        a_layout,
+        b_layout,
+        c_layout,
+        d_layout,
+        matmul_pref,
+        requested.try_into().unwrap_or(c_int::MAX),
+        heuristics.as_mut_ptr(),
+        &mut algo_count,
+    )
+    .result()?;
+
+    if algo_count == 0 {
+        return Err(CublasError(
+            sys::cublasStatus_t::CUBLAS_STATUS_NOT_SUPPORTED,
+        ));
+    }
+
+    heuristics.set_len(algo_count as usize);
+    heuristics.retain(|h: &sys::cublasLtMatmulHeuristicResult_t| h.state.result().is_ok());
+    Ok(heuristics)
+}
+
 /// Computes the matrix multiplication of matrics A and B to produce the output matrix D,
 /// according to the following operation: D = alpha*(A*B) + beta*(C)
 /// where A, B, and C are input matrices, and alpha and beta are input scalars. See
@@ -1,4 +1,5 @@
 //! Safe abstractions around [crate::cublaslt::result] for doing matmul.
+#![allow(clippy::too_many_arguments)]
 
 use super::{result, result::CublasError, sys};
 use crate::cublaslt::result::set_matrix_layout_attribute;
@@ -6,7 +7,8 @@ use crate::driver::sys::{CUdevice_attribute, CUdeviceptr};
 use crate::driver::{CudaSlice, CudaStream, DevicePtr, DevicePtrMut, DriverError};
 use core::ffi::c_int;
 use core::mem;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 /// Wrapper around [sys::cublasLtHandle_t]
 ///
@@ -14,6 +16,8 @@ use std::sync::Arc;
 /// 2. Execute matmul kernel with matmul. f32 is supported. f16 and bf16 are supported
 ///    if feature `half` is activated
 ///
+/// The algorithm picked for each problem shape is cached, see [MatmulShared::algo_cache()].
+///
 /// Note: This maintains a instance of [`Arc<CudaDevice>`], so will prevent the device
 /// from being dropped. Kernels will be launched on the device device default stream.
 #[derive(Debug)]
@@ -21,6 +25,7 @@ pub struct CudaBlasLT {
     handle: sys::cublasLtHandle_t,
     workspace: Workspace,
     stream: Arc<CudaStream>,
+    algo_cache: AlgoCache,
 }
 
 unsafe impl Send for CudaBlasLT {}
@@ -35,6 +40,7 @@ impl CudaBlasLT {
             handle,
             workspace,
             stream,
+            algo_cache: AlgoCache::default(),
         })
     }
 }
@@ -78,7 +84,7 @@ impl Workspace {
 }
 
 /// Available activation for kernel fusing in matmul
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Activation {
     Relu,
     Gelu,
@@ -228,7 +234,7 @@ impl MatmulDesc {
                 self.handle,
                 sys::cublasLtMatmulDescAttributes_t::CUBLASLT_MATMUL_DESC_EPILOGUE,
                 (&epilogue) as *const _ as *const _,
-                mem::size_of::<sys::cublasLtMatmulDescAttributes_t>(),
+                mem::size_of::<sys::cublasLtEpilogue_t>(),
             )?;
         }
         Ok(())
@@ -282,6 +288,12 @@ pub trait MatmulShared {
 
     /// Returns a reference to the underlying stream
     fn stream(&self) -> &Arc<CudaStream>;
+
+    /// Returns the cache [Matmul::matmul()] uses to skip the algorithm heuristic for
+    /// previously seen problems, if any.
+    fn algo_cache(&self) -> Option<&AlgoCache> {
+        None
+    }
 }
 
 /// Configuration for [Matmul]
@@ -305,32 +317,156 @@ pub struct MatmulConfig {
     pub batch_size: Option<c_int>,
 }
 
-/// Matrix matrix multiplication with elements of type `T`.
-pub trait Matmul<T>: MatmulShared {
-    /// Underlying CUDA Type for `T`
-    fn matrix_type() -> sys::cudaDataType;
+/// A candidate algorithm returned by [Matmul::get_heuristics()].
+#[derive(Debug, Copy, Clone)]
+pub struct AlgoResult {
+    pub algo: sys::cublasLtMatmulAlgo_t,
+    /// Workspace bytes this algorithm needs, at most the size of the [Workspace].
+    pub workspace_size: usize,
+    /// Ratio of the number of blocks launched to the number the device can run at once.
+    pub waves_count: f32,
+}
 
-    /// Underlying CUDA Compute Type for `T`
-    fn compute_type() -> sys::cublasComputeType_t;
+impl From<sys::cublasLtMatmulHeuristicResult_t> for AlgoResult {
+    fn from(result: sys::cublasLtMatmulHeuristicResult_t) -> Self {
+        Self {
+            algo: result.algo,
+            workspace_size: result.workspaceSize,
+            waves_count: result.wavesCount,
+        }
+    }
+}
 
-    /// Matrix matrix multiplication. See
-    /// [nvidia docs](https://docs.nvidia.com/cuda/cublas/index.html#cublasltmatmul)
-    ///
-    /// # Safety
-    /// This is unsafe because improper arguments may lead to invalid
-    /// memory accesses.
-    unsafe fn matmul<I: DevicePtr<T>, O: DevicePtrMut<T>>(
-        &self,
-        cfg: MatmulConfig,
-        a: &I,
-        b: &I,
-        c: &mut O,
-        bias: Option<&I>,
+/// Everything about a [Matmul] call that the algorithm heuristic depends on.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct AlgoKey {
+    matrix_type: sys::cudaDataType,
+    compute_type: sys::cublasComputeType_t,
+    transa: bool,
+    transb: bool,
+    transc: bool,
+    m: u64,
+    n: u64,
+    k: u64,
+    lda: i64,
+    ldb: i64,
+    ldc: i64,
+    stride_a: Option<i64>,
+    stride_b: Option<i64>,
+    stride_c: Option<i64>,
+    stride_bias: Option<i64>,
+    batch_size: Option<c_int>,
+    bias: bool,
+    act: Option<Activation>,
+}
+
+impl AlgoKey {
+    fn new<T, M: Matmul<T> + ?Sized>(
+        cfg: &MatmulConfig,
+        bias: bool,
         act: Option<&Activation>,
-    ) -> Result<(), CublasError> {
-        let stream = self.stream();
-        let workspace = self.workspace();
+    ) -> Self {
+        Self {
+            matrix_type: M::matrix_type(),
+            compute_type: M::compute_type(),
+            transa: cfg.transa,
+            transb: cfg.transb,
+            transc: cfg.transc,
+            m: cfg.m,
+            n: cfg.n,
+            k: cfg.k,
+            lda: cfg.lda,
+            ldb: cfg.ldb,
+            ldc: cfg.ldc,
+            stride_a: cfg.stride_a,
+            stride_b: cfg.stride_b,
+            stride_c: cfg.stride_c,
+            stride_bias: cfg.stride_bias,
+            batch_size: cfg.batch_size,
+            bias,
+            act: act.cloned(),
+        }
+    }
+}
+
+/// Caches the algorithm picked by the heuristic per problem shape, so that repeated
+/// [Matmul::matmul()] calls with the same shape skip `cublasLtMatmulAlgoGetHeuristic`.
+///
+/// Holds at most [AlgoCache::capacity()] shapes ([AlgoCache::DEFAULT_CAPACITY] by default).
+/// When full, an arbitrary cached shape is evicted to make room for a new one.
+#[derive(Debug)]
+pub struct AlgoCache {
+    algos: Mutex<HashMap<AlgoKey, sys::cublasLtMatmulAlgo_t>>,
+    capacity: usize,
+}
+
+impl Default for AlgoCache {
+    fn default() -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
+    }
+}
+
+impl AlgoCache {
+    /// The number of problem shapes cached by [AlgoCache::default()].
+    pub const DEFAULT_CAPACITY: usize = 1024;
+
+    /// Creates an empty cache holding at most `capacity` problem shapes.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            algos: Default::default(),
+            capacity,
+        }
+    }
+
+    /// The maximum number of cached problem shapes.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of cached problem shapes.
+    pub fn len(&self) -> usize {
+        self.algos.lock().unwrap().len()
+    }
+
+    /// Whether no problem shape has been cached yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all cached algorithms.
+    pub fn clear(&self) {
+        self.algos.lock().unwrap().clear();
+    }
+
+    fn insert(&self, key: AlgoKey, algo: sys::cublasLtMatmulAlgo_t) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut algos = self.algos.lock().unwrap();
+        if algos.len() >= self.capacity && !algos.contains_key(&key) {
+            let evicted = algos.keys().next().cloned().unwrap();
+            algos.remove(&evicted);
+        }
+        algos.insert(key, algo);
+    }
+}
 
+/// Layouts and description of a single matmul problem.
+struct MatmulProblem {
+    a_layout: MatrixLayout,
+    b_layout: MatrixLayout,
+    c_layout: MatrixLayout,
+    matmul_desc: MatmulDesc,
+}
+
+impl MatmulProblem {
+    fn new(
+        matrix_type: sys::cudaDataType,
+        compute_type: sys::cublasComputeType_t,
+        cfg: &MatmulConfig,
+        bias: Option<&CUdeviceptr>,
+        act: Option<&Activation>,
+    ) -> Result<Self, CublasError> {
         let (a_rows, a_cols) = if cfg.transa {
             (cfg.k, cfg.m)
         } else {
@@ -343,23 +479,23 @@ pub trait Matmul<T>: MatmulShared {
         };
 
         // Creates matrix layouts
-        let a_layout = MatrixLayout::new(Self::matrix_type(), a_rows, a_cols, cfg.lda)?;
+        let a_layout = MatrixLayout::new(matrix_type, a_rows, a_cols, cfg.lda)?;
         if let (Some(batch_size), Some(stride_a)) = (cfg.batch_size, cfg.stride_a) {
             a_layout.set_batch(batch_size, stride_a)?;
         }
 
-        let b_layout = MatrixLayout::new(Self::matrix_type(), b_rows, b_cols, cfg.ldb)?;
+        let b_layout = MatrixLayout::new(matrix_type, b_rows, b_cols, cfg.ldb)?;
         if let (Some(batch_size), Some(stride_b)) = (cfg.batch_size, cfg.stride_b) {
             b_layout.set_batch(batch_size, stride_b)?;
         }
 
-        let c_layout = MatrixLayout::new(Self::matrix_type(), cfg.m, cfg.n, cfg.ldc)?;
+        let c_layout = MatrixLayout::new(matrix_type, cfg.m, cfg.n, cfg.ldc)?;
         if let (Some(batch_size), Some(stride_c)) = (cfg.batch_size, cfg.stride_c) {
             c_layout.set_batch(batch_size, stride_c)?;
         }
 
         // Matmul description
-        let matmul_desc = MatmulDesc::new(Self::compute_type(), sys::cudaDataType_t::CUDA_R_32F)?;
+        let matmul_desc = MatmulDesc::new(compute_type, sys::cudaDataType_t::CUDA_R_32F)?;
 
         // Set transa
         matmul_desc.set_transpose(cfg.transa, Matrix::A)?;
@@ -369,52 +505,224 @@ pub trait Matmul<T>: MatmulShared {
         matmul_desc.set_transpose(cfg.transc, Matrix::C)?;
 
         // Epilogue system can be leveraged to fuse add and activation operations
-        let (bias, _record_bias) = bias.map(|b| b.device_ptr(stream)).unzip();
-        matmul_desc.set_epilogue(act, bias.as_ref(), cfg.stride_bias)?;
+        matmul_desc.set_epilogue(act, bias, cfg.stride_bias)?;
 
+        Ok(Self {
+            a_layout,
+            b_layout,
+            c_layout,
+            matmul_desc,
+        })
+    }
+
+    fn heuristics(
+        &self,
+        handle: sys::cublasLtHandle_t,
+        workspace_size: usize,
+        max_results: usize,
+    ) -> Result<Vec<AlgoResult>, CublasError> {
         // Create matmul heuristic search preferences
         let matmul_pref = MatmulPref::new()?;
 
         // Set workspace size
-        matmul_pref.set_workspace_size(self.workspace().size)?;
-
-        // Get heuristic given Config, bias, act and workspace size
-        let heuristic = result::get_matmul_algo_heuristic(
-            *self.handle(),
-            matmul_desc.handle,
-            a_layout.handle,
-            b_layout.handle,
-            c_layout.handle,
-            c_layout.handle,
-            matmul_pref.handle,
+        matmul_pref.set_workspace_size(workspace_size)?;
+
+        // Get heuristics given Config, bias, act and workspace size
+        let heuristics = unsafe {
+            result::get_matmul_algo_heuristics(
+                handle,
+                self.matmul_desc.handle,
+                self.a_layout.handle,
+                self.b_layout.handle,
+                self.c_layout.handle,
+                self.c_layout.handle,
+                matmul_pref.handle,
+                max_results,
+            )
+        }?;
+        Ok(heuristics.into_iter().map(AlgoResult::from).collect())
+    }
+}
+
+/// Matrix matrix multiplication with elements of type `T`.
+pub trait Matmul<T>: MatmulShared {
+    /// Underlying CUDA Type for `T`
+    fn matrix_type() -> sys::cudaDataType;
+
+    /// Underlying CUDA Compute Type for `T`
+    fn compute_type() -> sys::cublasComputeType_t;
+
+    /// Returns up to `max_results` algorithms for the problem described by `cfg`, `bias` and `act`,
+    /// ranked from fastest to slowest by `cublasLtMatmulAlgoGetHeuristic`. Only algorithms whose
+    /// [AlgoResult::workspace_size] fits in [MatmulShared::workspace()] are returned.
+    ///
+    /// Pass one of the results to [Matmul::matmul_with_algo()] to reuse it without re-running the heuristic.
+    ///
+    /// Returns [sys::cublasStatus_t::CUBLAS_STATUS_NOT_SUPPORTED] if no algorithm is applicable.
+    ///
+    /// See [nvidia docs](https://docs.nvidia.com/cuda/cublas/index.html#cublasltmatmulalgogetheuristic)
+    fn get_heuristics<I: DevicePtr<T>>(
+        &self,
+        cfg: MatmulConfig,
+        bias: Option<&I>,
+        act: Option<&Activation>,
+        max_results: usize,
+    ) -> Result<Vec<AlgoResult>, CublasError> {
+        let (bias, _record_bias) = bias.map(|b| b.device_ptr(self.stream())).unzip();
+        let problem = MatmulProblem::new(
+            Self::matrix_type(),
+            Self::compute_type(),
+            &cfg,
+            bias.as_ref(),
+            act,
         )?;
+        problem.heuristics(*self.handle(), self.workspace().size, max_results)
+    }
+
+    /// Matrix matrix multiplication. See
+    /// [nvidia docs](https://docs.nvidia.com/cuda/cublas/index.html#cublasltmatmul)
+    ///
+    /// `bias` and `act` are fused into the matmul epilogue, computing `act(alpha * op(a) @ op(b) + beta * c + bias)`
+    /// in a single kernel. `bias` holds one value per row of `c` (`cfg.m` elements), and is repeated
+    /// every `cfg.stride_bias` elements for batched calls.
+    ///
+    /// The fastest algorithm according to [Matmul::get_heuristics()] is used. If [MatmulShared::algo_cache()]
+    /// is set, the algorithm is cached per problem shape.
+    ///
+    /// Returns [sys::cublasStatus_t::CUBLAS_STATUS_INVALID_VALUE] if `bias` is too short.
+    ///
+    /// # Safety
+    /// This is unsafe because improper arguments may lead to invalid
+    /// memory accesses.
+    unsafe fn matmul<I: DevicePtr<T>, O: DevicePtrMut<T>>(
+        &self,
+        cfg: MatmulConfig,
+        a: &I,
+        b: &I,
+        c: &mut O,
+        bias: Option<&I>,
+        act: Option<&Activation>,
+    ) -> Result<(), CublasError> {
+        let Some(cache) = self.algo_cache() else {
+            launch_matmul(self, cfg, None, a, b, c, bias, act)?;
+            return Ok(());
+        };
+        let key = AlgoKey::new::<T, Self>(&cfg, bias.is_some(), act);
+        let cached = cache.algos.lock().unwrap().get(&key).copied();
+        let algo = launch_matmul(self, cfg, cached, a, b, c, bias, act)?;
+        if cached.is_none() {
+            cache.insert(key, algo);
+        }
+        Ok(())
+    }
 
-        // Launch matmul kernel
-        let (a, _record_a) = a.device_ptr(stream);
-        let (b, _record_b) = b.device_ptr(stream);
-        let (c, _record_c) = c.device_ptr_mut(stream);
-        let (w, _record_w) = workspace.buffer.device_ptr(stream);
-        result::matmul(
-            *self.handle(),
-            matmul_desc.handle,
-            (&cfg.alpha) as *const _ as *const _,
-            (&cfg.beta) as *const _ as *const _,
-            a as *const _,
-            a_layout.handle,
-            b as *const _,
-            b_layout.handle,
-            c as *const _,
-            c_layout.handle,
-            c as *mut _,
-            c_layout.handle,
-            (&heuristic.algo) as *const _,
-            w as *mut _,
-            workspace.size,
-            stream.cu_stream() as *mut _,
-        )
+    /// Like [Matmul::matmul()], but runs `algo` instead of querying the heuristic.
+    /// `algo` should come from [Matmul::get_heuristics()] for the same `cfg`, `bias` and `act`.
+    ///
+    /// Returns [sys::cublasStatus_t::CUBLAS_STATUS_INVALID_VALUE] if `bias` is too short, or if
+    /// `algo` needs a bigger workspace than [MatmulShared::workspace()].
+    ///
+    /// # Safety
+    /// This is unsafe because improper arguments may lead to invalid
+    /// memory accesses.
+    unsafe fn matmul_with_algo<I: DevicePtr<T>, O: DevicePtrMut<T>>(
+        &self,
+        cfg: MatmulConfig,
+        algo: &AlgoResult,
+        a: &I,
+        b: &I,
+        c: &mut O,
+        bias: Option<&I>,
+        act: Option<&Activation>,
+    ) -> Result<(), CublasError> {
+        if algo.workspace_size > self.workspace().size {
+            return Err(CublasError(
+                sys::cublasStatus_t::CUBLAS_STATUS_INVALID_VALUE,
+            ));
+        }
+        launch_matmul(self, cfg, Some(algo.algo), a, b, c, bias, act)?;
+        Ok(())
     }
 }
 
+/// Launches the matmul with `algo`, or the fastest algorithm according to the heuristic if `None`.
+/// Returns the algorithm that was used.
+unsafe fn launch_matmul<T, M: Matmul<T> + ?Sized, I: DevicePtr<T>, O: DevicePtrMut<T>>(
+    blas: &M,
+    cfg: MatmulConfig,
+    algo: Option<sys::cublasLtMatmulAlgo_t>,
+    a: &I,
+    b: &I,
+    c: &mut O,
+    bias: Option<&I>,
+    act: Option<&Activation>,
+) -> Result<sys::cublasLtMatmulAlgo_t, CublasError> {
+    let stream = blas.stream();
+    let workspace = blas.workspace();
+
+    if let Some(bias) = bias {
+        let required = match (cfg.batch_size, cfg.stride_bias) {
+            (Some(batch_size), Some(stride_bias)) if batch_size > 1 => (batch_size as u64 - 1)
+                .checked_mul(stride_bias.max(0) as u64)
+                .and_then(|n| n.checked_add(cfg.m)),
+            _ => Some(cfg.m),
+        };
+        if required.map_or(true, |required| (bias.len() as u64) < required) {
+            return Err(CublasError(
+                sys::cublasStatus_t::CUBLAS_STATUS_INVALID_VALUE,
+            ));
+        }
+    }
+
+    let (bias, _record_bias) = bias.map(|b| b.device_ptr(stream)).unzip();
+    let problem = MatmulProblem::new(
+        M::matrix_type(),
+        M::compute_type(),
+        &cfg,
+        bias.as_ref(),
+        act,
+    )?;
+
+    let algo = match algo {
+        Some(algo) => algo,
+        None => {
+            problem
+                .heuristics(*blas.handle(), workspace.size, 1)?
+                .into_iter()
+                .next()
+                .ok_or(CublasError(
+                    sys::cublasStatus_t::CUBLAS_STATUS_NOT_SUPPORTED,
+                ))?
+                .algo
+        }
+    };
+
+    // Launch matmul kernel
+    let (a, _record_a) = a.device_ptr(stream);
+    let (b, _record_b) = b.device_ptr(stream);
+    let (c, _record_c) = c.device_ptr_mut(stream);
+    let (w, _record_w) = workspace.buffer.device_ptr(stream);
+    result::matmul(
+        *blas.handle(),
+        problem.matmul_desc.handle,
+        (&cfg.alpha) as *const _ as *const _,
+        (&cfg.beta) as *const _ as *const _,
+        a as *const _,
+        problem.a_layout.handle,
+        b as *const _,
+        problem.b_layout.handle,
+        c as *const _,
+        problem.c_layout.handle,
+        c as *mut _,
+        problem.c_layout.handle,
+        (&algo) as *const _,
+        w as *mut _,
+        workspace.size,
+        stream.cu_stream() as *mut _,
+    )?;
+    Ok(algo)
+}
+
 impl MatmulShared for CudaBlasLT {
     fn handle(&self) -> &sys::cublasLtHandle_t {
         &self.handle
@@ -427,6 +735,10 @@ impl MatmulShared for CudaBlasLT {
     fn stream(&self) -> &Arc<CudaStream> {
         &self.stream
     }
+
+    fn algo_cache(&self) -> Option<&AlgoCache> {
+        Some(&self.algo_cache)
+    }
 }
 
 impl Matmul<f32> for CudaBlasLT {
@@ -471,6 +783,36 @@ mod tests {
     use super::*;
     use std::ffi::CString;
 
+    #[test]
+    fn test_algo_cache_capacity() {
+        let cache = AlgoCache::with_capacity(2);
+        assert_eq!(cache.capacity(), 2);
+        assert_eq!(AlgoCache::default().capacity(), AlgoCache::DEFAULT_CAPACITY);
+        let algo: sys::cublasLtMatmulAlgo_t = unsafe { std::mem::zeroed() };
+        for m in 1..=5 {
+            let cfg = MatmulConfig {
+                transa: false,
+                transb: false,
+                transc: false,
+                m,
+                n: 1,
+                k: 1,
+                alpha: 1.0,
+                lda: 1,
+                ldb: 1,
+                beta: 0.0,
+                ldc: 1,
+                stride_a: None,
+                stride_b: None,
+                stride_c: None,
+                stride_bias: None,
+                batch_size: None,
+            };
+            cache.insert(AlgoKey::new::<f32, CudaBlasLT>(&cfg, false, None), algo);
+            assert_eq!(cache.len(), (m as usize).min(2));
+        }
+    }
+
     fn matmul_truth<T, const M: usize, const N: usize, const K: usize>(
         alpha: T,
         a: &[[T; K]; M],
@@ -583,6 +925,193 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_matmul_bias_relu_f32() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let blas = CudaBlasLT::new(stream.clone()).unwrap();
+        const M: usize = 2;
+        const K: usize = 3;
+        const N: usize = 4;
+        let a: [[f32; K]; M] = [[1.0, -2.0, 0.5], [-1.0, 0.25, 2.0]];
+        let b: [[f32; N]; K] = [
+            [1.0, 0.0, -1.0, 2.0],
+            [0.5, 1.0, 0.0, -1.0],
+            [-2.0, 1.0, 1.0, 0.0],
+        ];
+        let bias: [f32; N] = [0.5, -0.5, 1.0, -4.0];
+        let mut c: [[f32; N]; M] = [[0.0; N]; M];
+        matmul_truth(1.0, &a, &b, 0.0, &mut c);
+        for m in 0..M {
+            for n in 0..N {
+                c[m][n] = (c[m][n] + bias[n]).max(0.0);
+            }
+        }
+
+        let a_dev = stream.memcpy_stod(&a.concat()).unwrap();
+        let b_dev = stream.memcpy_stod(&b.concat()).unwrap();
+        let bias_dev = stream.memcpy_stod(&bias).unwrap();
+        let mut c_dev = stream.alloc_zeros::<f32>(M * N).unwrap();
+        let cfg = MatmulConfig {
+            transa: false,
+            transb: false,
+            transc: false,
+            m: N as u64,
+            n: M as u64,
+            k: K as u64,
+            alpha: 1.0,
+            lda: N as i64,
+            ldb: K as i64,
+            beta: 0.0,
+            ldc: N as i64,
+            stride_a: None,
+            stride_b: None,
+            stride_c: None,
+            stride_bias: None,
+            batch_size: None,
+        };
+        unsafe {
+            blas.matmul(
+                cfg,
+                &b_dev,
+                &a_dev,
+                &mut c_dev,
+                Some(&bias_dev),
+                Some(&Activation::Relu),
+            )
+        }
+        .unwrap();
+
+        let c_host = stream.memcpy_dtov(&c_dev).unwrap();
+        for m in 0..M {
+            for n in 0..N {
+                let found = c_host[m * N + n];
+                let expected = c[m][n];
+                assert!(
+                    (found - expected).abs() <= 1e-3,
+                    "found={found:?}, expected={expected:?}"
+                );
+            }
+        }
+
+        let short_bias = stream.alloc_zeros::<f32>(N - 1).unwrap();
+        assert_eq!(
+            unsafe {
+                blas.matmul(
+                    cfg,
+                    &b_dev,
+                    &a_dev,
+                    &mut c_dev,
+                    Some(&short_bias),
+                    Some(&Activation::Relu),
+                )
+            },
+            Err(CublasError(
+                sys::cublasStatus_t::CUBLAS_STATUS_INVALID_VALUE
+            ))
+        );
+
+        // a huge bias stride must not wrap around the length check
+        let overflow_cfg = MatmulConfig {
+            batch_size: Some(4),
+            stride_bias: Some(i64::MAX),
+            ..cfg
+        };
+        let bias = stream.alloc_zeros::<f32>(N).unwrap();
+        assert_eq!(
+            unsafe {
+                blas.matmul(
+                    overflow_cfg,
+                    &b_dev,
+                    &a_dev,
+                    &mut c_dev,
+                    Some(&bias),
+                    Some(&Activation::Relu),
+                )
+            },
+            Err(CublasError(
+                sys::cublasStatus_t::CUBLAS_STATUS_INVALID_VALUE
+            ))
+        );
+    }
+
+    #[test]
+    fn test_matmul_heuristics_and_cache() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let blas = CudaBlasLT::new(stream.clone()).unwrap();
+        const M: usize = 2;
+        const K: usize = 3;
+        const N: usize = 4;
+        let a: [[f32; K]; M] = [[1.0, -2.0, 0.5], [-1.0, 0.25, 2.0]];
+        let b: [[f32; N]; K] = [
+            [1.0, 0.0, -1.0, 2.0],
+            [0.5, 1.0, 0.0, -1.0],
+            [-2.0, 1.0, 1.0, 0.0],
+        ];
+        let mut c: [[f32; N]; M] = [[0.0; N]; M];
+        matmul_truth(1.0, &a, &b, 0.0, &mut c);
+
+        let a_dev = stream.memcpy_stod(&a.concat()).unwrap();
+        let b_dev = stream.memcpy_stod(&b.concat()).unwrap();
+        let mut c_dev = stream.alloc_zeros::<f32>(M * N).unwrap();
+        let cfg = MatmulConfig {
+            transa: false,
+            transb: false,
+            transc: false,
+            m: N as u64,
+            n: M as u64,
+            k: K as u64,
+            alpha: 1.0,
+            lda: N as i64,
+            ldb: K as i64,
+            beta: 0.0,
+            ldc: N as i64,
+            stride_a: None,
+            stride_b: None,
+            stride_c: None,
+            stride_bias: None,
+            batch_size: None,
+        };
+
+        let algos = blas
+            .get_heuristics(cfg, None::<&CudaSlice<f32>>, None, 4)
+            .unwrap();
+        assert!(!algos.is_empty() && algos.len() <= 4);
+        for algo in algos.iter() {
+            assert!(algo.workspace_size <= blas.workspace().size);
+            unsafe { blas.matmul_with_algo(cfg, algo, &b_dev, &a_dev, &mut c_dev, None, None) }
+                .unwrap();
+            let c_host = stream.memcpy_dtov(&c_dev).unwrap();
+            for m in 0..M {
+                for n in 0..N {
+                    assert!((c_host[m * N + n] - c[m][n]).abs() <= 1e-3);
+                }
+            }
+        }
+
+        let cache = blas.algo_cache().unwrap();
+        assert!(cache.is_empty());
+        for _ in 0..2 {
+            unsafe { blas.matmul(cfg, &b_dev, &a_dev, &mut c_dev, None, None) }.unwrap();
+        }
+        assert_eq!(cache.len(), 1);
+        unsafe {
+            blas.matmul(
+                cfg,
+                &b_dev,
+                &a_dev,
+                &mut c_dev,
+                None,
+                Some(&Activation::Relu),
+            )
+        }
+        .unwrap();
+        assert_eq!(cache.len(), 2);
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
     #[cfg(feature = "f16")]
     #[test]
     fn test_matmul_half() {
@@ -668,6 +1197,7 @@ mod tests {
                 MatmulConfig {
                     transa: false,
                     transb: false,
+                    transc: false,
                     m: N as u64,
                     n: M as u64,
                     k: K as u64,
@@ -722,6 +1252,7 @@ mod tests {
                 MatmulConfig {
                     transa: false,
                     transb: false,
+                    transc: false,
                     m: N as u64,
                     n: M as u64,
                     k: K as u64,
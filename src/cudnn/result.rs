@@ -361,6 +361,50 @@ pub unsafe fn get_convolution_forward_algorithm(
     .result()
 }
 
+/// Returns the maximum number of forward algorithms that can be requested from
+/// [get_convolution_forward_algorithm()] or [find_convolution_forward_algorithm()].
+/// See [nvidia docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnGetConvolutionForwardAlgorithmMaxCount)
+///
+/// # Safety
+/// `handle` must still be allocated.
+pub unsafe fn get_convolution_forward_algorithm_max_count(
+    handle: sys::cudnnHandle_t,
+) -> Result<std::ffi::c_int, CudnnError> {
+    let mut count = 0;
+    sys::cudnnGetConvolutionForwardAlgorithmMaxCount(handle, &mut count).result()?;
+    Ok(count)
+}
+
+/// Benchmarks the forward algorithms by actually running them, and writes the results sorted
+/// by execution time. See [nvidia docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnFindConvolutionForwardAlgorithm)
+///
+/// # Safety
+/// - All handles & descriptors must still be allocated.
+/// - The pointers must point to valid memory.
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn find_convolution_forward_algorithm(
+    handle: sys::cudnnHandle_t,
+    src: sys::cudnnTensorDescriptor_t,
+    filter: sys::cudnnFilterDescriptor_t,
+    conv: sys::cudnnConvolutionDescriptor_t,
+    dest: sys::cudnnTensorDescriptor_t,
+    requested_algo_count: std::ffi::c_int,
+    returned_algo_count: *mut std::ffi::c_int,
+    perf_results: *mut sys::cudnnConvolutionFwdAlgoPerf_t,
+) -> Result<(), CudnnError> {
+    sys::cudnnFindConvolutionForwardAlgorithm(
+        handle,
+        src,
+        filter,
+        conv,
+        dest,
+        requested_algo_count,
+        returned_algo_count,
+        perf_results,
+    )
+    .result()
+}
+
 /// Returns size in **bytes**. See [nvidia docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnGetConvolutionForwardWorkspaceSize)
 /// # Safety
 /// - All handles & descriptors must still be allocated.
@@ -864,6 +908,16 @@ pub unsafe fn set_activation_descriptor(
     sys::cudnnSetActivationDescriptor(desc, mode, nan_propagation, coef).result()
 }
 
+/// Destroys an activation descriptor. See [nvidia docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnDestroyActivationDescriptor)
+///
+/// # Safety
+/// `desc` must NOT have been freed already.
+pub unsafe fn destroy_activation_descriptor(
+    desc: sys::cudnnActivationDescriptor_t,
+) -> Result<(), CudnnError> {
+    sys::cudnnDestroyActivationDescriptor(desc).result()
+}
+
 /// # Safety
 /// Make sure the handle is valid, all data are associated with the handle, and no pointers are null
 /// unless explicitly accepted by the underlying apis.
@@ -880,3 +934,94 @@ pub unsafe fn activation_forward(
 ) -> Result<(), CudnnError> {
     sys::cudnnActivationForward(handle, activation_desc, alpha, x_desc, x, beta, y_desc, y).result()
 }
+
+/// Computes the gradient of an activation. See [nvidia docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnActivationBackward)
+///
+/// # Safety
+/// Make sure the handle is valid, all data are associated with the handle, and no pointers are null
+/// unless explicitly accepted by the underlying apis.
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn activation_backward(
+    handle: sys::cudnnHandle_t,
+    activation_desc: sys::cudnnActivationDescriptor_t,
+    alpha: *const ::core::ffi::c_void,
+    y_desc: sys::cudnnTensorDescriptor_t,
+    y: *const ::core::ffi::c_void,
+    dy_desc: sys::cudnnTensorDescriptor_t,
+    dy: *const ::core::ffi::c_void,
+    x_desc: sys::cudnnTensorDescriptor_t,
+    x: *const ::core::ffi::c_void,
+    beta: *const ::core::ffi::c_void,
+    dx_desc: sys::cudnnTensorDescriptor_t,
+    dx: *mut ::core::ffi::c_void,
+) -> Result<(), CudnnError> {
+    sys::cudnnActivationBackward(
+        handle,
+        activation_desc,
+        alpha,
+        y_desc,
+        y,
+        dy_desc,
+        dy,
+        x_desc,
+        x,
+        beta,
+        dx_desc,
+        dx,
+    )
+    .result()
+}
+
+/// Derives the descriptor of the scale, bias, mean and variance tensors of a batch normalization
+/// from the descriptor of its input. See [nvidia docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnDeriveBNTensorDescriptor)
+///
+/// # Safety
+/// `derived_bn_desc` and `x_desc` must be valid tensor descriptors.
+pub unsafe fn derive_bn_tensor_descriptor(
+    derived_bn_desc: sys::cudnnTensorDescriptor_t,
+    x_desc: sys::cudnnTensorDescriptor_t,
+    mode: sys::cudnnBatchNormMode_t,
+) -> Result<(), CudnnError> {
+    sys::cudnnDeriveBNTensorDescriptor(derived_bn_desc, x_desc, mode).result()
+}
+
+/// Batch normalization using the running statistics. See [nvidia docs](https://docs.nvidia.com/deeplearning/cudnn/api/index.html#cudnnBatchNormalizationForwardInference)
+///
+/// # Safety
+/// Make sure the handle is valid, all data are associated with the handle, and no pointers are null
+/// unless explicitly accepted by the underlying apis.
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn batch_normalization_forward_inference(
+    handle: sys::cudnnHandle_t,
+    mode: sys::cudnnBatchNormMode_t,
+    alpha: *const ::core::ffi::c_void,
+    beta: *const ::core::ffi::c_void,
+    x_desc: sys::cudnnTensorDescriptor_t,
+    x: *const ::core::ffi::c_void,
+    y_desc: sys::cudnnTensorDescriptor_t,
+    y: *mut ::core::ffi::c_void,
+    bn_scale_bias_mean_var_desc: sys::cudnnTensorDescriptor_t,
+    bn_scale: *const ::core::ffi::c_void,
+    bn_bias: *const ::core::ffi::c_void,
+    estimated_mean: *const ::core::ffi::c_void,
+    estimated_variance: *const ::core::ffi::c_void,
+    epsilon: f64,
+) -> Result<(), CudnnError> {
+    sys::cudnnBatchNormalizationForwardInference(
+        handle,
+        mode,
+        alpha,
+        beta,
+        x_desc,
+        x,
+        y_desc,
+        y,
+        bn_scale_bias_mean_var_desc,
+        bn_scale,
+        bn_bias,
+        estimated_mean,
+        estimated_variance,
+        epsilon,
+    )
+    .result()
+}
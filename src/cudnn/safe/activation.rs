@@ -12,10 +12,39 @@ pub struct ActivationDescriptor<T> {
     pub(crate) marker: PhantomData<T>,
 }
 
+/// The activation function applied by an [`ActivationDescriptor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ActivationMode {
+    /// `max(0, x)`
+    Relu,
+    /// `1 / (1 + exp(-x))`
+    Sigmoid,
+    /// `tanh(x)`
+    Tanh,
+    /// `x` if `x > 0`, otherwise `coef * (exp(x) - 1)`
+    Elu,
+}
+
+impl From<ActivationMode> for sys::cudnnActivationMode_t {
+    fn from(mode: ActivationMode) -> Self {
+        match mode {
+            ActivationMode::Relu => sys::cudnnActivationMode_t::CUDNN_ACTIVATION_RELU,
+            ActivationMode::Sigmoid => sys::cudnnActivationMode_t::CUDNN_ACTIVATION_SIGMOID,
+            ActivationMode::Tanh => sys::cudnnActivationMode_t::CUDNN_ACTIVATION_TANH,
+            ActivationMode::Elu => sys::cudnnActivationMode_t::CUDNN_ACTIVATION_ELU,
+        }
+    }
+}
+
 impl Cudnn {
+    /// Create an activation descriptor. `mode` is either an [`ActivationMode`] or
+    /// a raw [`sys::cudnnActivationMode_t`].
+    ///
+    /// `coef` is the `alpha` of [`ActivationMode::Elu`] and the ceiling of clipped relu;
+    /// it is ignored by the other modes.
     pub fn create_activation<T: CudnnDataType>(
         self: &Arc<Cudnn>,
-        mode: sys::cudnnActivationMode_t,
+        mode: impl Into<sys::cudnnActivationMode_t>,
         nan_propagation: sys::cudnnNanPropagation_t,
         coef: f64,
     ) -> Result<ActivationDescriptor<T>, CudnnError> {
@@ -25,13 +54,24 @@ impl Cudnn {
             handle: self.clone(),
             marker: PhantomData,
         };
-        unsafe { result::set_activation_descriptor(desc.desc, mode, nan_propagation, coef) }?;
+        unsafe {
+            result::set_activation_descriptor(desc.desc, mode.into(), nan_propagation, coef)
+        }?;
         Ok(desc)
     }
 }
 
+impl<T> Drop for ActivationDescriptor<T> {
+    fn drop(&mut self) {
+        let desc = std::mem::replace(&mut self.desc, std::ptr::null_mut());
+        if !desc.is_null() {
+            unsafe { result::destroy_activation_descriptor(desc) }.unwrap()
+        }
+    }
+}
+
 /// The activation forward operation. Pass in references to descriptors
-/// directly, and then call [`ConvForward::launch()`] .
+/// directly, and then call [`ActivationForward::launch()`].
 pub struct ActivationForward<'a, A: CudnnDataType, X: CudnnDataType, Y: CudnnDataType> {
     /// Activation function.
     pub act: &'a ActivationDescriptor<A>,
@@ -60,8 +100,8 @@ where
         y: &mut Dst,
     ) -> Result<(), CudnnError>
     where
-        Src: DevicePtr<A>,
-        Dst: DevicePtrMut<A>,
+        Src: DevicePtr<X>,
+        Dst: DevicePtrMut<Y>,
     {
         let stream = &self.act.handle.stream;
         let alpha = alpha.into_scaling_parameter();
@@ -80,3 +120,71 @@ where
         )
     }
 }
+
+/// The activation backward operation. Pass in references to descriptors
+/// directly, and then call [`ActivationBackward::launch()`].
+pub struct ActivationBackward<'a, A: CudnnDataType, T: CudnnDataType> {
+    /// Activation function.
+    pub act: &'a ActivationDescriptor<A>,
+    /// Output of the forward pass.
+    pub y: &'a TensorDescriptor<T>,
+    /// Gradient with respect to `y`.
+    pub dy: &'a TensorDescriptor<T>,
+    /// Input of the forward pass.
+    pub x: &'a TensorDescriptor<T>,
+    /// Gradient with respect to `x`.
+    pub dx: &'a TensorDescriptor<T>,
+}
+
+impl<A, T> ActivationBackward<'_, A, T>
+where
+    A: CudnnDataType,
+    T: CudnnDataType,
+{
+    /// Launches the operation.
+    ///
+    /// - `y` is the output of the forward pass
+    /// - `dy` is the gradient of the output
+    /// - `x` is the input of the forward pass
+    /// - `dx` is the gradient of the input
+    ///
+    /// # Safety
+    /// The arguments must match the data type/layout specified in the
+    /// descriptors in `self`.
+    pub unsafe fn launch<Y, Dy, X, Dx>(
+        &self,
+        (alpha, beta): (T, T),
+        y: &Y,
+        dy: &Dy,
+        x: &X,
+        dx: &mut Dx,
+    ) -> Result<(), CudnnError>
+    where
+        Y: DevicePtr<T>,
+        Dy: DevicePtr<T>,
+        X: DevicePtr<T>,
+        Dx: DevicePtrMut<T>,
+    {
+        let stream = &self.act.handle.stream;
+        let alpha = alpha.into_scaling_parameter();
+        let beta = beta.into_scaling_parameter();
+        let (y, _record_y) = y.device_ptr(stream);
+        let (dy, _record_dy) = dy.device_ptr(stream);
+        let (x, _record_x) = x.device_ptr(stream);
+        let (dx, _record_dx) = dx.device_ptr_mut(stream);
+        result::activation_backward(
+            self.act.handle.handle,
+            self.act.desc,
+            (&alpha) as *const T::Scalar as *const std::ffi::c_void,
+            self.y.desc,
+            y as *const T as *const std::ffi::c_void,
+            self.dy.desc,
+            dy as *const T as *const std::ffi::c_void,
+            self.x.desc,
+            x as *const T as *const std::ffi::c_void,
+            (&beta) as *const T::Scalar as *const std::ffi::c_void,
+            self.dx.desc,
+            dx as *mut T as *mut std::ffi::c_void,
+        )
+    }
+}
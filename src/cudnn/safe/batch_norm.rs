@@ -0,0 +1,132 @@
+use crate::cudnn::{result, sys, Cudnn, CudnnDataType, CudnnError, TensorDescriptor};
+use crate::driver::{DevicePtr, DevicePtrMut};
+use core::marker::PhantomData;
+use std::sync::Arc;
+
+/// How the statistics of a batch normalization are shared across the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BatchNormMode {
+    /// One set of parameters per `C, H, W` element, i.e. parameters have shape `1xCxHxW`.
+    /// Typically used after fully connected layers.
+    PerActivation,
+    /// One set of parameters per channel, i.e. parameters have shape `1xCx1x1`.
+    /// Typically used after convolutions.
+    Spatial,
+}
+
+impl From<BatchNormMode> for sys::cudnnBatchNormMode_t {
+    fn from(mode: BatchNormMode) -> Self {
+        match mode {
+            BatchNormMode::PerActivation => {
+                sys::cudnnBatchNormMode_t::CUDNN_BATCHNORM_PER_ACTIVATION
+            }
+            BatchNormMode::Spatial => sys::cudnnBatchNormMode_t::CUDNN_BATCHNORM_SPATIAL,
+        }
+    }
+}
+
+/// A descriptor of the scale, bias, mean and variance tensors of a batch normalization,
+/// derived from the input tensor descriptor. Create with [`Cudnn::create_batch_norm()`].
+///
+/// `P` is the data type of the parameters. This is `f32` for `f16`/`bf16`/`f32` inputs, and
+/// `f64` for `f64` inputs.
+#[derive(Debug)]
+pub struct BatchNormDescriptor<P> {
+    pub(crate) desc: TensorDescriptor<P>,
+    pub(crate) mode: BatchNormMode,
+}
+
+impl Cudnn {
+    /// Derives the batch norm parameter descriptor from the descriptor of the input `x`.
+    pub fn create_batch_norm<P: CudnnDataType, T: CudnnDataType>(
+        self: &Arc<Cudnn>,
+        x: &TensorDescriptor<T>,
+        mode: BatchNormMode,
+    ) -> Result<BatchNormDescriptor<P>, CudnnError> {
+        let desc = result::create_tensor_descriptor()?;
+        let desc = TensorDescriptor {
+            desc,
+            handle: self.clone(),
+            marker: PhantomData,
+        };
+        unsafe { result::derive_bn_tensor_descriptor(desc.desc, x.desc, mode.into()) }?;
+        Ok(BatchNormDescriptor { desc, mode })
+    }
+}
+
+impl<P> BatchNormDescriptor<P> {
+    /// The [BatchNormMode] this descriptor was derived with.
+    pub fn mode(&self) -> BatchNormMode {
+        self.mode
+    }
+}
+
+/// The batch normalization inference operation, using running (estimated) statistics.
+/// Pass in references to descriptors directly, and then call [`BatchNormInference::launch()`].
+pub struct BatchNormInference<'a, P: CudnnDataType, X: CudnnDataType, Y: CudnnDataType> {
+    pub bn: &'a BatchNormDescriptor<P>,
+    pub x: &'a TensorDescriptor<X>,
+    pub y: &'a TensorDescriptor<Y>,
+}
+
+impl<P, X, Y> BatchNormInference<'_, P, X, Y>
+where
+    P: CudnnDataType,
+    X: CudnnDataType,
+    Y: CudnnDataType,
+{
+    /// Launches the operation, computing
+    /// `y = alpha * (scale * (x - mean) / sqrt(variance + epsilon) + bias) + beta * y`.
+    ///
+    /// - `x` is the input tensor
+    /// - `y` is the output
+    /// - `scale`, `bias`, `mean` and `variance` are the parameters described by `self.bn`
+    /// - `epsilon` must be at least `1e-5`
+    ///
+    /// # Safety
+    /// The arguments must match the data type/layout specified in the
+    /// descriptors in `self`.
+    #[allow(clippy::too_many_arguments)]
+    pub unsafe fn launch<Src, Dst, Param>(
+        &self,
+        (alpha, beta): (Y, Y),
+        x: &Src,
+        y: &mut Dst,
+        scale: &Param,
+        bias: &Param,
+        mean: &Param,
+        variance: &Param,
+        epsilon: f64,
+    ) -> Result<(), CudnnError>
+    where
+        Src: DevicePtr<X>,
+        Dst: DevicePtrMut<Y>,
+        Param: DevicePtr<P>,
+    {
+        let stream = &self.x.handle.stream;
+        let alpha = alpha.into_scaling_parameter();
+        let beta = beta.into_scaling_parameter();
+        let (x, _record_x) = x.device_ptr(stream);
+        let (y, _record_y) = y.device_ptr_mut(stream);
+        let (scale, _record_scale) = scale.device_ptr(stream);
+        let (bias, _record_bias) = bias.device_ptr(stream);
+        let (mean, _record_mean) = mean.device_ptr(stream);
+        let (variance, _record_variance) = variance.device_ptr(stream);
+        result::batch_normalization_forward_inference(
+            self.x.handle.handle,
+            self.bn.mode.into(),
+            (&alpha) as *const Y::Scalar as *const std::ffi::c_void,
+            (&beta) as *const Y::Scalar as *const std::ffi::c_void,
+            self.x.desc,
+            x as *const X as *const std::ffi::c_void,
+            self.y.desc,
+            y as *mut Y as *mut std::ffi::c_void,
+            self.bn.desc.desc,
+            scale as *const P as *const std::ffi::c_void,
+            bias as *const P as *const std::ffi::c_void,
+            mean as *const P as *const std::ffi::c_void,
+            variance as *const P as *const std::ffi::c_void,
+            epsilon,
+        )
+    }
+}
@@ -191,7 +191,8 @@ impl<T> Drop for ConvDescriptor<T> {
 
 /// The convolution 2d forward operation. Pass in references to descriptors
 /// directly, and then call:
-/// 1. [`ConvForward::pick_algorithm()`] to use cudnn heuristics to select the algorithm
+/// 1. [`ConvForward::pick_algorithm()`] to use cudnn heuristics to select the algorithm,
+///    or [`ConvForward::find_algorithms()`] to benchmark them
 /// 2. [`ConvForward::get_workspace_size()`] to get required workspace size.
 /// 3. [`ConvForward::launch()`] to execute it
 #[derive(Debug)]
@@ -235,6 +236,37 @@ impl<X: CudnnDataType, C: CudnnDataType, Y: CudnnDataType> ConvForward<'_, X, C,
         Ok(perf_results[0].algo)
     }
 
+    /// Benchmarks all available algorithms by running them, and returns the ones that succeeded
+    /// ranked from fastest to slowest. Each result contains the algorithm in `algo`, its time in
+    /// milliseconds in `time`, and the workspace size in bytes it needs in `memory`.
+    ///
+    /// Unlike [`ConvForward::pick_algorithm()`], this launches work on the stream and
+    /// allocates temporary device memory, so it is much slower.
+    pub fn find_algorithms(&self) -> Result<Vec<sys::cudnnConvolutionFwdAlgoPerf_t>, CudnnError> {
+        let max_count = unsafe {
+            result::get_convolution_forward_algorithm_max_count(self.conv.handle.handle)
+        }?;
+        let mut returned_count = [0; 1];
+        let mut perf_results = vec![Default::default(); max_count as usize];
+        unsafe {
+            result::find_convolution_forward_algorithm(
+                self.conv.handle.handle,
+                self.x.desc,
+                self.w.desc,
+                self.conv.desc,
+                self.y.desc,
+                max_count,
+                returned_count.as_mut_ptr(),
+                perf_results.as_mut_ptr(),
+            )
+        }?;
+        Ok(perf_results[..returned_count[0] as usize]
+            .iter()
+            .filter(|perf| perf.status == sys::cudnnStatus_t::CUDNN_STATUS_SUCCESS)
+            .copied()
+            .collect())
+    }
+
     /// Returns size in **bytes** to execute the selected algorithm.
     pub fn get_workspace_size(
         &self,
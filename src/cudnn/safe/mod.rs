@@ -17,11 +17,13 @@
 //! # Reductions
 
 mod activation;
+mod batch_norm;
 mod conv;
 mod core;
 mod pooling;
 mod reduce;
 
+pub use self::batch_norm::{BatchNormDescriptor, BatchNormInference, BatchNormMode};
 #[allow(deprecated)]
 pub use self::conv::{
     // Deprecated APIs
@@ -41,7 +43,7 @@ pub use self::core::{Cudnn, CudnnDataType, TensorDescriptor};
 pub use self::pooling::{PoolingDescriptor, PoolingForward};
 pub use self::reduce::{FlatIndices, NoIndices, ReduceTensor, ReductionDescriptor};
 pub use super::result::CudnnError;
-pub use activation::{ActivationDescriptor, ActivationForward};
+pub use activation::{ActivationBackward, ActivationDescriptor, ActivationForward, ActivationMode};
 
 #[cfg(test)]
 mod tests {
@@ -142,6 +144,68 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_conv2d_find_algorithms() -> Result<(), CudnnError> {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let cudnn = Cudnn::new(stream.clone())?;
+
+        let conv = cudnn.create_conv2d::<f32>(
+            [0; 2],
+            [1; 2],
+            [1; 2],
+            cudnn::sys::cudnnConvolutionMode_t::CUDNN_CROSS_CORRELATION,
+        )?;
+        let x_desc = cudnn.create_4d_tensor::<f32>(
+            cudnn::sys::cudnnTensorFormat_t::CUDNN_TENSOR_NCHW,
+            [1, 1, 4, 4],
+        )?;
+        let filter_desc = cudnn.create_4d_filter::<f32>(
+            cudnn::sys::cudnnTensorFormat_t::CUDNN_TENSOR_NCHW,
+            [1, 1, 3, 3],
+        )?;
+        let y_desc = cudnn.create_4d_tensor::<f32>(
+            cudnn::sys::cudnnTensorFormat_t::CUDNN_TENSOR_NCHW,
+            [1, 1, 2, 2],
+        )?;
+        let op = ConvForward {
+            conv: &conv,
+            x: &x_desc,
+            w: &filter_desc,
+            y: &y_desc,
+        };
+
+        let perfs = op.find_algorithms()?;
+        assert!(!perfs.is_empty());
+        for pair in perfs.windows(2) {
+            assert!(pair[0].time <= pair[1].time);
+        }
+
+        let x = stream.memcpy_stod(&[1.0f32; 16]).unwrap();
+        let filter = stream.memcpy_stod(&[1.0f32; 9]).unwrap();
+        for perf in perfs {
+            let workspace_size = op.get_workspace_size(perf.algo)?.max(perf.memory);
+            let mut workspace = stream.alloc_zeros::<u8>(workspace_size.max(1)).unwrap();
+            let mut y = stream.alloc_zeros::<f32>(4).unwrap();
+            unsafe {
+                op.launch(
+                    perf.algo,
+                    Some(&mut workspace),
+                    (1.0, 0.0),
+                    &x,
+                    &filter,
+                    &mut y,
+                )
+            }?;
+            let y_host = stream.memcpy_dtov(&y).unwrap();
+            for v in y_host {
+                assert!((v - 9.0).abs() < 1e-3, "{:?}: {v}", perf.algo);
+            }
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_conv1d() -> Result<(), CudnnError> {
         let ctx = CudaContext::new(0).unwrap();
@@ -477,4 +541,106 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_relu_forward_backward() -> Result<(), CudnnError> {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let cudnn = Cudnn::new(stream.clone())?;
+
+        let act = cudnn.create_activation::<f32>(
+            ActivationMode::Relu,
+            cudnn::sys::cudnnNanPropagation_t::CUDNN_NOT_PROPAGATE_NAN,
+            0.0,
+        )?;
+
+        let x_host: Vec<f32> = (0..2 * 3 * 4 * 5)
+            .map(|i| (i as f32 - 59.5) * 0.25)
+            .collect();
+        let dy_host: Vec<f32> = (0..x_host.len()).map(|i| i as f32 * 0.5).collect();
+        let desc = cudnn.create_4d_tensor::<f32>(
+            cudnn::sys::cudnnTensorFormat_t::CUDNN_TENSOR_NCHW,
+            [2, 3, 4, 5],
+        )?;
+
+        let x = stream.memcpy_stod(&x_host).unwrap();
+        let dy = stream.memcpy_stod(&dy_host).unwrap();
+        let mut y = stream.alloc_zeros::<f32>(x_host.len()).unwrap();
+        let mut dx = stream.alloc_zeros::<f32>(x_host.len()).unwrap();
+
+        let fwd = ActivationForward {
+            act: &act,
+            x: &desc,
+            y: &desc,
+        };
+        unsafe { fwd.launch((1.0, 0.0), &x, &mut y) }?;
+
+        let bwd = ActivationBackward {
+            act: &act,
+            y: &desc,
+            dy: &desc,
+            x: &desc,
+            dx: &desc,
+        };
+        unsafe { bwd.launch((1.0, 0.0), &y, &dy, &x, &mut dx) }?;
+
+        let y_host = stream.memcpy_dtov(&y).unwrap();
+        let dx_host = stream.memcpy_dtov(&dx).unwrap();
+        for i in 0..x_host.len() {
+            assert_eq!(y_host[i], x_host[i].max(0.0));
+            let grad = if x_host[i] > 0.0 { dy_host[i] } else { 0.0 };
+            assert_eq!(dx_host[i], grad);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_batch_norm_inference() -> Result<(), CudnnError> {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let cudnn = Cudnn::new(stream.clone())?;
+
+        const N: usize = 2;
+        const C: usize = 3;
+        const HW: usize = 4;
+        let eps = 1e-5;
+
+        let x_host: Vec<f32> = (0..N * C * HW).map(|i| (i as f32 * 0.7).sin()).collect();
+        let scale_host = [0.5f32, 1.0, 2.0];
+        let bias_host = [0.1f32, -0.2, 0.3];
+        let mean_host = [0.0f32, 0.25, -0.5];
+        let var_host = [1.0f32, 0.5, 2.0];
+
+        let x_desc = cudnn.create_4d_tensor::<f32>(
+            cudnn::sys::cudnnTensorFormat_t::CUDNN_TENSOR_NCHW,
+            [N as i32, C as i32, 2, 2],
+        )?;
+        let bn = cudnn.create_batch_norm::<f32, f32>(&x_desc, BatchNormMode::Spatial)?;
+        assert_eq!(bn.mode(), BatchNormMode::Spatial);
+
+        let x = stream.memcpy_stod(&x_host).unwrap();
+        let mut y = stream.alloc_zeros::<f32>(x_host.len()).unwrap();
+        let scale = stream.memcpy_stod(&scale_host).unwrap();
+        let bias = stream.memcpy_stod(&bias_host).unwrap();
+        let mean = stream.memcpy_stod(&mean_host).unwrap();
+        let var = stream.memcpy_stod(&var_host).unwrap();
+
+        let op = BatchNormInference {
+            bn: &bn,
+            x: &x_desc,
+            y: &x_desc,
+        };
+        unsafe { op.launch((1.0, 0.0), &x, &mut y, &scale, &bias, &mean, &var, eps) }?;
+
+        let y_host = stream.memcpy_dtov(&y).unwrap();
+        for (i, (&x, &y)) in x_host.iter().zip(y_host.iter()).enumerate() {
+            let c = (i / HW) % C;
+            let expected = scale_host[c] * (x - mean_host[c]) / (var_host[c] + eps as f32).sqrt()
+                + bias_host[c];
+            assert!((y - expected).abs() < 1e-5, "{i}: {y} vs {expected}");
+        }
+
+        Ok(())
+    }
 }
@@ -75,6 +75,20 @@ pub unsafe fn set_offset(
     sys::curandSetGeneratorOffset(generator, offset).result()
 }
 
+/// Set the ordering of results of the generator, which trades off performance against
+/// how results are laid out in memory.
+///
+/// See [cuRAND docs](https://docs.nvidia.com/cuda/curand/group__HOST.html)
+///
+/// # Safety
+/// The generator must be allocated and not already freed.
+pub unsafe fn set_ordering(
+    generator: sys::curandGenerator_t,
+    ordering: sys::curandOrdering_t,
+) -> Result<(), CurandError> {
+    sys::curandSetGeneratorOrdering(generator, ordering).result()
+}
+
 /// Set the current stream for CURAND kernel launches.
 ///
 /// See [cuRAND docs](https://docs.nvidia.com/cuda/curand/group__HOST.html#group__HOST_1gc78c8d07c7acea4242e2a62bc41ff1f5)
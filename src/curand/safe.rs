@@ -1,7 +1,7 @@
 //! Safe abstractions around [crate::curand::result] with [CudaRng].
 
 use super::{result, sys};
-use crate::driver::{CudaStream, DevicePtrMut};
+use crate::driver::{result as driver_result, CudaStream, DevicePtrMut, DriverError};
 use std::sync::Arc;
 
 /// Host side RNG that can fill [crate::driver::CudaSlice]/[crate::driver::CudaViewMut] with random values.
@@ -23,10 +23,13 @@ use std::sync::Arc;
 /// rng.fill_with_uniform(&mut a_dev).unwrap();
 /// ```
 ///
-/// The three distributions are:
+/// The four distributions are:
 /// 1. Uniform - [CudaRng::fill_with_uniform()]
 /// 2. Normal - [CudaRng::fill_with_normal()]
 /// 3. LogNormal - [CudaRng::fill_with_log_normal()]
+/// 4. Poisson - [CudaRng::fill_with_poisson()]
+///
+/// Use [CudaRng::new_with_type()] to pick the underlying generator, see [RngType].
 pub struct CudaRng {
     pub(crate) gen: sys::curandGenerator_t,
     pub(crate) stream: Arc<CudaStream>,
@@ -35,15 +38,33 @@ pub struct CudaRng {
 impl CudaRng {
     /// Constructs the RNG with the given `seed`. All calls run on `stream`.
     pub fn new(seed: u64, stream: Arc<CudaStream>) -> Result<Self, result::CurandError> {
+        Self::new_with_type(seed, stream, RngType::Default)
+    }
+
+    /// Constructs the RNG with the given `seed` and generator `kind`. All calls run on `stream`.
+    pub fn new_with_type(
+        seed: u64,
+        stream: Arc<CudaStream>,
+        kind: RngType,
+    ) -> Result<Self, result::CurandError> {
         let ctx = &stream.ctx;
         ctx.record_err(ctx.bind_to_thread());
-        let gen = result::create_generator()?;
+        let gen = result::create_generator_kind(kind.into())?;
         unsafe { result::set_stream(gen, stream.cu_stream as _) }?;
         let mut rng = Self { gen, stream };
         rng.set_seed(seed)?;
         Ok(rng)
     }
 
+    /// Returns the stream all generation is ordered on.
+    pub fn stream(&self) -> &Arc<CudaStream> {
+        &self.stream
+    }
+
+    /// Orders all future generation on `stream` instead of the stream this RNG was created with,
+    /// so that random fills are ordered against the kernels that consume them.
+    /// The RNG keeps `stream` alive until it is replaced or the RNG is dropped.
+    ///
     /// # Safety
     /// Users must ensure this stream is properly synchronized
     pub unsafe fn set_stream(
@@ -63,6 +84,17 @@ impl CudaRng {
         unsafe { result::set_offset(self.gen, offset) }
     }
 
+    /// Sets how results are ordered in memory. Only the `CURAND_ORDERING_PSEUDO_*` orderings
+    /// are valid for the generators in [RngType], `CURAND_ORDERING_PSEUDO_DYNAMIC` only for [RngType::Philox].
+    ///
+    /// See [cuRAND docs](https://docs.nvidia.com/cuda/curand/host-api-overview.html#generator-options)
+    pub fn set_ordering(
+        &mut self,
+        ordering: sys::curandOrdering_t,
+    ) -> Result<(), result::CurandError> {
+        unsafe { result::set_ordering(self.gen, ordering) }
+    }
+
     /// Fill the [crate::driver::CudaSlice]/[crate::driver::CudaViewMut] with data from a `Uniform` distribution
     pub fn fill_with_uniform<T, Dst: DevicePtrMut<T>>(
         &self,
@@ -77,6 +109,8 @@ impl CudaRng {
     }
 
     /// Fill the [crate::driver::CudaSlice]/[crate::driver::CudaViewMut] with data from a `Normal(mean, std)` distribution.
+    ///
+    /// Odd lengths are supported by generating one extra value into a temporary buffer.
     pub fn fill_with_normal<T, Dst: DevicePtrMut<T>>(
         &self,
         dst: &mut Dst,
@@ -86,12 +120,14 @@ impl CudaRng {
     where
         sys::curandGenerator_t: result::NormalFill<T>,
     {
-        let num = dst.len();
-        let (dst, _record_dst) = dst.device_ptr_mut(&self.stream);
-        unsafe { result::NormalFill::fill(self.gen, dst as *mut T, num, mean, std) }
+        self.fill_pairs(dst, |out, num| unsafe {
+            result::NormalFill::fill(self.gen, out, num, mean, std)
+        })
     }
 
     /// Fill the [crate::driver::CudaSlice]/[crate::driver::CudaViewMut] with data from a `LogNormal(mean, std)` distribution.
+    ///
+    /// Odd lengths are supported by generating one extra value into a temporary buffer.
     pub fn fill_with_log_normal<T, Dst: DevicePtrMut<T>>(
         &self,
         dst: &mut Dst,
@@ -101,9 +137,89 @@ impl CudaRng {
     where
         sys::curandGenerator_t: result::LogNormalFill<T>,
     {
+        self.fill_pairs(dst, |out, num| unsafe {
+            result::LogNormalFill::fill(self.gen, out, num, mean, std)
+        })
+    }
+
+    /// Fill the [crate::driver::CudaSlice]/[crate::driver::CudaViewMut] with data from a `Poisson(lambda)` distribution.
+    ///
+    /// Returns [sys::curandStatus_t::CURAND_STATUS_OUT_OF_RANGE] if `lambda` is not positive and finite.
+    pub fn fill_with_poisson<Dst: DevicePtrMut<u32>>(
+        &self,
+        dst: &mut Dst,
+        lambda: f64,
+    ) -> Result<(), result::CurandError> {
+        if !(lambda > 0.0 && lambda.is_finite()) {
+            return Err(result::CurandError(
+                sys::curandStatus_t::CURAND_STATUS_OUT_OF_RANGE,
+            ));
+        }
         let num = dst.len();
         let (dst, _record_dst) = dst.device_ptr_mut(&self.stream);
-        unsafe { result::LogNormalFill::fill(self.gen, dst as *mut T, num, mean, std) }
+        unsafe { result::generate::poisson_u32(self.gen, dst as *mut u32, num, lambda) }
+    }
+
+    /// cuRAND generates normal values in pairs, so pseudo random generators reject odd lengths.
+    /// For odd lengths this generates `len + 1` values into a temporary buffer and copies `len` of them over.
+    ///
+    /// Driver errors from the temporary buffer are mapped with [driver_error_to_curand()].
+    fn fill_pairs<T, Dst: DevicePtrMut<T>>(
+        &self,
+        dst: &mut Dst,
+        fill: impl FnOnce(*mut T, usize) -> Result<(), result::CurandError>,
+    ) -> Result<(), result::CurandError> {
+        let num = dst.len();
+        let (dst, _record_dst) = dst.device_ptr_mut(&self.stream);
+        if num % 2 == 0 {
+            return fill(dst as *mut T, num);
+        }
+        let size = std::mem::size_of::<T>();
+        let mut tmp =
+            unsafe { self.stream.alloc::<u8>((num + 1) * size) }.map_err(driver_error_to_curand)?;
+        let (src, _record_src) = tmp.device_ptr_mut(&self.stream);
+        fill(src as *mut T, num + 1)?;
+        unsafe { driver_result::memcpy_dtod_async(dst, src, num * size, self.stream.cu_stream) }
+            .map_err(driver_error_to_curand)
+    }
+}
+
+/// cuRAND reports failures of the CUDA calls it makes itself as `CURAND_STATUS_ALLOCATION_FAILED`
+/// (out of memory) or `CURAND_STATUS_LAUNCH_FAILURE` (anything else), so [CudaRng] does the same
+/// for its own driver calls. This keeps [result::CurandError] as the only error type of [CudaRng].
+fn driver_error_to_curand(err: DriverError) -> result::CurandError {
+    match err.0 {
+        crate::driver::sys::cudaError_enum::CUDA_ERROR_OUT_OF_MEMORY => {
+            result::CurandError(sys::curandStatus_t::CURAND_STATUS_ALLOCATION_FAILED)
+        }
+        _ => result::CurandError(sys::curandStatus_t::CURAND_STATUS_LAUNCH_FAILURE),
+    }
+}
+
+/// The pseudo random generator behind a [CudaRng].
+///
+/// All of them support every `fill_with_*` method of [CudaRng].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RngType {
+    /// cuRAND's default pseudo random generator, currently [RngType::Xorwow].
+    #[default]
+    Default,
+    /// XORWOW, fast with a small state but the weakest statistical quality.
+    Xorwow,
+    /// Philox4x32-10, counter based with good statistical quality.
+    Philox,
+    /// MRG32k3a, combined multiple recursive generator with good statistical quality.
+    Mrg32k3a,
+}
+
+impl From<RngType> for sys::curandRngType_t {
+    fn from(kind: RngType) -> Self {
+        match kind {
+            RngType::Default => sys::curandRngType_t::CURAND_RNG_PSEUDO_DEFAULT,
+            RngType::Xorwow => sys::curandRngType_t::CURAND_RNG_PSEUDO_XORWOW,
+            RngType::Philox => sys::curandRngType_t::CURAND_RNG_PSEUDO_PHILOX4_32_10,
+            RngType::Mrg32k3a => sys::curandRngType_t::CURAND_RNG_PSEUDO_MRG32K3A,
+        }
     }
 }
 
@@ -235,6 +351,27 @@ mod tests {
         assert_eq!(a_host, b_host);
     }
 
+    #[test]
+    fn test_set_stream() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let other = ctx.new_stream().unwrap();
+
+        let mut a_dev = stream.alloc_zeros::<f32>(10).unwrap();
+        let a_rng = CudaRng::new(0, stream.clone()).unwrap();
+        a_rng.fill_with_uniform(&mut a_dev).unwrap();
+        let a_host = stream.memcpy_dtov(&a_dev).unwrap();
+
+        let mut b_dev = other.alloc_zeros::<f32>(10).unwrap();
+        let mut b_rng = CudaRng::new(0, stream.clone()).unwrap();
+        unsafe { b_rng.set_stream(other.clone()) }.unwrap();
+        assert_eq!(b_rng.stream(), &other);
+        b_rng.fill_with_uniform(&mut b_dev).unwrap();
+        let b_host = other.memcpy_dtov(&b_dev).unwrap();
+
+        assert_eq!(a_host, b_host);
+    }
+
     const N: usize = 1000;
 
     #[test]
@@ -287,6 +424,68 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_normal_statistics() {
+        const SAMPLES: usize = 100_001;
+        let a = gen_normal::<f64>(0, SAMPLES, 3.0, 2.0);
+        let mean = a.iter().sum::<f64>() / SAMPLES as f64;
+        let var = a.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / SAMPLES as f64;
+        assert!((mean - 3.0).abs() < 0.05, "mean={mean}");
+        assert!((var - 4.0).abs() < 0.1, "var={var}");
+
+        let b = gen_log_normal::<f32>(0, SAMPLES, 0.0, 0.5);
+        let log_mean = b.iter().map(|x| (*x as f64).ln()).sum::<f64>() / SAMPLES as f64;
+        assert!(b.iter().all(|x| *x > 0.0));
+        assert!(log_mean.abs() < 0.05, "log_mean={log_mean}");
+    }
+
+    #[test]
+    fn test_normal_odd_len() {
+        for n in [1, 3, 7] {
+            let a = gen_normal::<f32>(0, n, 0.0, 1.0);
+            assert!(a.iter().all(|x| *x != 0.0), "{a:?}");
+            let b = gen_log_normal::<f64>(0, n, 0.0, 1.0);
+            assert!(b.iter().all(|x| *x > 0.0), "{b:?}");
+        }
+    }
+
+    #[test]
+    fn test_poisson() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        for kind in [RngType::Xorwow, RngType::Philox, RngType::Mrg32k3a] {
+            let rng = CudaRng::new_with_type(0, stream.clone(), kind).unwrap();
+            let mut a_dev = stream.alloc_zeros::<u32>(N).unwrap();
+            rng.fill_with_poisson(&mut a_dev, 4.0).unwrap();
+            let a = stream.memcpy_dtov(&a_dev).unwrap();
+            let mean = a.iter().map(|x| *x as f64).sum::<f64>() / N as f64;
+            assert!((mean - 4.0).abs() < 0.5, "{kind:?} mean={mean}");
+
+            for lambda in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+                assert_eq!(
+                    rng.fill_with_poisson(&mut a_dev, lambda),
+                    Err(super::result::CurandError(
+                        super::sys::curandStatus_t::CURAND_STATUS_OUT_OF_RANGE
+                    ))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_set_ordering() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let mut rng = CudaRng::new_with_type(0, stream.clone(), RngType::Philox).unwrap();
+        rng.set_ordering(super::sys::curandOrdering_t::CURAND_ORDERING_PSEUDO_SEEDED)
+            .unwrap();
+        let mut a_dev = stream.alloc_zeros::<f32>(N).unwrap();
+        rng.fill_with_uniform(&mut a_dev).unwrap();
+        assert!(rng
+            .set_ordering(super::sys::curandOrdering_t::CURAND_ORDERING_QUASI_DEFAULT)
+            .is_err());
+    }
+
     #[test]
     fn test_log_normal_f32() {
         let a = gen_log_normal::<f32>(0, N, 0.0, 1.0);
@@ -12,7 +12,7 @@
 //! turns into [stream::create()], where [stream] is a module.
 
 use super::sys::{self};
-use core::ffi::{c_uchar, c_uint, c_void, CStr};
+use core::ffi::{c_uchar, c_uint, c_ushort, c_void, CStr};
 use std::mem::MaybeUninit;
 
 /// Wrapper around [sys::CUresult]. See
@@ -31,17 +31,20 @@ impl sys::CUresult {
 }
 
 impl DriverError {
-    /// Gets the name for this error.
+    /// Gets the short enum name for this error, e.g. `"CUDA_ERROR_INVALID_VALUE"`.
+    /// Returns `"<unknown>"` if the driver does not recognize the error code.
     ///
     /// See [cuGetErrorName() docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__ERROR.html#group__CUDA__ERROR_1g2c4ac087113652bb3d1f95bf2513c468)
-    pub fn error_name(&self) -> Result<&CStr, DriverError> {
+    pub fn error_name(&self) -> &'static str {
         if self.0 == sys::CUresult::TIG_ERROR_OUT_OF_FUEL {
-            return Ok(c"TIG_ERROR_OUT_OF_FUEL");
+            return "TIG_ERROR_OUT_OF_FUEL";
         }
-        let mut err_str = MaybeUninit::uninit();
+        let mut err_str = core::ptr::null();
         unsafe {
-            sys::cuGetErrorName(self.0, err_str.as_mut_ptr()).result()?;
-            Ok(CStr::from_ptr(err_str.assume_init()))
+            if sys::cuGetErrorName(self.0, &mut err_str).result().is_err() || err_str.is_null() {
+                return "<unknown>";
+            }
+            CStr::from_ptr(err_str).to_str().unwrap_or("<unknown>")
         }
     }
 
@@ -52,12 +55,27 @@ impl DriverError {
         if self.0 == sys::CUresult::TIG_ERROR_OUT_OF_FUEL {
             return Ok(c"ran out of fuel");
         }
-        let mut err_str = MaybeUninit::uninit();
+        let mut err_str = core::ptr::null();
         unsafe {
-            sys::cuGetErrorString(self.0, err_str.as_mut_ptr()).result()?;
-            Ok(CStr::from_ptr(err_str.assume_init()))
+            sys::cuGetErrorString(self.0, &mut err_str).result()?;
+            if err_str.is_null() {
+                return Err(DriverError(sys::CUresult::CUDA_ERROR_INVALID_VALUE));
+            }
+            Ok(CStr::from_ptr(err_str))
         }
     }
+
+    /// Whether this is [sys::CUresult::CUDA_ERROR_CONTEXT_IS_DESTROYED], e.g. from
+    /// using a stream while its context is being torn down.
+    pub fn is_context_destroyed(&self) -> bool {
+        self.0 == sys::CUresult::CUDA_ERROR_CONTEXT_IS_DESTROYED
+    }
+
+    /// Whether this is [sys::CUresult::CUDA_ERROR_OUT_OF_MEMORY]. Unlike most other errors,
+    /// the operation may succeed if retried after memory has been freed.
+    pub fn is_out_of_memory(&self) -> bool {
+        self.0 == sys::CUresult::CUDA_ERROR_OUT_OF_MEMORY
+    }
 }
 
 impl std::fmt::Debug for DriverError {
@@ -95,6 +113,16 @@ pub fn init() -> Result<(), DriverError> {
     unsafe { sys::cuInit(0).result() }
 }
 
+/// Returns the latest CUDA version supported by the installed driver, encoded as `1000 * major + 10 * minor`
+/// (e.g. `12020` for 12.2). Does not require [init()].
+///
+/// See [cuDriverGetVersion() docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__VERSION.html)
+pub fn driver_version() -> Result<i32, DriverError> {
+    let mut version = 0;
+    unsafe { sys::cuDriverGetVersion(&mut version).result()? };
+    Ok(version)
+}
+
 pub mod device {
     //! Device management functions (`cuDevice*`).
     //!
@@ -161,15 +189,36 @@ pub mod device {
     ///
     /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__DEVICE.html#group__CUDA__DEVICE_1gef75aa30df95446a845f2a7b9fffbb7f)
     pub fn get_name(dev: sys::CUdevice) -> Result<String, DriverError> {
-        const BUF_SIZE: usize = 128;
+        const BUF_SIZE: usize = 256;
         let mut buf = [0u8; BUF_SIZE];
         unsafe {
             sys::cuDeviceGetName(buf.as_mut_ptr() as _, BUF_SIZE as _, dev).result()?;
         }
-        let name = CStr::from_bytes_until_nul(&buf).expect("No null byte was present");
-        Ok(String::from_utf8_lossy(name.to_bytes()).into())
+        let name = match CStr::from_bytes_until_nul(&buf) {
+            Ok(name) => name.to_bytes(),
+            // the name was truncated to fill the whole buffer
+            Err(_) => &buf,
+        };
+        Ok(String::from_utf8_lossy(name).into())
+    }
+
+    /// Whether `dev` can directly access memory of `peer_dev`.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__PEER__ACCESS.html)
+    pub fn can_access_peer(
+        dev: sys::CUdevice,
+        peer_dev: sys::CUdevice,
+    ) -> Result<bool, DriverError> {
+        let mut can_access = MaybeUninit::uninit();
+        unsafe {
+            sys::cuDeviceCanAccessPeer(can_access.as_mut_ptr(), dev, peer_dev).result()?;
+            Ok(can_access.assume_init() != 0)
+        }
     }
 
+    /// Get the UUID of the device.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__DEVICE.html)
     pub fn get_uuid(dev: sys::CUdevice) -> Result<sys::CUuuid, DriverError> {
         let id: sys::CUuuid;
         unsafe {
@@ -202,6 +251,45 @@ pub mod function {
         Ok(())
     }
 
+    /// Returns the (possibly mangled) name of `f`.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__EXEC.html)
+    ///
+    /// # Safety
+    /// Function must exist.
+    #[cfg(not(any(
+        feature = "cuda-11040",
+        feature = "cuda-11050",
+        feature = "cuda-11060",
+        feature = "cuda-11070",
+        feature = "cuda-11080",
+        feature = "cuda-12000",
+        feature = "cuda-12010",
+        feature = "cuda-12020"
+    )))]
+    pub unsafe fn get_name(f: sys::CUfunction) -> Result<std::ffi::CString, super::DriverError> {
+        let mut name = std::ptr::null();
+        sys::cuFuncGetName(&mut name, f).result()?;
+        Ok(core::ffi::CStr::from_ptr(name).to_owned())
+    }
+
+    /// Gets the specific attribute of a cuda function.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__EXEC.html)
+    ///
+    /// # Safety
+    /// Function must exist.
+    pub unsafe fn get_function_attribute(
+        f: sys::CUfunction,
+        attribute: CUfunction_attribute_enum,
+    ) -> Result<i32, super::DriverError> {
+        let mut value = std::mem::MaybeUninit::uninit();
+        unsafe {
+            sys::cuFuncGetAttribute(value.as_mut_ptr(), attribute, f).result()?;
+            Ok(value.assume_init())
+        }
+    }
+
     /// Sets the cache config of a CUDA function.
     ///
     /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-runtime-api/group__CUDART__EXECUTION.html#group__CUDART__EXECUTION_1g6699ca1943ac2655effa0d571b2f4f15)
@@ -404,6 +492,44 @@ pub mod primary_ctx {
     pub unsafe fn release(dev: sys::CUdevice) -> Result<(), DriverError> {
         sys::cuDevicePrimaryCtxRelease_v2(dev).result()
     }
+
+    /// Destroys all allocations and resets all state on the primary context of the device.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__PRIMARY__CTX.html)
+    ///
+    /// # Safety
+    ///
+    /// This is only safe with a device that was returned from [super::device::get], and
+    /// nothing else in the process may still be using the primary context.
+    pub unsafe fn reset(dev: sys::CUdevice) -> Result<(), DriverError> {
+        sys::cuDevicePrimaryCtxReset_v2(dev).result()
+    }
+
+    /// Sets the flags (a combination of [sys::CUctx_flags] values) for the primary context of the device.
+    /// Scheduling flags take effect even if the primary context is already active.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__PRIMARY__CTX.html)
+    ///
+    /// # Safety
+    ///
+    /// This is only safe with a device that was returned from [super::device::get].
+    pub unsafe fn set_flags(dev: sys::CUdevice, flags: u32) -> Result<(), DriverError> {
+        sys::cuDevicePrimaryCtxSetFlags_v2(dev, flags).result()
+    }
+
+    /// Returns the flags of the primary context of the device, and whether it is active.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__PRIMARY__CTX.html)
+    ///
+    /// # Safety
+    ///
+    /// This is only safe with a device that was returned from [super::device::get].
+    pub unsafe fn get_state(dev: sys::CUdevice) -> Result<(u32, bool), DriverError> {
+        let mut flags = MaybeUninit::uninit();
+        let mut active = MaybeUninit::uninit();
+        sys::cuDevicePrimaryCtxGetState(dev, flags.as_mut_ptr(), active.as_mut_ptr()).result()?;
+        Ok((flags.assume_init(), active.assume_init() != 0))
+    }
 }
 
 pub mod ctx {
@@ -463,6 +589,39 @@ pub mod ctx {
     pub fn synchronize() -> Result<(), DriverError> {
         unsafe { sys::cuCtxSynchronize() }.result()
     }
+
+    /// Enables the current context to access memory allocations in `peer_ctx`.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__PEER__ACCESS.html)
+    ///
+    /// # Safety
+    /// `peer_ctx` must be a valid context.
+    pub unsafe fn enable_peer_access(peer_ctx: sys::CUcontext) -> Result<(), DriverError> {
+        sys::cuCtxEnablePeerAccess(peer_ctx, 0).result()
+    }
+
+    /// Disables access from the current context to memory allocations in `peer_ctx`.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__PEER__ACCESS.html)
+    ///
+    /// # Safety
+    /// `peer_ctx` must be a valid context.
+    pub unsafe fn disable_peer_access(peer_ctx: sys::CUcontext) -> Result<(), DriverError> {
+        sys::cuCtxDisablePeerAccess(peer_ctx).result()
+    }
+
+    /// Returns `(least, greatest)` stream priorities of the current context.
+    /// Note that lower numbers mean higher priority, so `greatest <= least`.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__CTX.html)
+    pub fn get_stream_priority_range() -> Result<(i32, i32), DriverError> {
+        let mut least = MaybeUninit::uninit();
+        let mut greatest = MaybeUninit::uninit();
+        unsafe {
+            sys::cuCtxGetStreamPriorityRange(least.as_mut_ptr(), greatest.as_mut_ptr()).result()?;
+            Ok((least.assume_init(), greatest.assume_init()))
+        }
+    }
 }
 
 pub mod stream {
@@ -508,6 +667,15 @@ pub mod stream {
         std::ptr::null_mut()
     }
 
+    /// The per-thread default stream (`CU_STREAM_PER_THREAD`). Each host thread gets its own implicit
+    /// stream, which only synchronizes with the legacy null stream, not with the per-thread streams of
+    /// other threads.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/stream-sync-behavior.html#stream-sync-behavior__default-stream)
+    pub fn per_thread() -> sys::CUstream {
+        std::ptr::null_mut::<u8>().wrapping_add(0x2).cast()
+    }
+
     /// Creates a stream with the specified kind.
     ///
     /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__STREAM.html#group__CUDA__STREAM_1ga581f0c5833e21ded8b5a56594e243f4)
@@ -519,6 +687,36 @@ pub mod stream {
         }
     }
 
+    /// Creates a stream with the specified kind and priority.
+    /// Lower numbers mean higher priority, see [super::ctx::get_stream_priority_range()].
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__STREAM.html)
+    pub fn create_with_priority(
+        kind: StreamKind,
+        priority: i32,
+    ) -> Result<sys::CUstream, DriverError> {
+        let mut stream = MaybeUninit::uninit();
+        unsafe {
+            sys::cuStreamCreateWithPriority(stream.as_mut_ptr(), kind.flags() as u32, priority)
+                .result()?;
+            Ok(stream.assume_init())
+        }
+    }
+
+    /// Returns the priority of a stream.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__STREAM.html)
+    ///
+    /// # Safety
+    ///
+    /// This should only be called with stream created by [create] and not already
+    /// destroyed.
+    pub unsafe fn get_priority(stream: sys::CUstream) -> Result<i32, DriverError> {
+        let mut priority = MaybeUninit::uninit();
+        sys::cuStreamGetPriority(stream, priority.as_mut_ptr()).result()?;
+        Ok(priority.assume_init())
+    }
+
     /// Wait until a stream's tasks are completed.
     ///
     /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__STREAM.html#group__CUDA__STREAM_1g15e49dd91ec15991eb7c0a741beb7dad)
@@ -531,6 +729,32 @@ pub mod stream {
         sys::cuStreamSynchronize(stream).result()
     }
 
+    /// Queries whether all work submitted to the stream has completed.
+    /// Returns `Ok` if it has, or `Err`: `CUDA_ERROR_NOT_READY` if any work is incomplete.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__STREAM.html#group__CUDA__STREAM_1g1b0d24bbe97fa68e4bc511fb6adfeb0b)
+    ///
+    /// # Safety
+    ///
+    /// This should only be called with stream created by [create] and not already
+    /// destroyed. This follows default stream semantics, see relevant cuda docs.
+    pub unsafe fn query(stream: sys::CUstream) -> Result<(), DriverError> {
+        sys::cuStreamQuery(stream).result()
+    }
+
+    /// Returns the context the stream belongs to.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__STREAM.html)
+    ///
+    /// # Safety
+    ///
+    /// This should only be called with a valid stream that was not already destroyed.
+    pub unsafe fn get_ctx(stream: sys::CUstream) -> Result<sys::CUcontext, DriverError> {
+        let mut ctx = MaybeUninit::uninit();
+        sys::cuStreamGetCtx(stream, ctx.as_mut_ptr()).result()?;
+        Ok(ctx.assume_init())
+    }
+
     /// Destroys a stream.
     ///
     /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__STREAM.html#group__CUDA__STREAM_1g244c8833de4596bcd31a06cdf21ee758)
@@ -573,6 +797,50 @@ pub mod stream {
         sys::cuStreamAttachMemAsync(stream, dptr, num_bytes, flags as u32).result()
     }
 
+    /// Makes `stream` wait until the 32 bit value at `addr` satisfies `flags` with respect to `value`.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEMOP.html)
+    ///
+    /// # Safety
+    /// `addr` must be a valid device address of a `u32` that stays alive until the wait completes.
+    #[cfg(not(any(
+        feature = "cuda-11040",
+        feature = "cuda-11050",
+        feature = "cuda-11060",
+        feature = "cuda-11070",
+        feature = "cuda-11080"
+    )))]
+    pub unsafe fn wait_value_32(
+        stream: sys::CUstream,
+        addr: sys::CUdeviceptr,
+        value: u32,
+        flags: sys::CUstreamWaitValue_flags,
+    ) -> Result<(), DriverError> {
+        sys::cuStreamWaitValue32_v2(stream, addr, value, flags as u32).result()
+    }
+
+    /// Writes the 32 bit `value` to `addr`, ordered on `stream`.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEMOP.html)
+    ///
+    /// # Safety
+    /// `addr` must be a valid device address of a `u32` that stays alive until the write completes.
+    #[cfg(not(any(
+        feature = "cuda-11040",
+        feature = "cuda-11050",
+        feature = "cuda-11060",
+        feature = "cuda-11070",
+        feature = "cuda-11080"
+    )))]
+    pub unsafe fn write_value_32(
+        stream: sys::CUstream,
+        addr: sys::CUdeviceptr,
+        value: u32,
+        flags: sys::CUstreamWriteValue_flags,
+    ) -> Result<(), DriverError> {
+        sys::cuStreamWriteValue32_v2(stream, addr, value, flags as u32).result()
+    }
+
     /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__EXEC.html#group__CUDA__EXEC_1gab95a78143bae7f21eebb978f91e7f3f)
     ///
     /// # Safety
@@ -585,6 +853,21 @@ pub mod stream {
         sys::cuLaunchHostFunc(stream, Some(func), arg).result()
     }
 
+    /// Adds a callback that is called once all previously submitted work on `stream` has completed,
+    /// or with an error status if the context hits an error first.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__STREAM.html)
+    ///
+    /// # Safety
+    /// `func` must not call any CUDA API, and `arg` must be valid until `func` is called.
+    pub unsafe fn add_callback(
+        stream: sys::CUstream,
+        func: unsafe extern "C" fn(sys::CUstream, sys::CUresult, *mut ::core::ffi::c_void),
+        arg: *mut std::ffi::c_void,
+    ) -> Result<(), DriverError> {
+        sys::cuStreamAddCallback(stream, Some(func), arg, 0).result()
+    }
+
     /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__STREAM.html#group__CUDA__STREAM_1g767167da0bbf07157dc20b6c258a2143)
     /// # Safety
     /// Stream must be valid
@@ -645,6 +928,34 @@ pub unsafe fn malloc_sync(num_bytes: usize) -> Result<sys::CUdeviceptr, DriverEr
     Ok(dev_ptr.assume_init())
 }
 
+/// Allocates pitched memory for `height` rows of `width_bytes` bytes. Returns the device pointer
+/// and the pitch (the number of bytes between the start of consecutive rows).
+///
+/// `element_size` is the size of the largest reads/writes that will be done on the memory,
+/// and must be 4, 8, or 16.
+///
+/// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html)
+///
+/// # Safety
+/// 1. The memory return by this is unset, which may be invalid for `T`.
+pub unsafe fn malloc_pitch(
+    width_bytes: usize,
+    height: usize,
+    element_size: u32,
+) -> Result<(sys::CUdeviceptr, usize), DriverError> {
+    let mut dev_ptr = MaybeUninit::uninit();
+    let mut pitch = MaybeUninit::uninit();
+    sys::cuMemAllocPitch_v2(
+        dev_ptr.as_mut_ptr(),
+        pitch.as_mut_ptr(),
+        width_bytes,
+        height,
+        element_size,
+    )
+    .result()?;
+    Ok((dev_ptr.assume_init(), pitch.assume_init()))
+}
+
 /// Allocates managed memory.
 ///
 /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html#group__CUDA__MEM_1gb347ded34dc326af404aa02af5388a32)
@@ -677,6 +988,44 @@ pub unsafe fn free_host(host_ptr: *mut c_void) -> Result<(), DriverError> {
     sys::cuMemFreeHost(host_ptr).result()
 }
 
+/// Page-locks `num_bytes` of existing host memory starting at `host_ptr`.
+///
+/// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html)
+/// # Safety
+/// 1. `host_ptr` must point to `num_bytes` of valid host memory.
+/// 2. The memory must stay allocated until it is passed to [host_unregister].
+pub unsafe fn host_register(
+    host_ptr: *mut c_void,
+    num_bytes: usize,
+    flags: c_uint,
+) -> Result<(), DriverError> {
+    sys::cuMemHostRegister_v2(host_ptr, num_bytes, flags).result()
+}
+
+/// Unregisters memory registered with [host_register].
+///
+/// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html)
+/// # Safety
+/// 1. `host_ptr` must be the pointer that was passed to [host_register].
+/// 2. All async accesses to the memory must have been completed.
+pub unsafe fn host_unregister(host_ptr: *mut c_void) -> Result<(), DriverError> {
+    sys::cuMemHostUnregister(host_ptr).result()
+}
+
+/// Returns the device pointer of host memory that was allocated with [malloc_host] or registered with
+/// [host_register] using the `DEVICEMAP` flag.
+///
+/// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html)
+/// # Safety
+/// 1. `host_ptr` must be page-locked memory mapped into the device address space.
+pub unsafe fn host_get_device_pointer(
+    host_ptr: *mut c_void,
+) -> Result<sys::CUdeviceptr, DriverError> {
+    let mut dev_ptr = MaybeUninit::uninit();
+    sys::cuMemHostGetDevicePointer_v2(dev_ptr.as_mut_ptr(), host_ptr, 0).result()?;
+    Ok(dev_ptr.assume_init())
+}
+
 /// Advise about the usage of a given memory range.
 ///
 /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__UNIFIED.html#group__CUDA__UNIFIED_1g27608c857a9254789c13f3e3b72029e2)
@@ -776,6 +1125,62 @@ pub unsafe fn memset_d8_async(
     sys::cuMemsetD8Async(dptr, uc, num_bytes, stream).result()
 }
 
+/// Sets `num_elements` 16-bit values of device memory to `us` with stream ordered semantics.
+/// `dptr` must be 2 byte aligned.
+///
+/// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html)
+///
+/// # Safety
+/// 1. The resulting memory pattern may not be valid for `T`.
+/// 2. The device pointer should not have been freed already (double free)
+/// 3. The stream should be the stream the memory was allocated on.
+pub unsafe fn memset_d16_async(
+    dptr: sys::CUdeviceptr,
+    us: c_ushort,
+    num_elements: usize,
+    stream: sys::CUstream,
+) -> Result<(), DriverError> {
+    sys::cuMemsetD16Async(dptr, us, num_elements, stream).result()
+}
+
+/// Sets `num_elements` 32-bit values of device memory to `ui` with stream ordered semantics.
+/// `dptr` must be 4 byte aligned.
+///
+/// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html)
+///
+/// # Safety
+/// 1. The resulting memory pattern may not be valid for `T`.
+/// 2. The device pointer should not have been freed already (double free)
+/// 3. The stream should be the stream the memory was allocated on.
+pub unsafe fn memset_d32_async(
+    dptr: sys::CUdeviceptr,
+    ui: c_uint,
+    num_elements: usize,
+    stream: sys::CUstream,
+) -> Result<(), DriverError> {
+    sys::cuMemsetD32Async(dptr, ui, num_elements, stream).result()
+}
+
+/// Sets a 2d region of `height` rows of `width` 32-bit values, with rows `pitch` bytes apart,
+/// to `ui` with stream ordered semantics. `dptr` must be 4 byte aligned.
+///
+/// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html)
+///
+/// # Safety
+/// 1. The resulting memory pattern may not be valid for `T`.
+/// 2. The device pointer should not have been freed already (double free)
+/// 3. The stream should be the stream the memory was allocated on.
+pub unsafe fn memset_d2d32_async(
+    dptr: sys::CUdeviceptr,
+    pitch: usize,
+    ui: c_uint,
+    width: usize,
+    height: usize,
+    stream: sys::CUstream,
+) -> Result<(), DriverError> {
+    sys::cuMemsetD2D32Async(dptr, pitch, ui, width, height, stream).result()
+}
+
 /// Sets device memory with stream ordered semantics.
 ///
 /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html#group__CUDA__MEM_1g6e582bf866e9e2fb014297bfaf354d7b)
@@ -885,6 +1290,56 @@ pub unsafe fn memcpy_dtod_async(
     sys::cuMemcpyDtoDAsync_v2(dst, src, num_bytes, stream).result()
 }
 
+/// Copies a 2D region of memory with stream ordered semantics, as described by `params`.
+///
+/// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html)
+///
+/// # Safety
+/// 1. All pointers in `params` must be valid for the region described by `params`.
+/// 2. **This function is asynchronous** in most cases, so host memory must stay alive
+///    until the copy completes.
+pub unsafe fn memcpy_2d_async(
+    params: &sys::CUDA_MEMCPY2D,
+    stream: sys::CUstream,
+) -> Result<(), DriverError> {
+    sys::cuMemcpy2DAsync_v2(params, stream).result()
+}
+
+/// Copies a 3D region of memory with stream ordered semantics, as described by `params`.
+/// Also used to copy to and from [sys::CUarray]s.
+///
+/// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html)
+///
+/// # Safety
+/// 1. All pointers and arrays in `params` must be valid for the region described by `params`.
+/// 2. **This function is asynchronous** in most cases, so host memory must stay alive
+///    until the copy completes.
+pub unsafe fn memcpy_3d_async(
+    params: &sys::CUDA_MEMCPY3D,
+    stream: sys::CUstream,
+) -> Result<(), DriverError> {
+    sys::cuMemcpy3DAsync_v2(params, stream).result()
+}
+
+/// Copies memory between two devices (contexts) with stream ordered semantics.
+///
+/// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html)
+///
+/// # Safety
+/// 1. `dst` must be allocated in `dst_ctx` and `src` in `src_ctx`.
+/// 2. Neither device pointer should have been freed already.
+/// 3. Both buffers must be valid for `num_bytes`.
+pub unsafe fn memcpy_peer_async(
+    dst: sys::CUdeviceptr,
+    dst_ctx: sys::CUcontext,
+    src: sys::CUdeviceptr,
+    src_ctx: sys::CUcontext,
+    num_bytes: usize,
+    stream: sys::CUstream,
+) -> Result<(), DriverError> {
+    sys::cuMemcpyPeerAsync(dst, dst_ctx, src, src_ctx, num_bytes, stream).result()
+}
+
 /// Copies memory from Device to Device
 ///
 /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html#group__CUDA__MEM_1g1725774abf8b51b91945f3336b778c8b)
@@ -968,6 +1423,73 @@ pub mod module {
         Ok(func.assume_init())
     }
 
+    /// Returns the device pointer and size in bytes of the global variable `name` in `module`.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MODULE.html)
+    ///
+    /// # Safety
+    /// `module` must be a properly allocated and not freed module.
+    pub unsafe fn get_global(
+        module: sys::CUmodule,
+        name: CString,
+    ) -> Result<(sys::CUdeviceptr, usize), DriverError> {
+        let name_ptr = name.as_c_str().as_ptr();
+        let mut dptr = MaybeUninit::uninit();
+        let mut bytes = MaybeUninit::uninit();
+        sys::cuModuleGetGlobal_v2(dptr.as_mut_ptr(), bytes.as_mut_ptr(), module, name_ptr)
+            .result()?;
+        Ok((dptr.assume_init(), bytes.assume_init()))
+    }
+
+    /// Returns the number of functions in `module`.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MODULE.html)
+    ///
+    /// # Safety
+    /// `module` must be a properly allocated and not freed module.
+    #[cfg(not(any(
+        feature = "cuda-11040",
+        feature = "cuda-11050",
+        feature = "cuda-11060",
+        feature = "cuda-11070",
+        feature = "cuda-11080",
+        feature = "cuda-12000",
+        feature = "cuda-12010",
+        feature = "cuda-12020",
+        feature = "cuda-12030"
+    )))]
+    pub unsafe fn get_function_count(module: sys::CUmodule) -> Result<u32, DriverError> {
+        let mut count = MaybeUninit::uninit();
+        sys::cuModuleGetFunctionCount(count.as_mut_ptr(), module).result()?;
+        Ok(count.assume_init())
+    }
+
+    /// Returns handles to all the functions in `module`.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MODULE.html)
+    ///
+    /// # Safety
+    /// `module` must be a properly allocated and not freed module.
+    #[cfg(not(any(
+        feature = "cuda-11040",
+        feature = "cuda-11050",
+        feature = "cuda-11060",
+        feature = "cuda-11070",
+        feature = "cuda-11080",
+        feature = "cuda-12000",
+        feature = "cuda-12010",
+        feature = "cuda-12020",
+        feature = "cuda-12030"
+    )))]
+    pub unsafe fn enumerate_functions(
+        module: sys::CUmodule,
+    ) -> Result<std::vec::Vec<sys::CUfunction>, DriverError> {
+        let count = get_function_count(module)?;
+        let mut functions = std::vec![std::ptr::null_mut(); count as usize];
+        sys::cuModuleEnumerateFunctions(functions.as_mut_ptr(), count, module).result()?;
+        Ok(functions)
+    }
+
     /// Unloads a module.
     ///
     /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MODULE.html#group__CUDA__MODULE_1g8ea3d716524369de3763104ced4ea57b)
@@ -1134,6 +1656,144 @@ pub unsafe fn launch_cooperative_kernel(
     .result()
 }
 
+pub mod ipc {
+    //! Inter process communication functions (`cuIpc*`).
+    //!
+    //! See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html)
+
+    use super::{sys, DriverError};
+    use std::mem::MaybeUninit;
+
+    /// Gets a handle to an existing device allocation that can be opened in another process.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html)
+    ///
+    /// # Safety
+    /// `dptr` must be the base pointer of an allocation made with [super::malloc_sync()].
+    pub unsafe fn get_mem_handle(
+        dptr: sys::CUdeviceptr,
+    ) -> Result<sys::CUipcMemHandle, DriverError> {
+        let mut handle = MaybeUninit::uninit();
+        sys::cuIpcGetMemHandle(handle.as_mut_ptr(), dptr).result()?;
+        Ok(handle.assume_init())
+    }
+
+    /// Opens a handle from [get_mem_handle()] that was created in another process.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html)
+    ///
+    /// # Safety
+    /// `handle` must have been created by [get_mem_handle()] in another process,
+    /// and the allocation must still be alive.
+    pub unsafe fn open_mem_handle(
+        handle: sys::CUipcMemHandle,
+        flags: sys::CUipcMem_flags,
+    ) -> Result<sys::CUdeviceptr, DriverError> {
+        let mut dptr = MaybeUninit::uninit();
+        sys::cuIpcOpenMemHandle_v2(dptr.as_mut_ptr(), handle, flags as u32).result()?;
+        Ok(dptr.assume_init())
+    }
+
+    /// Closes memory opened with [open_mem_handle()].
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html)
+    ///
+    /// # Safety
+    /// `dptr` must have been returned by [open_mem_handle()] and not closed already.
+    pub unsafe fn close_mem_handle(dptr: sys::CUdeviceptr) -> Result<(), DriverError> {
+        sys::cuIpcCloseMemHandle(dptr).result()
+    }
+}
+
+pub mod mem_pool {
+    //! Stream ordered memory pool functions (`cuMemPool*`).
+    //!
+    //! See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MALLOC__ASYNC.html)
+
+    use super::{sys, DriverError};
+    use core::ffi::c_void;
+    use std::mem::MaybeUninit;
+
+    /// Creates a new memory pool.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MALLOC__ASYNC.html)
+    pub fn create(props: &sys::CUmemPoolProps) -> Result<sys::CUmemoryPool, DriverError> {
+        let mut pool = MaybeUninit::uninit();
+        unsafe {
+            sys::cuMemPoolCreate(pool.as_mut_ptr(), props).result()?;
+            Ok(pool.assume_init())
+        }
+    }
+
+    /// Returns the default memory pool of `device`.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__DEVICE.html)
+    pub fn get_default(device: sys::CUdevice) -> Result<sys::CUmemoryPool, DriverError> {
+        let mut pool = MaybeUninit::uninit();
+        unsafe {
+            sys::cuDeviceGetDefaultMemPool(pool.as_mut_ptr(), device).result()?;
+            Ok(pool.assume_init())
+        }
+    }
+
+    /// Destroys a memory pool. Outstanding allocations are released once they are freed.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MALLOC__ASYNC.html)
+    ///
+    /// # Safety
+    /// 1. `pool` must have been created with [create()], not [get_default()].
+    /// 2. `pool` must not have been destroyed already.
+    pub unsafe fn destroy(pool: sys::CUmemoryPool) -> Result<(), DriverError> {
+        sys::cuMemPoolDestroy(pool).result()
+    }
+
+    /// Sets an attribute of a memory pool.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MALLOC__ASYNC.html)
+    ///
+    /// # Safety
+    /// 1. `pool` must be valid.
+    /// 2. `value` must point to the type expected by `attr`.
+    pub unsafe fn set_attribute(
+        pool: sys::CUmemoryPool,
+        attr: sys::CUmemPool_attribute,
+        value: *mut c_void,
+    ) -> Result<(), DriverError> {
+        sys::cuMemPoolSetAttribute(pool, attr, value).result()
+    }
+
+    /// Releases memory back to the OS until the pool holds less than `min_bytes_to_keep`.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MALLOC__ASYNC.html)
+    ///
+    /// # Safety
+    /// `pool` must be valid.
+    pub unsafe fn trim_to(
+        pool: sys::CUmemoryPool,
+        min_bytes_to_keep: usize,
+    ) -> Result<(), DriverError> {
+        sys::cuMemPoolTrimTo(pool, min_bytes_to_keep).result()
+    }
+
+    /// Allocates memory from `pool` with stream ordered semantics.
+    /// Free with [super::free_async()].
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MALLOC__ASYNC.html)
+    ///
+    /// # Safety
+    /// 1. The stream and pool should be valid.
+    /// 2. The memory return by this is unset, which may be invalid for `T`.
+    pub unsafe fn malloc_async(
+        stream: sys::CUstream,
+        num_bytes: usize,
+        pool: sys::CUmemoryPool,
+    ) -> Result<sys::CUdeviceptr, DriverError> {
+        let mut dev_ptr = MaybeUninit::uninit();
+        sys::cuMemAllocFromPoolAsync(dev_ptr.as_mut_ptr(), num_bytes, pool, stream).result()?;
+        Ok(dev_ptr.assume_init())
+    }
+}
+
 pub mod external_memory {
     use std::mem::MaybeUninit;
 
@@ -1237,6 +1897,318 @@ pub mod external_memory {
     }
 }
 
+pub mod external_semaphore {
+    use std::mem::MaybeUninit;
+
+    use super::{
+        sys::{self},
+        DriverError,
+    };
+
+    /// Imports an external semaphore object from a file descriptor. `type_` should be
+    /// either an `OPAQUE_FD` or a `TIMELINE_SEMAPHORE_FD` handle type.
+    ///
+    /// The semaphore should be destroyed using [`destroy_external_semaphore`].
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__EXTRES__INTEROP.html)
+    ///
+    /// # Safety
+    /// `fd` must be a valid file descriptor for a semaphore of type `type_`.
+    #[cfg(unix)]
+    pub unsafe fn import_external_semaphore_fd(
+        fd: std::os::fd::RawFd,
+        type_: sys::CUexternalSemaphoreHandleType,
+    ) -> Result<sys::CUexternalSemaphore, DriverError> {
+        let mut external_semaphore = MaybeUninit::uninit();
+        let handle_description = sys::CUDA_EXTERNAL_SEMAPHORE_HANDLE_DESC {
+            type_,
+            handle: sys::CUDA_EXTERNAL_SEMAPHORE_HANDLE_DESC_st__bindgen_ty_1 { fd },
+            ..Default::default()
+        };
+        sys::cuImportExternalSemaphore(external_semaphore.as_mut_ptr(), &handle_description)
+            .result()?;
+        Ok(external_semaphore.assume_init())
+    }
+
+    /// Imports an external semaphore object from a win32 handle. `type_` should be
+    /// either an `OPAQUE_WIN32` or a `TIMELINE_SEMAPHORE_WIN32` handle type.
+    ///
+    /// The semaphore should be destroyed using [`destroy_external_semaphore`].
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__EXTRES__INTEROP.html)
+    ///
+    /// # Safety
+    /// `handle` must be a valid handle for a semaphore of type `type_`.
+    #[cfg(windows)]
+    pub unsafe fn import_external_semaphore_win32(
+        handle: std::os::windows::io::RawHandle,
+        type_: sys::CUexternalSemaphoreHandleType,
+    ) -> Result<sys::CUexternalSemaphore, DriverError> {
+        let mut external_semaphore = MaybeUninit::uninit();
+        let handle_description = sys::CUDA_EXTERNAL_SEMAPHORE_HANDLE_DESC {
+            type_,
+            handle: sys::CUDA_EXTERNAL_SEMAPHORE_HANDLE_DESC_st__bindgen_ty_1 {
+                win32: sys::CUDA_EXTERNAL_SEMAPHORE_HANDLE_DESC_st__bindgen_ty_1__bindgen_ty_1 {
+                    handle,
+                    name: std::ptr::null(),
+                },
+            },
+            ..Default::default()
+        };
+        sys::cuImportExternalSemaphore(external_semaphore.as_mut_ptr(), &handle_description)
+            .result()?;
+        Ok(external_semaphore.assume_init())
+    }
+
+    /// Destroys an external semaphore object.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__EXTRES__INTEROP.html)
+    ///
+    /// # Safety
+    /// 1. All outstanding signals/waits on the semaphore must have completed.
+    /// 2. The external semaphore must only be destroyed once.
+    pub unsafe fn destroy_external_semaphore(
+        external_semaphore: sys::CUexternalSemaphore,
+    ) -> Result<(), DriverError> {
+        sys::cuDestroyExternalSemaphore(external_semaphore).result()
+    }
+
+    /// Signals an external semaphore with stream ordered semantics. `value` is
+    /// only used by timeline semaphores.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__EXTRES__INTEROP.html)
+    ///
+    /// # Safety
+    /// The semaphore and stream must be valid and not destroyed.
+    pub unsafe fn signal_async(
+        external_semaphore: sys::CUexternalSemaphore,
+        value: u64,
+        stream: sys::CUstream,
+    ) -> Result<(), DriverError> {
+        let mut params = sys::CUDA_EXTERNAL_SEMAPHORE_SIGNAL_PARAMS::default();
+        params.params.fence.value = value;
+        sys::cuSignalExternalSemaphoresAsync(&external_semaphore, &params, 1, stream).result()
+    }
+
+    /// Makes a stream wait on an external semaphore. `value` is only used by
+    /// timeline semaphores.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__EXTRES__INTEROP.html)
+    ///
+    /// # Safety
+    /// The semaphore and stream must be valid and not destroyed.
+    pub unsafe fn wait_async(
+        external_semaphore: sys::CUexternalSemaphore,
+        value: u64,
+        stream: sys::CUstream,
+    ) -> Result<(), DriverError> {
+        let mut params = sys::CUDA_EXTERNAL_SEMAPHORE_WAIT_PARAMS::default();
+        params.params.fence.value = value;
+        sys::cuWaitExternalSemaphoresAsync(&external_semaphore, &params, 1, stream).result()
+    }
+}
+
+pub mod link {
+    //! JIT linker functions (`cuLink*`).
+    //!
+    //! See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MODULE.html#group__CUDA__MODULE)
+
+    use super::{sys, DriverError};
+    use core::ffi::{c_void, CStr};
+    use std::mem::MaybeUninit;
+
+    /// Creates a pending JIT linker invocation.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MODULE.html)
+    ///
+    /// # Safety
+    /// 1. `options` and `values` must have the same length.
+    /// 2. Any buffers passed through `values` must outlive the returned link state.
+    pub unsafe fn create(
+        options: &mut [sys::CUjit_option],
+        values: &mut [*mut c_void],
+    ) -> Result<sys::CUlinkState, DriverError> {
+        assert_eq!(options.len(), values.len());
+        let mut state = MaybeUninit::uninit();
+        sys::cuLinkCreate_v2(
+            options.len() as _,
+            options.as_mut_ptr(),
+            values.as_mut_ptr(),
+            state.as_mut_ptr(),
+        )
+        .result()?;
+        Ok(state.assume_init())
+    }
+
+    /// Adds an input to a pending linker invocation. `name` is only used in log messages.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MODULE.html)
+    ///
+    /// # Safety
+    /// `state` must have been created by [create] and not yet destroyed.
+    pub unsafe fn add_data(
+        state: sys::CUlinkState,
+        input_type: sys::CUjitInputType,
+        data: &[u8],
+        name: &CStr,
+    ) -> Result<(), DriverError> {
+        sys::cuLinkAddData_v2(
+            state,
+            input_type,
+            data.as_ptr() as *mut _,
+            data.len(),
+            name.as_ptr(),
+            0,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+        .result()
+    }
+
+    /// Adds the file at `path` as input to a pending linker invocation.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MODULE.html)
+    ///
+    /// # Safety
+    /// `state` must have been created by [create] and not yet destroyed.
+    pub unsafe fn add_file(
+        state: sys::CUlinkState,
+        input_type: sys::CUjitInputType,
+        path: &CStr,
+    ) -> Result<(), DriverError> {
+        sys::cuLinkAddFile_v2(
+            state,
+            input_type,
+            path.as_ptr(),
+            0,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+        .result()
+    }
+
+    /// Completes a pending linker invocation, returning the linked cubin and its size in bytes.
+    /// The cubin is owned by `state`, and freed when it is destroyed.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MODULE.html)
+    ///
+    /// # Safety
+    /// `state` must have been created by [create] and not yet destroyed.
+    pub unsafe fn complete(state: sys::CUlinkState) -> Result<(*mut c_void, usize), DriverError> {
+        let mut cubin = MaybeUninit::uninit();
+        let mut size = MaybeUninit::uninit();
+        sys::cuLinkComplete(state, cubin.as_mut_ptr(), size.as_mut_ptr()).result()?;
+        Ok((cubin.assume_init(), size.assume_init()))
+    }
+
+    /// Destroys a linker invocation, including the cubin from [complete].
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MODULE.html)
+    ///
+    /// # Safety
+    /// `state` must have been created by [create] and not yet destroyed.
+    pub unsafe fn destroy(state: sys::CUlinkState) -> Result<(), DriverError> {
+        sys::cuLinkDestroy(state).result()
+    }
+}
+
+pub mod array {
+    //! CUDA array management functions (`cuArray*`).
+    //!
+    //! See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html)
+
+    use super::{sys, DriverError};
+    use std::mem::MaybeUninit;
+
+    /// Allocates a 1D, 2D or 3D CUDA array. Unused dimensions of `desc` are 0.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html)
+    pub fn create(desc: &sys::CUDA_ARRAY3D_DESCRIPTOR) -> Result<sys::CUarray, DriverError> {
+        let mut array = MaybeUninit::uninit();
+        unsafe {
+            sys::cuArray3DCreate_v2(array.as_mut_ptr(), desc).result()?;
+            Ok(array.assume_init())
+        }
+    }
+
+    /// Frees a CUDA array.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html)
+    ///
+    /// # Safety
+    /// `array` must have been created by [create], not yet destroyed, and no pending work may use it.
+    pub unsafe fn destroy(array: sys::CUarray) -> Result<(), DriverError> {
+        sys::cuArrayDestroy(array).result()
+    }
+}
+
+pub mod surface {
+    //! Surface object management functions (`cuSurfObject*`).
+    //!
+    //! See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__SURFOBJECT.html)
+
+    use super::{sys, DriverError};
+    use std::mem::MaybeUninit;
+
+    /// Creates a surface object over the array described by `res_desc`.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__SURFOBJECT.html)
+    ///
+    /// # Safety
+    /// The array in `res_desc` must be valid, and outlive the surface object.
+    pub unsafe fn create(
+        res_desc: &sys::CUDA_RESOURCE_DESC,
+    ) -> Result<sys::CUsurfObject, DriverError> {
+        let mut surf = MaybeUninit::uninit();
+        sys::cuSurfObjectCreate(surf.as_mut_ptr(), res_desc).result()?;
+        Ok(surf.assume_init())
+    }
+
+    /// Destroys a surface object.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__SURFOBJECT.html)
+    ///
+    /// # Safety
+    /// `surf` must have been created by [create] and not yet destroyed.
+    pub unsafe fn destroy(surf: sys::CUsurfObject) -> Result<(), DriverError> {
+        sys::cuSurfObjectDestroy(surf).result()
+    }
+}
+
+pub mod texture {
+    //! Texture object management functions (`cuTexObject*`).
+    //!
+    //! See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__TEXOBJECT.html)
+
+    use super::{sys, DriverError};
+    use std::mem::MaybeUninit;
+
+    /// Creates a texture object reading from the resource described by `res_desc`.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__TEXOBJECT.html)
+    ///
+    /// # Safety
+    /// The memory described by `res_desc` must be valid, and outlive the texture object.
+    pub unsafe fn create(
+        res_desc: &sys::CUDA_RESOURCE_DESC,
+        tex_desc: &sys::CUDA_TEXTURE_DESC,
+    ) -> Result<sys::CUtexObject, DriverError> {
+        let mut tex = MaybeUninit::uninit();
+        sys::cuTexObjectCreate(tex.as_mut_ptr(), res_desc, tex_desc, std::ptr::null()).result()?;
+        Ok(tex.assume_init())
+    }
+
+    /// Destroys a texture object.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__TEXOBJECT.html)
+    ///
+    /// # Safety
+    /// `tex` must have been created by [create] and not yet destroyed.
+    pub unsafe fn destroy(tex: sys::CUtexObject) -> Result<(), DriverError> {
+        sys::cuTexObjectDestroy(tex).result()
+    }
+}
+
 pub mod graph {
     use super::*;
 
@@ -1246,10 +2218,22 @@ pub mod graph {
     pub unsafe fn instantiate(
         graph: sys::CUgraph,
         flags: sys::CUgraphInstantiate_flags,
+    ) -> Result<sys::CUgraphExec, DriverError> {
+        instantiate_with_raw_flags(graph, flags as u32 as u64)
+    }
+
+    /// Like [instantiate()], but takes the flags as a bitmask of [sys::CUgraphInstantiate_flags],
+    /// e.g. `0` for no flags.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__GRAPH.html#group__CUDA__GRAPH_1gb53b435e178cccfa37ac87285d2c3fa1)
+    /// # Safety
+    /// graph must be valid
+    pub unsafe fn instantiate_with_raw_flags(
+        graph: sys::CUgraph,
+        flags: u64,
     ) -> Result<sys::CUgraphExec, DriverError> {
         let mut graph_exec = MaybeUninit::uninit();
-        sys::cuGraphInstantiateWithFlags(graph_exec.as_mut_ptr(), graph, flags as u32 as u64)
-            .result()?;
+        sys::cuGraphInstantiateWithFlags(graph_exec.as_mut_ptr(), graph, flags).result()?;
         Ok(graph_exec.assume_init())
     }
 
@@ -1276,4 +2260,165 @@ pub mod graph {
     ) -> Result<(), DriverError> {
         sys::cuGraphLaunch(graph_exec, stream).result()
     }
+
+    /// Creates an empty graph.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__GRAPH.html)
+    pub fn create() -> Result<sys::CUgraph, DriverError> {
+        let mut graph = MaybeUninit::uninit();
+        unsafe {
+            sys::cuGraphCreate(graph.as_mut_ptr(), 0).result()?;
+            Ok(graph.assume_init())
+        }
+    }
+
+    /// Adds a kernel node that depends on `dependencies`.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__GRAPH.html)
+    /// # Safety
+    /// 1. graph and all dependencies must be valid, and the dependencies must belong to graph.
+    /// 2. The kernel params must match the kernel's signature.
+    pub unsafe fn add_kernel_node(
+        graph: sys::CUgraph,
+        dependencies: &[sys::CUgraphNode],
+        params: &sys::CUDA_KERNEL_NODE_PARAMS,
+    ) -> Result<sys::CUgraphNode, DriverError> {
+        let mut node = MaybeUninit::uninit();
+        #[cfg(any(
+            feature = "cuda-11040",
+            feature = "cuda-11050",
+            feature = "cuda-11060",
+            feature = "cuda-11070",
+            feature = "cuda-11080"
+        ))]
+        let result = sys::cuGraphAddKernelNode(
+            node.as_mut_ptr(),
+            graph,
+            dependencies.as_ptr(),
+            dependencies.len(),
+            params,
+        );
+        #[cfg(not(any(
+            feature = "cuda-11040",
+            feature = "cuda-11050",
+            feature = "cuda-11060",
+            feature = "cuda-11070",
+            feature = "cuda-11080"
+        )))]
+        let result = sys::cuGraphAddKernelNode_v2(
+            node.as_mut_ptr(),
+            graph,
+            dependencies.as_ptr(),
+            dependencies.len(),
+            params,
+        );
+        result.result()?;
+        Ok(node.assume_init())
+    }
+
+    /// Adds a memcpy node that depends on `dependencies`.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__GRAPH.html)
+    /// # Safety
+    /// 1. graph and all dependencies must be valid, and the dependencies must belong to graph.
+    /// 2. The memory described by params must be valid whenever the graph is launched.
+    pub unsafe fn add_memcpy_node(
+        graph: sys::CUgraph,
+        dependencies: &[sys::CUgraphNode],
+        params: &sys::CUDA_MEMCPY3D,
+        ctx: sys::CUcontext,
+    ) -> Result<sys::CUgraphNode, DriverError> {
+        let mut node = MaybeUninit::uninit();
+        sys::cuGraphAddMemcpyNode(
+            node.as_mut_ptr(),
+            graph,
+            dependencies.as_ptr(),
+            dependencies.len(),
+            params,
+            ctx,
+        )
+        .result()?;
+        Ok(node.assume_init())
+    }
+
+    /// Adds a memset node that depends on `dependencies`.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__GRAPH.html)
+    /// # Safety
+    /// 1. graph and all dependencies must be valid, and the dependencies must belong to graph.
+    /// 2. The memory described by params must be valid whenever the graph is launched.
+    pub unsafe fn add_memset_node(
+        graph: sys::CUgraph,
+        dependencies: &[sys::CUgraphNode],
+        params: &sys::CUDA_MEMSET_NODE_PARAMS,
+        ctx: sys::CUcontext,
+    ) -> Result<sys::CUgraphNode, DriverError> {
+        let mut node = MaybeUninit::uninit();
+        sys::cuGraphAddMemsetNode(
+            node.as_mut_ptr(),
+            graph,
+            dependencies.as_ptr(),
+            dependencies.len(),
+            params,
+            ctx,
+        )
+        .result()?;
+        Ok(node.assume_init())
+    }
+
+    /// Returns all the nodes of the graph.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__GRAPH.html)
+    /// # Safety
+    /// graph must be valid
+    pub unsafe fn get_nodes(graph: sys::CUgraph) -> Result<Vec<sys::CUgraphNode>, DriverError> {
+        let mut num_nodes = 0;
+        sys::cuGraphGetNodes(graph, std::ptr::null_mut(), &mut num_nodes).result()?;
+        let mut nodes = vec![std::ptr::null_mut(); num_nodes];
+        sys::cuGraphGetNodes(graph, nodes.as_mut_ptr(), &mut num_nodes).result()?;
+        nodes.truncate(num_nodes);
+        Ok(nodes)
+    }
+
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__GRAPH.html)
+    /// # Safety
+    /// node must be valid
+    pub unsafe fn node_get_type(
+        node: sys::CUgraphNode,
+    ) -> Result<sys::CUgraphNodeType, DriverError> {
+        let mut node_type = MaybeUninit::uninit();
+        sys::cuGraphNodeGetType(node, node_type.as_mut_ptr()).result()?;
+        Ok(node_type.assume_init())
+    }
+
+    /// Updates the parameters of a kernel node in an instantiated graph, without changing the graph it was
+    /// instantiated from.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__GRAPH.html)
+    /// # Safety
+    /// 1. graph_exec must be valid, and node must be a kernel node of the graph it was instantiated from.
+    /// 2. The kernel params must match the kernel's signature.
+    pub unsafe fn exec_kernel_node_set_params(
+        graph_exec: sys::CUgraphExec,
+        node: sys::CUgraphNode,
+        params: &sys::CUDA_KERNEL_NODE_PARAMS,
+    ) -> Result<(), DriverError> {
+        #[cfg(any(
+            feature = "cuda-11040",
+            feature = "cuda-11050",
+            feature = "cuda-11060",
+            feature = "cuda-11070",
+            feature = "cuda-11080"
+        ))]
+        let result = sys::cuGraphExecKernelNodeSetParams(graph_exec, node, params);
+        #[cfg(not(any(
+            feature = "cuda-11040",
+            feature = "cuda-11050",
+            feature = "cuda-11060",
+            feature = "cuda-11070",
+            feature = "cuda-11080"
+        )))]
+        let result = sys::cuGraphExecKernelNodeSetParams_v2(graph_exec, node, params);
+        result.result()
+    }
 }
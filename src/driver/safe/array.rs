@@ -0,0 +1,247 @@
+use std::sync::Arc;
+
+use super::{CudaContext, CudaEvent, CudaStream, DeviceRepr, HostSlice};
+use crate::driver::{result, sys, DriverError};
+
+/// The shape and element format of a [CudaArray], see [CudaContext::alloc_array()].
+///
+/// A 1D array has `height` and `depth` 0, and a 2D array has `depth` 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ArrayDescriptor {
+    pub width: usize,
+    pub height: usize,
+    pub depth: usize,
+    /// The type of each channel. Arrays of 8, 16 and 32 bit integers, [sys::CUarray_format::CU_AD_FORMAT_HALF]
+    /// and [sys::CUarray_format::CU_AD_FORMAT_FLOAT] are supported.
+    pub format: sys::CUarray_format,
+    /// Either 1, 2 or 4.
+    pub num_channels: u32,
+    /// A combination of the `sys::CUDA_ARRAY3D_*` flags, e.g. [sys::CUDA_ARRAY3D_SURFACE_LDST].
+    pub flags: u32,
+}
+
+impl ArrayDescriptor {
+    /// The number of bytes of each element, or `None` for formats that are not supported.
+    pub fn element_size(&self) -> Option<usize> {
+        let channel_size = match self.format {
+            sys::CUarray_format::CU_AD_FORMAT_UNSIGNED_INT8
+            | sys::CUarray_format::CU_AD_FORMAT_SIGNED_INT8 => 1,
+            sys::CUarray_format::CU_AD_FORMAT_UNSIGNED_INT16
+            | sys::CUarray_format::CU_AD_FORMAT_SIGNED_INT16
+            | sys::CUarray_format::CU_AD_FORMAT_HALF => 2,
+            sys::CUarray_format::CU_AD_FORMAT_UNSIGNED_INT32
+            | sys::CUarray_format::CU_AD_FORMAT_SIGNED_INT32
+            | sys::CUarray_format::CU_AD_FORMAT_FLOAT => 4,
+            _ => return None,
+        };
+        Some(channel_size * self.num_channels as usize)
+    }
+
+    /// The total number of elements.
+    pub fn len(&self) -> usize {
+        self.width * self.height.max(1) * self.depth.max(1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl From<ArrayDescriptor> for sys::CUDA_ARRAY3D_DESCRIPTOR {
+    fn from(desc: ArrayDescriptor) -> Self {
+        sys::CUDA_ARRAY3D_DESCRIPTOR {
+            Width: desc.width,
+            Height: desc.height,
+            Depth: desc.depth,
+            Format: desc.format,
+            NumChannels: desc.num_channels,
+            Flags: desc.flags,
+        }
+    }
+}
+
+/// An opaque 1D, 2D or 3D CUDA array (`CUarray`), the memory layout used by textures
+/// and surfaces. Create with [CudaContext::alloc_array()].
+///
+/// The layout in memory is chosen by the driver to optimize 2D/3D locality, so the data can
+/// only be accessed with [CudaStream::memcpy_htoa()]/[CudaStream::memcpy_atoh()], or in kernels
+/// through a texture or surface object.
+///
+/// Work using the array is tracked with an event, which is synchronized before freeing
+/// the array on drop.
+#[derive(Debug)]
+pub struct CudaArray {
+    pub(crate) cu_array: sys::CUarray,
+    pub(crate) desc: ArrayDescriptor,
+    pub(crate) event: CudaEvent,
+    pub(crate) ctx: Arc<CudaContext>,
+}
+
+unsafe impl Send for CudaArray {}
+unsafe impl Sync for CudaArray {}
+
+impl Drop for CudaArray {
+    fn drop(&mut self) {
+        let ctx = &self.ctx;
+        ctx.record_err(self.event.synchronize());
+        ctx.record_err(ctx.bind_to_thread());
+        ctx.record_err(unsafe { result::array::destroy(self.cu_array) });
+    }
+}
+
+impl CudaContext {
+    /// Allocates a [CudaArray]. The contents are uninitialized.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html)
+    pub fn alloc_array(self: &Arc<Self>, desc: ArrayDescriptor) -> Result<CudaArray, DriverError> {
+        self.bind_to_thread()?;
+        let event = self.new_event(Some(sys::CUevent_flags::CU_EVENT_DISABLE_TIMING))?;
+        let cu_array = result::array::create(&desc.into())?;
+        Ok(CudaArray {
+            cu_array,
+            desc,
+            event,
+            ctx: self.clone(),
+        })
+    }
+}
+
+impl CudaArray {
+    /// The descriptor this array was allocated with.
+    pub fn descriptor(&self) -> &ArrayDescriptor {
+        &self.desc
+    }
+
+    /// The underlying [sys::CUarray].
+    ///
+    /// # Safety
+    /// While this function is marked as safe, actually using the
+    /// returned object is unsafe.
+    ///
+    /// **You must not free/release the array**, as it is still owned by the [CudaArray].
+    pub fn cu_array(&self) -> sys::CUarray {
+        self.cu_array
+    }
+
+    /// Parameters to copy the whole array, with `WidthInBytes`/`Height`/`Depth` set
+    /// and the host side left to fill in. Returns `None` for unsupported formats.
+    fn memcpy_3d_params(&self) -> Option<sys::CUDA_MEMCPY3D> {
+        let width_in_bytes = self.desc.width * self.desc.element_size()?;
+        Some(sys::CUDA_MEMCPY3D {
+            srcXInBytes: 0,
+            srcY: 0,
+            srcZ: 0,
+            srcLOD: 0,
+            srcMemoryType: sys::CUmemorytype::CU_MEMORYTYPE_HOST,
+            srcHost: std::ptr::null(),
+            srcDevice: 0,
+            srcArray: std::ptr::null_mut(),
+            reserved0: std::ptr::null_mut(),
+            srcPitch: width_in_bytes,
+            srcHeight: self.desc.height.max(1),
+            dstXInBytes: 0,
+            dstY: 0,
+            dstZ: 0,
+            dstLOD: 0,
+            dstMemoryType: sys::CUmemorytype::CU_MEMORYTYPE_HOST,
+            dstHost: std::ptr::null_mut(),
+            dstDevice: 0,
+            dstArray: std::ptr::null_mut(),
+            reserved1: std::ptr::null_mut(),
+            dstPitch: width_in_bytes,
+            dstHeight: self.desc.height.max(1),
+            WidthInBytes: width_in_bytes,
+            Height: self.desc.height.max(1),
+            Depth: self.desc.depth.max(1),
+        })
+    }
+}
+
+impl CudaStream {
+    /// Copies tightly packed host data into all of `dst`, in row-major order
+    /// (`x` fastest, then `y`, then `z`).
+    ///
+    /// Returns [sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE] if `src` is not exactly as many
+    /// bytes as `dst`, or the format of `dst` is not supported.
+    pub fn memcpy_htoa<T: DeviceRepr, Src: HostSlice<T> + ?Sized>(
+        self: &Arc<Self>,
+        src: &Src,
+        dst: &mut CudaArray,
+    ) -> Result<(), DriverError> {
+        self.bind_to_thread()?;
+        let mut params = dst
+            .memcpy_3d_params()
+            .ok_or(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE))?;
+        if src.len() * std::mem::size_of::<T>() != dst.desc.len() * dst.desc.element_size().unwrap()
+        {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE));
+        }
+        self.wait(&dst.event)?;
+        let (src, _record_src) = unsafe { src.stream_synced_slice(self) };
+        params.srcHost = src.as_ptr() as *const _;
+        params.dstMemoryType = sys::CUmemorytype::CU_MEMORYTYPE_ARRAY;
+        params.dstArray = dst.cu_array;
+        unsafe { result::memcpy_3d_async(&params, self.cu_stream) }?;
+        dst.event.record(self)
+    }
+
+    /// Copies all of `src` into tightly packed host memory, in row-major order
+    /// (`x` fastest, then `y`, then `z`).
+    ///
+    /// Returns [sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE] if `dst` is not exactly as many
+    /// bytes as `src`, or the format of `src` is not supported.
+    pub fn memcpy_atoh<T: DeviceRepr, Dst: HostSlice<T> + ?Sized>(
+        self: &Arc<Self>,
+        src: &CudaArray,
+        dst: &mut Dst,
+    ) -> Result<(), DriverError> {
+        self.bind_to_thread()?;
+        let mut params = src
+            .memcpy_3d_params()
+            .ok_or(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE))?;
+        if dst.len() * std::mem::size_of::<T>() != src.desc.len() * src.desc.element_size().unwrap()
+        {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE));
+        }
+        self.wait(&src.event)?;
+        let (dst, _record_dst) = unsafe { dst.stream_synced_mut_slice(self) };
+        params.srcMemoryType = sys::CUmemorytype::CU_MEMORYTYPE_ARRAY;
+        params.srcArray = src.cu_array;
+        params.dstHost = dst.as_mut_ptr() as *mut _;
+        unsafe { result::memcpy_3d_async(&params, self.cu_stream) }?;
+        src.event.record(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_array_round_trip() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+
+        for (width, height, depth) in [(100, 0, 0), (7, 5, 0), (4, 3, 2)] {
+            let desc = ArrayDescriptor {
+                width,
+                height,
+                depth,
+                format: sys::CUarray_format::CU_AD_FORMAT_FLOAT,
+                num_channels: 2,
+                flags: 0,
+            };
+            assert_eq!(desc.element_size(), Some(8));
+            let mut array = ctx.alloc_array(desc).unwrap();
+
+            let src: Vec<[f32; 2]> = (0..desc.len()).map(|i| [i as f32, -(i as f32)]).collect();
+            stream.memcpy_htoa(&src, &mut array).unwrap();
+            let mut dst = vec![[0.0f32; 2]; desc.len()];
+            stream.memcpy_atoh(&array, &mut dst).unwrap();
+            stream.synchronize().unwrap();
+            assert_eq!(src, dst);
+
+            assert!(stream.memcpy_htoa(&src[1..], &mut array).is_err());
+        }
+    }
+}
@@ -4,15 +4,19 @@ use crate::driver::{
 };
 
 use std::{
+    boxed::Box,
     ffi::CString,
     marker::PhantomData,
-    ops::{Bound, RangeBounds},
+    ops::{Bound, Range, RangeBounds},
     string::String,
     sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering},
     sync::Arc,
     vec::Vec,
 };
 
+/// The byte that guard regions are filled with, see [CudaContext::set_alloc_guard_bytes()].
+pub const ALLOC_GUARD_SENTINEL: u8 = 0xAB;
+
 /// Represents a primary cuda context on a certain device. When created with [CudaContext::new()] it will
 /// push a new primary context onto the stack.
 ///
@@ -23,6 +27,9 @@ use std::{
 ///
 /// This object is thread safe and can be shared/used on multiple threads. All safe apis call
 /// [CudaContext::bind_to_thread()] before doing work in a certain context.
+///
+/// Code that uses raw handles (e.g. [CudaStream::cu_stream()] with another library) must call
+/// [CudaContext::bind_to_thread()] itself, since the thread may have changed since the last cudarc call.
 #[derive(Debug)]
 pub struct CudaContext {
     pub(crate) cu_device: sys::CUdevice,
@@ -31,6 +38,7 @@ pub struct CudaContext {
     pub(crate) has_async_alloc: bool,
     pub(crate) num_streams: AtomicUsize,
     pub(crate) event_tracking: AtomicBool,
+    pub(crate) alloc_guard: AtomicUsize,
     pub(crate) error_state: AtomicU32,
 }
 
@@ -56,8 +64,178 @@ impl PartialEq for CudaContext {
 }
 impl Eq for CudaContext {}
 
+/// Commonly used device attributes for [CudaContext::attribute()]. Anything not listed
+/// here can be queried by passing a [sys::CUdevice_attribute] directly.
+///
+/// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__DEVICE.html)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeviceAttribute {
+    /// Major compute capability version number.
+    ComputeCapabilityMajor,
+    /// Minor compute capability version number.
+    ComputeCapabilityMinor,
+    /// Number of streaming multiprocessors (SMs).
+    MultiprocessorCount,
+    /// Warp size in threads.
+    WarpSize,
+    /// Maximum number of threads per block.
+    MaxThreadsPerBlock,
+    /// Maximum resident threads per multiprocessor.
+    MaxThreadsPerMultiprocessor,
+    /// Maximum x dimension of a block.
+    MaxBlockDimX,
+    /// Maximum y dimension of a block.
+    MaxBlockDimY,
+    /// Maximum z dimension of a block.
+    MaxBlockDimZ,
+    /// Maximum x dimension of a grid.
+    MaxGridDimX,
+    /// Maximum y dimension of a grid.
+    MaxGridDimY,
+    /// Maximum z dimension of a grid.
+    MaxGridDimZ,
+    /// Maximum number of 32-bit registers available per block.
+    MaxRegistersPerBlock,
+    /// Maximum shared memory per block in bytes, without opting in.
+    MaxSharedMemoryPerBlock,
+    /// Maximum shared memory per block in bytes when opted in with [CudaFunction::set_max_dynamic_shared_size()].
+    MaxSharedMemoryPerBlockOptin,
+    /// Maximum shared memory per multiprocessor in bytes.
+    MaxSharedMemoryPerMultiprocessor,
+    /// Constant memory available in bytes.
+    TotalConstantMemory,
+    /// Size of the L2 cache in bytes.
+    L2CacheSize,
+    /// Peak clock frequency in kilohertz.
+    ClockRate,
+    /// Peak memory clock frequency in kilohertz.
+    MemoryClockRate,
+    /// Global memory bus width in bits.
+    GlobalMemoryBusWidth,
+    /// Number of asynchronous copy engines.
+    AsyncEngineCount,
+    /// PCI bus id of the device.
+    PciBusId,
+    /// PCI device id of the device.
+    PciDeviceId,
+    /// PCI domain id of the device.
+    PciDomainId,
+    /// 1 if the device is an integrated GPU sharing memory with the host.
+    Integrated,
+    /// 1 if ECC is enabled.
+    EccEnabled,
+    /// 1 if the device shares a unified address space with the host.
+    UnifiedAddressing,
+    /// 1 if the device can run multiple kernels concurrently.
+    ConcurrentKernels,
+    /// 1 if the device supports [crate::driver::LaunchArgs::launch_cooperative()].
+    CooperativeLaunch,
+    /// 1 if the device supports unified (managed) memory.
+    ManagedMemory,
+    /// 1 if the device can access managed memory concurrently with the host.
+    ConcurrentManagedAccess,
+    /// 1 if the device supports stream ordered allocations and memory pools.
+    MemoryPoolsSupported,
+}
+
+impl From<DeviceAttribute> for sys::CUdevice_attribute {
+    fn from(attr: DeviceAttribute) -> Self {
+        use sys::CUdevice_attribute::*;
+        match attr {
+            DeviceAttribute::ComputeCapabilityMajor => CU_DEVICE_ATTRIBUTE_COMPUTE_CAPABILITY_MAJOR,
+            DeviceAttribute::ComputeCapabilityMinor => CU_DEVICE_ATTRIBUTE_COMPUTE_CAPABILITY_MINOR,
+            DeviceAttribute::MultiprocessorCount => CU_DEVICE_ATTRIBUTE_MULTIPROCESSOR_COUNT,
+            DeviceAttribute::WarpSize => CU_DEVICE_ATTRIBUTE_WARP_SIZE,
+            DeviceAttribute::MaxThreadsPerBlock => CU_DEVICE_ATTRIBUTE_MAX_THREADS_PER_BLOCK,
+            DeviceAttribute::MaxThreadsPerMultiprocessor => {
+                CU_DEVICE_ATTRIBUTE_MAX_THREADS_PER_MULTIPROCESSOR
+            }
+            DeviceAttribute::MaxBlockDimX => CU_DEVICE_ATTRIBUTE_MAX_BLOCK_DIM_X,
+            DeviceAttribute::MaxBlockDimY => CU_DEVICE_ATTRIBUTE_MAX_BLOCK_DIM_Y,
+            DeviceAttribute::MaxBlockDimZ => CU_DEVICE_ATTRIBUTE_MAX_BLOCK_DIM_Z,
+            DeviceAttribute::MaxGridDimX => CU_DEVICE_ATTRIBUTE_MAX_GRID_DIM_X,
+            DeviceAttribute::MaxGridDimY => CU_DEVICE_ATTRIBUTE_MAX_GRID_DIM_Y,
+            DeviceAttribute::MaxGridDimZ => CU_DEVICE_ATTRIBUTE_MAX_GRID_DIM_Z,
+            DeviceAttribute::MaxRegistersPerBlock => CU_DEVICE_ATTRIBUTE_MAX_REGISTERS_PER_BLOCK,
+            DeviceAttribute::MaxSharedMemoryPerBlock => {
+                CU_DEVICE_ATTRIBUTE_MAX_SHARED_MEMORY_PER_BLOCK
+            }
+            DeviceAttribute::MaxSharedMemoryPerBlockOptin => {
+                CU_DEVICE_ATTRIBUTE_MAX_SHARED_MEMORY_PER_BLOCK_OPTIN
+            }
+            DeviceAttribute::MaxSharedMemoryPerMultiprocessor => {
+                CU_DEVICE_ATTRIBUTE_MAX_SHARED_MEMORY_PER_MULTIPROCESSOR
+            }
+            DeviceAttribute::TotalConstantMemory => CU_DEVICE_ATTRIBUTE_TOTAL_CONSTANT_MEMORY,
+            DeviceAttribute::L2CacheSize => CU_DEVICE_ATTRIBUTE_L2_CACHE_SIZE,
+            DeviceAttribute::ClockRate => CU_DEVICE_ATTRIBUTE_CLOCK_RATE,
+            DeviceAttribute::MemoryClockRate => CU_DEVICE_ATTRIBUTE_MEMORY_CLOCK_RATE,
+            DeviceAttribute::GlobalMemoryBusWidth => CU_DEVICE_ATTRIBUTE_GLOBAL_MEMORY_BUS_WIDTH,
+            DeviceAttribute::AsyncEngineCount => CU_DEVICE_ATTRIBUTE_ASYNC_ENGINE_COUNT,
+            DeviceAttribute::PciBusId => CU_DEVICE_ATTRIBUTE_PCI_BUS_ID,
+            DeviceAttribute::PciDeviceId => CU_DEVICE_ATTRIBUTE_PCI_DEVICE_ID,
+            DeviceAttribute::PciDomainId => CU_DEVICE_ATTRIBUTE_PCI_DOMAIN_ID,
+            DeviceAttribute::Integrated => CU_DEVICE_ATTRIBUTE_INTEGRATED,
+            DeviceAttribute::EccEnabled => CU_DEVICE_ATTRIBUTE_ECC_ENABLED,
+            DeviceAttribute::UnifiedAddressing => CU_DEVICE_ATTRIBUTE_UNIFIED_ADDRESSING,
+            DeviceAttribute::ConcurrentKernels => CU_DEVICE_ATTRIBUTE_CONCURRENT_KERNELS,
+            DeviceAttribute::CooperativeLaunch => CU_DEVICE_ATTRIBUTE_COOPERATIVE_LAUNCH,
+            DeviceAttribute::ManagedMemory => CU_DEVICE_ATTRIBUTE_MANAGED_MEMORY,
+            DeviceAttribute::ConcurrentManagedAccess => {
+                CU_DEVICE_ATTRIBUTE_CONCURRENT_MANAGED_ACCESS
+            }
+            DeviceAttribute::MemoryPoolsSupported => CU_DEVICE_ATTRIBUTE_MEMORY_POOLS_SUPPORTED,
+        }
+    }
+}
+
+/// Flags for [CudaContext::new_with_flags()]. Combine a scheduling flag with
+/// [ContextFlags::MAP_HOST] using `|`.
+///
+/// The scheduling flags control what the host thread does while it waits on the GPU
+/// (e.g. in [CudaStream::synchronize()]):
+/// - [ContextFlags::SPIN_SYNC] busy waits, lowest latency but uses a whole CPU core.
+/// - [ContextFlags::YIELD_SYNC] spins but yields the thread to other threads.
+/// - [ContextFlags::BLOCKING_SYNC] blocks the thread on a synchronization primitive.
+/// - [ContextFlags::AUTO] lets the driver pick based on the number of active contexts
+///   and logical processors.
+///
+/// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__CTX.html#group__CUDA__CTX_1g65dc0012348bc84810e2103a40d8e2cf)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ContextFlags(u32);
+
+impl ContextFlags {
+    pub const AUTO: Self = Self(sys::CUctx_flags::CU_CTX_SCHED_AUTO as u32);
+    pub const SPIN_SYNC: Self = Self(sys::CUctx_flags::CU_CTX_SCHED_SPIN as u32);
+    pub const YIELD_SYNC: Self = Self(sys::CUctx_flags::CU_CTX_SCHED_YIELD as u32);
+    pub const BLOCKING_SYNC: Self = Self(sys::CUctx_flags::CU_CTX_SCHED_BLOCKING_SYNC as u32);
+    /// Allows mapping pinned host memory into the device address space.
+    pub const MAP_HOST: Self = Self(sys::CUctx_flags::CU_CTX_MAP_HOST as u32);
+
+    /// The raw [sys::CUctx_flags] bits.
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+
+    /// Whether all the bits in `other` are set in `self`.
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl std::ops::BitOr for ContextFlags {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
 impl CudaContext {
     /// Creates a new context on the specified device ordinal.
+    ///
+    /// This retains the device's primary context (`cuDevicePrimaryCtxRetain`) rather than creating
+    /// a new one, so it is the same context used by the CUDA runtime and by other frameworks
+    /// in the process. Each [CudaContext] holds one reference, which is released on drop.
     pub fn new(ordinal: usize) -> Result<Arc<Self>, DriverError> {
         result::init()?;
         let cu_device = result::device::get(ordinal as i32)?;
@@ -76,12 +254,68 @@ impl CudaContext {
             has_async_alloc,
             num_streams: AtomicUsize::new(0),
             event_tracking: AtomicBool::new(true),
+            alloc_guard: AtomicUsize::new(0),
             error_state: AtomicU32::new(0),
         });
         ctx.bind_to_thread()?;
         Ok(ctx)
     }
 
+    /// Retains the primary context of the device. This is the same as [CudaContext::new()], and
+    /// exists to make the intent explicit when interoperating with other CUDA libraries
+    /// (e.g. PyTorch) that use the primary context.
+    ///
+    /// Retains are reference counted by the driver: the primary context stays alive as long as
+    /// any [CudaContext] (or other library) holds a reference to it.
+    pub fn primary(ordinal: usize) -> Result<Arc<Self>, DriverError> {
+        Self::new(ordinal)
+    }
+
+    /// Resets the device's primary context, destroying all of its allocations and state, and
+    /// returns a freshly retained context. This can be used to recover from sticky errors
+    /// (e.g. [DriverError::is_context_destroyed()] or an ECC failure) without restarting the process.
+    ///
+    /// Returns `CUDA_ERROR_CONTEXT_ALREADY_IN_USE` if there are other references to this
+    /// [Arc] (including any [CudaStream], [CudaSlice], [CudaModule] etc. created from it).
+    /// This can't see other [CudaContext]s for the same device though, see the safety section.
+    ///
+    /// Note that the primary context is shared by the whole process, so this also resets it
+    /// for any other library using the same device.
+    ///
+    /// # Safety
+    /// No other [CudaContext] for this device (e.g. from another call to [CudaContext::new()] or
+    /// [CudaContext::primary()]), or any resource created from one, may be alive. The reset
+    /// destroys everything in the primary context, so they would be left pointing at freed state.
+    pub unsafe fn reset(self: Arc<Self>) -> Result<Arc<Self>, DriverError> {
+        let ctx = Arc::try_unwrap(self)
+            .map_err(|_| DriverError(sys::cudaError_enum::CUDA_ERROR_CONTEXT_ALREADY_IN_USE))?;
+        let ordinal = ctx.ordinal;
+        let cu_device = ctx.cu_device;
+        // release our reference before resetting, so the driver's reference count stays balanced.
+        drop(ctx);
+        unsafe { result::primary_ctx::reset(cu_device) }?;
+        Self::new(ordinal)
+    }
+
+    /// Like [CudaContext::new()], but first sets the `flags` of the device's primary context.
+    ///
+    /// The primary context is shared with everything else in the process using the same device,
+    /// so this changes the flags for all of them. [CudaContext::new()] leaves the flags untouched,
+    /// which is [ContextFlags::AUTO] unless they were set elsewhere.
+    pub fn new_with_flags(ordinal: usize, flags: ContextFlags) -> Result<Arc<Self>, DriverError> {
+        result::init()?;
+        let cu_device = result::device::get(ordinal as i32)?;
+        unsafe { result::primary_ctx::set_flags(cu_device, flags.bits()) }?;
+        Self::new(ordinal)
+    }
+
+    /// The flags of the device's primary context. See [CudaContext::new_with_flags()].
+    pub fn flags(&self) -> Result<ContextFlags, DriverError> {
+        self.check_err()?;
+        let (flags, _active) = unsafe { result::primary_ctx::get_state(self.cu_device) }?;
+        Ok(ContextFlags(flags))
+    }
+
     /// The number of devices available.
     pub fn device_count() -> Result<i32, DriverError> {
         result::init()?;
@@ -99,10 +333,13 @@ impl CudaContext {
         result::device::get_name(self.cu_device)
     }
 
-    /// Get the UUID of this device.
-    pub fn uuid(&self) -> Result<sys::CUuuid, result::DriverError> {
+    /// Get the 16 byte UUID of this device. This is the same UUID that `nvidia-smi -L` reports
+    /// (formatted there as `GPU-xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx` in lowercase hex), so unlike
+    /// [CudaContext::ordinal()] it does not depend on `CUDA_VISIBLE_DEVICES`.
+    pub fn uuid(&self) -> Result<[u8; 16], result::DriverError> {
         self.check_err()?;
-        result::device::get_uuid(self.cu_device)
+        let uuid = result::device::get_uuid(self.cu_device)?;
+        Ok(uuid.bytes.map(|b| b as u8))
     }
 
     /// Get the underlying [sys::CUdevice] of this [CudaContext].
@@ -124,7 +361,9 @@ impl CudaContext {
     /// returned object is unsafe.
     ///
     /// **You must not free/release the context pointer**, as it is still
-    /// owned by the [CudaContext].
+    /// owned by the [CudaContext]. This is the device's primary context, so external code should
+    /// make it current (or call [CudaContext::bind_to_thread()]) rather than push/pop or destroy it,
+    /// and must keep the [CudaContext] alive for as long as it uses the handle.
     pub fn cu_ctx(&self) -> sys::CUcontext {
         self.cu_ctx
     }
@@ -141,14 +380,88 @@ impl CudaContext {
         Ok(())
     }
 
-    /// Get the value of the specified attribute of the device in [CudaContext].
-    pub fn attribute(&self, attrib: sys::CUdevice_attribute) -> Result<i32, result::DriverError> {
+    /// Whether this context's device can directly access memory allocated in `peer`.
+    pub fn can_access_peer(&self, peer: &CudaContext) -> Result<bool, DriverError> {
         self.check_err()?;
-        unsafe { result::device::get_attribute(self.cu_device, attrib) }
+        result::device::can_access_peer(self.cu_device, peer.cu_device)
     }
 
-    /// Synchronize this context. Will only block CPU if you call [CudaContext::set_flags()] with
-    /// [sys::CUctx_flags::CU_CTX_SCHED_BLOCKING_SYNC].
+    /// Allows kernels and copies in this context to directly access memory allocated in `peer`
+    /// (e.g. a [CudaSlice] allocated on one of `peer`'s streams). Access is one directional,
+    /// call this on `peer` as well for the other direction.
+    ///
+    /// Returns [sys::cudaError_enum::CUDA_ERROR_PEER_ACCESS_UNSUPPORTED] if [CudaContext::can_access_peer()]
+    /// is false, and [sys::cudaError_enum::CUDA_ERROR_PEER_ACCESS_ALREADY_ENABLED] if this was already called.
+    ///
+    /// Any memory of `peer` that is accessed from this context must outlive that access.
+    /// Since every [CudaSlice] holds onto its context, keeping the peer's [CudaSlice] alive until
+    /// the work using it has completed is enough. Note that peer memory is only usable through device pointers
+    /// (e.g. in [CudaStream::launch_builder()]), whose safety requirements already cover this.
+    ///
+    /// Event tracking of a [CudaSlice] only works within its own context, so if event tracking is
+    /// enabled (see [CudaContext::is_event_tracking()]), using a peer's [CudaSlice] on this context's stream will
+    /// record [sys::cudaError_enum::CUDA_ERROR_INVALID_CONTEXT]. Allocate the peer memory after
+    /// [CudaContext::disable_event_tracking()] on `peer`, and synchronize manually.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__PEER__ACCESS.html)
+    pub fn enable_peer_access(&self, peer: &Arc<CudaContext>) -> Result<(), DriverError> {
+        self.bind_to_thread()?;
+        unsafe { result::ctx::enable_peer_access(peer.cu_ctx) }
+    }
+
+    /// Reverts [CudaContext::enable_peer_access()].
+    ///
+    /// Returns [sys::cudaError_enum::CUDA_ERROR_PEER_ACCESS_NOT_ENABLED] if access was not enabled.
+    pub fn disable_peer_access(&self, peer: &Arc<CudaContext>) -> Result<(), DriverError> {
+        self.bind_to_thread()?;
+        unsafe { result::ctx::disable_peer_access(peer.cu_ctx) }
+    }
+
+    /// Get the value of the specified attribute of the device in [CudaContext]. `attrib` is
+    /// either a [DeviceAttribute] or a raw [sys::CUdevice_attribute].
+    pub fn attribute(
+        &self,
+        attrib: impl Into<sys::CUdevice_attribute>,
+    ) -> Result<i32, result::DriverError> {
+        self.check_err()?;
+        unsafe { result::device::get_attribute(self.cu_device, attrib.into()) }
+    }
+
+    /// The `(major, minor)` compute capability of the device, e.g. `(8, 0)` for `sm_80`.
+    pub fn compute_capability(&self) -> Result<(u32, u32), DriverError> {
+        let major = self.attribute(DeviceAttribute::ComputeCapabilityMajor)?;
+        let minor = self.attribute(DeviceAttribute::ComputeCapabilityMinor)?;
+        Ok((major as u32, minor as u32))
+    }
+
+    /// The number of streaming multiprocessors on the device.
+    pub fn multiprocessor_count(&self) -> Result<u32, DriverError> {
+        self.attribute(DeviceAttribute::MultiprocessorCount)
+            .map(|n| n as u32)
+    }
+
+    /// Returns the `(free, total)` device memory in bytes.
+    ///
+    /// This is only a snapshot: other contexts and processes may allocate or free memory at
+    /// any time, and due to fragmentation a single allocation of all the free bytes may still fail.
+    pub fn mem_info(&self) -> Result<(usize, usize), DriverError> {
+        self.bind_to_thread()?;
+        result::mem_get_info()
+    }
+
+    /// Returns the free device memory in bytes. See [CudaContext::mem_info()] for caveats.
+    pub fn free_mem(&self) -> Result<usize, DriverError> {
+        self.mem_info().map(|(free, _)| free)
+    }
+
+    /// Synchronize this context, i.e. wait until all work on **all** of its streams has completed.
+    /// Will only block CPU if you call [CudaContext::set_flags()] with
+    /// [sys::CUctx_flags::CU_CTX_SCHED_BLOCKING_SYNC], otherwise the calling thread spins.
+    ///
+    /// This stalls the whole device pipeline, so prefer [CudaStream::synchronize()] or events when
+    /// you know which streams produced the results, and use this sparingly.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__CTX.html)
     pub fn synchronize(&self) -> Result<(), DriverError> {
         self.bind_to_thread()?;
         result::ctx::synchronize()
@@ -226,6 +539,64 @@ impl CudaContext {
         self.event_tracking.store(false, Ordering::Relaxed);
     }
 
+    /// Debugging aid for out of bounds writes in kernels (e.g. hand written PTX), without
+    /// needing compute-sanitizer. Disabled (0) by default.
+    ///
+    /// When non-zero, every [CudaSlice] allocated by a [CudaStream] **after calling this function**
+    /// is padded with `bytes` of guard memory on both sides, filled with [ALLOC_GUARD_SENTINEL].
+    /// Use [CudaContext::check_guards()] to see if anything wrote to them.
+    ///
+    /// `bytes` is rounded up to a multiple of 256 so slices keep the alignment of `cuMemAlloc`.
+    /// This costs an extra allocation of `2 * bytes` and two memsets per slice, so it should only
+    /// be enabled while debugging.
+    pub fn set_alloc_guard_bytes(&self, bytes: usize) {
+        self.alloc_guard
+            .store(bytes.next_multiple_of(256), Ordering::Relaxed);
+    }
+
+    /// The number of guard bytes new allocations are padded with, see [CudaContext::set_alloc_guard_bytes()].
+    pub fn alloc_guard_bytes(&self) -> usize {
+        self.alloc_guard.load(Ordering::Relaxed)
+    }
+
+    /// Reads back the guard regions of `slice`, and returns `Ok(false)` if any byte before or after
+    /// the slice was overwritten. Waits for all work using `slice` to finish.
+    ///
+    /// Returns [sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE] if `slice` wasn't allocated with guards,
+    /// see [CudaContext::set_alloc_guard_bytes()].
+    ///
+    /// If `slice` belongs to a different [CudaContext], this will fail with
+    /// [sys::cudaError_enum::CUDA_ERROR_INVALID_CONTEXT].
+    pub fn check_guards<T>(&self, slice: &CudaSlice<T>) -> Result<bool, DriverError> {
+        if *self != *slice.stream.ctx {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_CONTEXT));
+        }
+        let SliceOwner::Guarded(guard) = slice.owner else {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE));
+        };
+        let stream = &slice.stream;
+        stream.bind_to_thread()?;
+        if let Some(write) = slice.write.as_ref() {
+            stream.wait(write)?;
+        }
+        let mut before = vec![0u8; guard];
+        let mut after = vec![0u8; guard];
+        let end = slice.cu_device_ptr + slice.num_bytes() as u64;
+        unsafe {
+            result::memcpy_dtoh_async(
+                &mut before,
+                slice.cu_device_ptr - guard as u64,
+                stream.cu_stream,
+            )?;
+            result::memcpy_dtoh_async(&mut after, end, stream.cu_stream)?;
+        }
+        stream.synchronize()?;
+        Ok(before
+            .iter()
+            .chain(after.iter())
+            .all(|&b| b == ALLOC_GUARD_SENTINEL))
+    }
+
     /// Checks to see if there have been any calls that stored an Err in a function
     /// that couldn't return a result (e.g. Drop calls).
     ///
@@ -242,7 +613,25 @@ impl CudaContext {
         }
     }
 
+    /// Returns and clears the error recorded by [CudaContext::record_err()], if any.
+    ///
+    /// Only a single error is stored: if multiple errors were recorded since the last call to
+    /// this (or to [CudaContext::check_err()]), the **most recent** one is returned and the
+    /// earlier ones are lost.
+    pub fn take_recorded_error(&self) -> Option<DriverError> {
+        self.check_err().err()
+    }
+
+    /// Whether an error has been recorded by [CudaContext::record_err()] that has not yet been
+    /// taken. Unlike [CudaContext::take_recorded_error()], this does not clear it.
+    pub fn has_recorded_error(&self) -> bool {
+        self.error_state.load(Ordering::Relaxed) != 0
+    }
+
     /// Records a result for later inspection when a Result can be returned.
+    ///
+    /// This overwrites any previously recorded error. Retrieve it with
+    /// [CudaContext::take_recorded_error()] or [CudaContext::check_err()].
     pub fn record_err<T>(&self, result: Result<T, DriverError>) {
         if let Err(err) = result {
             self.error_state.store(err.0 as u32, Ordering::Relaxed)
@@ -259,11 +648,13 @@ impl CudaContext {
 /// Note that calls to [CudaEvent::record()] will not change any **previous calls** to [CudaStream::wait()].
 ///
 /// # Thread safety
-/// This object is thread safe
+/// This object is thread safe: it is [Send] and [Sync], and all methods bind the event's context
+/// to the calling thread first. An event can be recorded on one thread and waited on from another.
 #[derive(Debug)]
 pub struct CudaEvent {
     pub(crate) cu_event: sys::CUevent,
     pub(crate) ctx: Arc<CudaContext>,
+    pub(crate) timing: bool,
 }
 
 unsafe impl Send for CudaEvent {}
@@ -287,9 +678,14 @@ impl CudaContext {
         let flags = flags.unwrap_or(sys::CUevent_flags::CU_EVENT_DISABLE_TIMING);
         self.bind_to_thread()?;
         let cu_event = result::event::create(flags)?;
+        let timing = !matches!(
+            flags,
+            sys::CUevent_flags::CU_EVENT_DISABLE_TIMING | sys::CUevent_flags::CU_EVENT_INTERPROCESS
+        );
         Ok(CudaEvent {
             cu_event,
             ctx: self.clone(),
+            timing,
         })
     }
 }
@@ -330,27 +726,56 @@ impl CudaEvent {
         unsafe { result::event::synchronize(self.cu_event) }
     }
 
-    /// The time between two events. `self` is the start event, and `end` is the end event.
+    /// Whether this event can be used with [CudaEvent::elapsed_ms()], i.e. it was **not**
+    /// created with [sys::CUevent_flags::CU_EVENT_DISABLE_TIMING].
+    pub fn is_timing_enabled(&self) -> bool {
+        self.timing
+    }
+
+    /// The time in milliseconds between two events. `self` is the start event, and `end` is the end event.
     /// This is effectively `end - self`.
+    ///
+    /// Both events must have been recorded, and this blocks until both have completed.
+    ///
+    /// Note that [CudaContext::new_event()] disables timing when `flags` is `None`, so
+    /// create both events with `Some(sys::CUevent_flags::CU_EVENT_DEFAULT)`. If either event has
+    /// timing disabled, this returns [sys::cudaError_enum::CUDA_ERROR_INVALID_HANDLE] without
+    /// synchronizing.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__EVENT.html)
     pub fn elapsed_ms(&self, end: &Self) -> Result<f32, DriverError> {
         if self.ctx != end.ctx {
             return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_CONTEXT));
         }
+        if !self.timing || !end.timing {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_HANDLE));
+        }
         self.ctx.bind_to_thread()?;
         self.synchronize()?;
         end.synchronize()?;
         unsafe { result::event::elapsed(self.cu_event, end.cu_event) }
     }
 
-    /// Returns `true` if all recorded work has been completed, `false` otherwise.
-    pub fn is_complete(&self) -> bool {
-        unsafe { result::event::query(self.cu_event) }.is_ok()
+    /// Returns `true` if all recorded work has been completed, `false` if some is still pending.
+    /// This does not block, so it can be used to poll many events.
+    ///
+    /// [sys::cudaError_enum::CUDA_ERROR_NOT_READY] is reported as `Ok(false)`, any other error is returned.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__EVENT.html#group__CUDA__EVENT_1g6f0704d755066b0ee705749ae911deef)
+    pub fn is_complete(&self) -> Result<bool, DriverError> {
+        self.ctx.bind_to_thread()?;
+        match unsafe { result::event::query(self.cu_event) } {
+            Ok(()) => Ok(true),
+            Err(DriverError(sys::cudaError_enum::CUDA_ERROR_NOT_READY)) => Ok(false),
+            Err(e) => Err(e),
+        }
     }
 }
 
 /// A wrapper around [sys::CUstream] that you can schedule work on.
 ///
-/// - Create with [CudaContext::new_stream()], [CudaContext::default_stream()], or [CudaStream::fork()].
+/// - Create with [CudaContext::new_stream()], [CudaContext::default_stream()], [CudaContext::per_thread_stream()],
+///   or [CudaStream::fork()].
 ///
 /// **Work done on this is asynchronous with respect to the host.**
 ///
@@ -359,11 +784,25 @@ impl CudaEvent {
 /// See [6.6. Event Management](https://docs.nvidia.com/cuda/cuda-runtime-api/group__CUDART__EVENT.html)
 /// See [Out-of-order execution](https://en.wikipedia.org/wiki/Out-of-order_execution)
 /// See [Dependence analysis](https://en.wikipedia.org/wiki/Dependence_analysis)
+///
+/// # Thread safety
+///
+/// This object is [Send] and [Sync], so an `Arc<CudaStream>` can be moved between threads (e.g. held
+/// across `.await` points by tasks that a multi threaded async runtime moves between worker threads).
+/// The driver ties the *current context* to a thread, not streams, so every method first calls
+/// [CudaStream::bind_to_thread()] to make the stream's context current on the calling thread.
+///
+/// The exception is [CudaContext::per_thread_stream()]: it must only be used on the thread that created it
+/// (debug builds panic otherwise). Call [CudaContext::per_thread_stream()] again on each thread instead.
 #[derive(Debug, PartialEq, Eq)]
 pub struct CudaStream {
     pub(crate) cu_stream: sys::CUstream,
     pub(crate) ctx: Arc<CudaContext>,
     pub(crate) fuel_check: bool,
+    /// Whether the stream is destroyed on drop, `false` for [CudaContext::wrap_borrowed_stream()].
+    pub(crate) owned: bool,
+    /// The thread that created a [CudaContext::per_thread_stream()], `None` for all other streams.
+    pub(crate) per_thread_owner: Option<std::thread::ThreadId>,
 }
 
 unsafe impl Send for CudaStream {}
@@ -374,8 +813,10 @@ impl Drop for CudaStream {
         self.ctx.record_err(self.ctx.bind_to_thread());
         if !self.cu_stream.is_null() {
             self.ctx.num_streams.fetch_sub(1, Ordering::Relaxed);
-            self.ctx
-                .record_err(unsafe { result::stream::destroy(self.cu_stream) });
+            if self.owned {
+                self.ctx
+                    .record_err(unsafe { result::stream::destroy(self.cu_stream) });
+            }
         }
     }
 }
@@ -386,19 +827,60 @@ impl CudaContext {
             cu_stream: std::ptr::null_mut(),
             ctx: self.clone(),
             fuel_check: true,
+            owned: false,
+            per_thread_owner: None,
         })
     }
 
     /// Get's the default stream for this context (the null ptr stream). Note that context's
     /// on the same device can all submit to the same default stream from separate context objects.
+    ///
+    /// This is the *legacy* default stream, which is shared by all host threads, so threads that
+    /// only use this stream serialize each other. See [CudaContext::per_thread_stream()].
     pub fn default_stream(self: &Arc<Self>) -> Arc<CudaStream> {
         Arc::new(CudaStream {
             cu_stream: std::ptr::null_mut(),
             ctx: self.clone(),
             fuel_check: false,
+            owned: false,
+            per_thread_owner: None,
         })
     }
 
+    /// Gets the per-thread default stream (`CU_STREAM_PER_THREAD`). Work submitted to it from
+    /// different host threads can run concurrently, unlike with [CudaContext::default_stream()].
+    ///
+    /// The returned [CudaStream] must only be used on the thread that created it, since the same
+    /// handle refers to a different stream on every thread. Debug builds panic if any of its methods
+    /// (or a [CudaSlice] allocated on it) is used from another thread. Each thread that wants its own
+    /// stream should call this itself.
+    ///
+    /// The per-thread stream still synchronizes with the legacy default stream (used e.g. by libraries
+    /// that were handed a null stream), just like streams created with `CU_STREAM_DEFAULT`.
+    ///
+    /// Interop: the CUDA runtime picks legacy or per-thread semantics for its null stream
+    /// once for the whole process (`--default-stream per-thread` / `CUDA_API_PER_THREAD_DEFAULT_STREAM`),
+    /// while the driver API chooses per call. Libraries built on the runtime with per-thread
+    /// semantics therefore share this stream, and libraries built without it share
+    /// [CudaContext::default_stream()]. Pass [CudaStream::cu_stream()] explicitly to libraries
+    /// that take a stream to avoid depending on that choice.
+    ///
+    /// Like [CudaContext::new_stream()], this swaps the context to multi stream mode.
+    pub fn per_thread_stream(self: &Arc<Self>) -> Result<Arc<CudaStream>, DriverError> {
+        self.bind_to_thread()?;
+        let prev_num_streams = self.num_streams.fetch_add(1, Ordering::Relaxed);
+        if prev_num_streams == 0 && self.is_event_tracking() {
+            self.synchronize()?;
+        }
+        Ok(Arc::new(CudaStream {
+            cu_stream: result::stream::per_thread(),
+            ctx: self.clone(),
+            fuel_check: false,
+            owned: false,
+            per_thread_owner: Some(std::thread::current().id()),
+        }))
+    }
+
     /// Create a new [sys::CUstream_flags::CU_STREAM_NON_BLOCKING] stream.
     ///
     /// This will swap the calling context to multi stream mode [CudaContext::is_in_multi_stream_mode()].
@@ -414,6 +896,83 @@ impl CudaContext {
             cu_stream,
             ctx: self.clone(),
             fuel_check: false,
+            owned: true,
+            per_thread_owner: None,
+        }))
+    }
+
+    /// Same as [CudaContext::new_stream()], but with the specified `priority`.
+    ///
+    /// Lower numbers mean higher priority. If `priority` is not contained in
+    /// [CudaContext::stream_priority_range()], this returns [sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE].
+    pub fn new_stream_with_priority(
+        self: &Arc<Self>,
+        priority: i32,
+    ) -> Result<Arc<CudaStream>, DriverError> {
+        if !self.stream_priority_range()?.contains(&priority) {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE));
+        }
+        let prev_num_streams = self.num_streams.fetch_add(1, Ordering::Relaxed);
+        if prev_num_streams == 0 && self.is_event_tracking() {
+            self.synchronize()?;
+        }
+        let cu_stream = result::stream::create_with_priority(
+            result::stream::StreamKind::NonBlocking,
+            priority,
+        )?;
+        Ok(Arc::new(CudaStream {
+            cu_stream,
+            ctx: self.clone(),
+            fuel_check: false,
+            owned: true,
+            per_thread_owner: None,
+        }))
+    }
+
+    /// The valid stream priorities for this context, from greatest priority (lowest number)
+    /// to least priority (highest number), e.g. `-5..1`.
+    ///
+    /// If the device does not support stream priorities, this range only contains `0`.
+    pub fn stream_priority_range(&self) -> Result<Range<i32>, DriverError> {
+        self.bind_to_thread()?;
+        let (least, greatest) = result::ctx::get_stream_priority_range()?;
+        Ok(greatest..least + 1)
+    }
+}
+
+impl CudaContext {
+    /// Wraps a stream created outside of cudarc (e.g. by another CUDA library) in a [CudaStream],
+    /// so all of its methods can enqueue work onto it. The stream is **not** destroyed when the
+    /// returned [CudaStream] is dropped.
+    ///
+    /// Like [CudaContext::new_stream()], this swaps the context to multi stream mode.
+    /// Returns [sys::cudaError_enum::CUDA_ERROR_INVALID_CONTEXT] if the stream belongs to a different context.
+    ///
+    /// # Safety
+    /// 1. `cu_stream` must be a valid, non-null stream.
+    /// 2. The stream must outlive the returned [CudaStream] and everything allocated on it
+    ///    (e.g. [CudaSlice]s free their memory on their stream when dropped).
+    pub unsafe fn wrap_borrowed_stream(
+        self: &Arc<Self>,
+        cu_stream: sys::CUstream,
+    ) -> Result<Arc<CudaStream>, DriverError> {
+        if cu_stream.is_null() {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_HANDLE));
+        }
+        self.bind_to_thread()?;
+        if result::stream::get_ctx(cu_stream)? != self.cu_ctx {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_CONTEXT));
+        }
+        let prev_num_streams = self.num_streams.fetch_add(1, Ordering::Relaxed);
+        if prev_num_streams == 0 && self.is_event_tracking() {
+            self.synchronize()?;
+        }
+        Ok(Arc::new(CudaStream {
+            cu_stream,
+            ctx: self.clone(),
+            fuel_check: false,
+            owned: false,
+            per_thread_owner: None,
         }))
     }
 }
@@ -421,21 +980,31 @@ impl CudaContext {
 impl CudaStream {
     /// Create's a new stream and then makes the new stream wait on `self`
     pub fn fork(&self) -> Result<Arc<Self>, DriverError> {
-        self.ctx.bind_to_thread()?;
+        self.bind_to_thread()?;
         self.ctx.num_streams.fetch_add(1, Ordering::Relaxed);
         let cu_stream = result::stream::create(result::stream::StreamKind::NonBlocking)?;
         let stream = Arc::new(CudaStream {
             cu_stream,
             ctx: self.ctx.clone(),
             fuel_check: false,
+            owned: true,
+            per_thread_owner: None,
         });
         stream.join(self)?;
         Ok(stream)
     }
 
-    /// The underlying cuda stream object
+    /// Get the underlying [sys::CUstream] of this [CudaStream], e.g. to hand to a C library.
+    ///
     /// # Safety
-    /// Do not destroy this value.
+    /// While this function is marked as safe, actually using the
+    /// returned object is unsafe.
+    ///
+    /// **You must not destroy the stream**, as it is still owned by the [CudaStream], and
+    /// the [CudaStream] must outlive any use of the handle.
+    ///
+    /// Work enqueued on the raw handle is not seen by cudarc's event tracking, so synchronize it
+    /// yourself with any [CudaSlice] it touches from other streams (e.g. with [CudaStream::synchronize()]).
     pub fn cu_stream(&self) -> sys::CUstream {
         self.cu_stream
     }
@@ -445,15 +1014,78 @@ impl CudaStream {
         &self.ctx
     }
 
+    /// Binds the context of this stream to the calling thread, see [CudaContext::bind_to_thread()].
+    /// All methods of [CudaStream] call this first, so it is only needed before using [CudaStream::cu_stream()]
+    /// directly, e.g. after moving to another thread.
+    ///
+    /// In debug builds this panics if a [CudaContext::per_thread_stream()] is used on a thread other than
+    /// the one that created it, since it would refer to a different stream there.
+    pub fn bind_to_thread(&self) -> Result<(), DriverError> {
+        if let Some(owner) = self.per_thread_owner {
+            debug_assert_eq!(
+                owner,
+                std::thread::current().id(),
+                "per thread stream used on a different thread than the one that created it"
+            );
+        }
+        self.ctx.bind_to_thread()
+    }
+
+    /// The priority of this stream, see [CudaContext::new_stream_with_priority()].
+    pub fn priority(&self) -> Result<i32, DriverError> {
+        self.bind_to_thread()?;
+        unsafe { result::stream::get_priority(self.cu_stream) }
+    }
+
     /// Will only block CPU if you call [CudaContext::set_flags()] with
     /// [sys::CUctx_flags::CU_CTX_SCHED_BLOCKING_SYNC].
     ///
     /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__STREAM.html#group__CUDA__STREAM_1g15e49dd91ec15991eb7c0a741beb7dad)
     pub fn synchronize(&self) -> Result<(), DriverError> {
-        self.ctx.bind_to_thread()?;
+        self.bind_to_thread()?;
         unsafe { result::stream::synchronize(self.cu_stream) }
     }
 
+    /// Returns `true` if all work submitted to this stream has completed, `false` if some is still
+    /// pending. Unlike [CudaStream::synchronize()] this never blocks.
+    ///
+    /// [sys::cudaError_enum::CUDA_ERROR_NOT_READY] is reported as `Ok(false)`, any other error is returned.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__STREAM.html#group__CUDA__STREAM_1g1b0d24bbe97fa68e4bc511fb6adfeb0b)
+    pub fn is_idle(&self) -> Result<bool, DriverError> {
+        self.bind_to_thread()?;
+        match unsafe { result::stream::query(self.cu_stream) } {
+            Ok(()) => Ok(true),
+            Err(DriverError(sys::cudaError_enum::CUDA_ERROR_NOT_READY)) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Waits until all work submitted to this stream has completed, or until `timeout` has elapsed.
+    /// Returns `Ok(true)` if the stream is idle, and `Ok(false)` on timeout.
+    ///
+    /// This polls [CudaStream::is_idle()], sleeping between polls with an exponential backoff
+    /// (starting at 10us, capped at 10ms), so it never busy-waits a whole core.
+    ///
+    /// CUDA has no way to cancel work that was already submitted, so a timeout only *detects* a
+    /// stuck kernel, the work keeps running. Typically the only way out is to tear down the process.
+    pub fn synchronize_timeout(&self, timeout: std::time::Duration) -> Result<bool, DriverError> {
+        const MAX_SLEEP: std::time::Duration = std::time::Duration::from_millis(10);
+        let start = std::time::Instant::now();
+        let mut sleep = std::time::Duration::from_micros(10);
+        loop {
+            if self.is_idle()? {
+                return Ok(true);
+            }
+            let elapsed = start.elapsed();
+            if elapsed >= timeout {
+                return Ok(false);
+            }
+            std::thread::sleep(sleep.min(timeout - elapsed));
+            sleep = (sleep * 2).min(MAX_SLEEP);
+        }
+    }
+
     /// Creates a new [CudaEvent] and records the current work in the stream to the event.
     pub fn record_event(
         &self,
@@ -471,39 +1103,247 @@ impl CudaStream {
     ///
     /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__STREAM.html#group__CUDA__STREAM_1g6a898b652dfc6aa1d5c8d97062618b2f)
     pub fn wait(&self, event: &CudaEvent) -> Result<(), DriverError> {
+        self.wait_with_flags(event, EventWaitFlags::Default)
+    }
+
+    /// Same as [CudaStream::wait()], but with explicit [EventWaitFlags].
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__STREAM.html#group__CUDA__STREAM_1g6a898b652dfc6aa1d5c8d97062618b2f)
+    pub fn wait_with_flags(
+        &self,
+        event: &CudaEvent,
+        flags: EventWaitFlags,
+    ) -> Result<(), DriverError> {
         if self.ctx != event.ctx {
             return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_CONTEXT));
         }
-        self.ctx.bind_to_thread()?;
+        self.bind_to_thread()?;
+        unsafe { result::stream::wait_event(self.cu_stream, event.cu_event, flags.into()) }
+    }
+
+    /// Makes all future work submitted to this stream wait until `slice[0]` satisfies `flags`
+    /// with respect to `value`. This is the waiting half of a lightweight semaphore in device memory,
+    /// see [CudaStream::write_value_u32()] for the signaling half.
+    ///
+    /// Event tracking of `slice` is intentionally bypassed: waiting on the recorded events of
+    /// the signaling stream would deadlock. Synchronize any other access to `slice` yourself.
+    ///
+    /// Returns [sys::cudaError_enum::CUDA_ERROR_INVALID_CONTEXT] if `slice` belongs to a different context,
+    /// [sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE] if `slice` is empty, and
+    /// [sys::cudaError_enum::CUDA_ERROR_NOT_SUPPORTED] if `flags` is [WaitFlags::Nor] and the device does not support it.
+    ///
+    /// Requires CUDA 12.0 or newer, where stream memory operations are supported on all devices.
+    #[cfg(not(any(
+        feature = "cuda-11040",
+        feature = "cuda-11050",
+        feature = "cuda-11060",
+        feature = "cuda-11070",
+        feature = "cuda-11080"
+    )))]
+    pub fn wait_value_u32(
+        &self,
+        slice: &CudaSlice<u32>,
+        value: u32,
+        flags: WaitFlags,
+    ) -> Result<(), DriverError> {
+        self.bind_to_thread()?;
+        self.check_value_slice(slice)?;
+        if flags == WaitFlags::Nor
+            && self.ctx.attribute(
+                sys::CUdevice_attribute::CU_DEVICE_ATTRIBUTE_CAN_USE_STREAM_WAIT_VALUE_NOR,
+            )? == 0
+        {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_NOT_SUPPORTED));
+        }
         unsafe {
-            result::stream::wait_event(
+            result::stream::wait_value_32(self.cu_stream, slice.cu_device_ptr, value, flags.into())
+        }
+    }
+
+    /// Writes `value` to `slice[0]` once previous work on this stream has completed.
+    /// Like [CudaStream::wait_value_u32()] this bypasses event tracking of `slice`.
+    ///
+    /// Requires CUDA 12.0 or newer.
+    #[cfg(not(any(
+        feature = "cuda-11040",
+        feature = "cuda-11050",
+        feature = "cuda-11060",
+        feature = "cuda-11070",
+        feature = "cuda-11080"
+    )))]
+    pub fn write_value_u32(
+        &self,
+        slice: &mut CudaSlice<u32>,
+        value: u32,
+    ) -> Result<(), DriverError> {
+        self.bind_to_thread()?;
+        self.check_value_slice(slice)?;
+        unsafe {
+            result::stream::write_value_32(
                 self.cu_stream,
-                event.cu_event,
-                sys::CUevent_wait_flags::CU_EVENT_WAIT_DEFAULT,
+                slice.cu_device_ptr,
+                value,
+                sys::CUstreamWriteValue_flags::CU_STREAM_WRITE_VALUE_DEFAULT,
             )
         }
     }
 
-    /// Ensures this stream waits for the current workload in `other` to complete.
-    /// This is shorthand for `self.wait(other.record_event())`
-    pub fn join(&self, other: &CudaStream) -> Result<(), DriverError> {
-        self.wait(&other.record_event(None)?)
+    #[cfg(not(any(
+        feature = "cuda-11040",
+        feature = "cuda-11050",
+        feature = "cuda-11060",
+        feature = "cuda-11070",
+        feature = "cuda-11080"
+    )))]
+    fn check_value_slice(&self, slice: &CudaSlice<u32>) -> Result<(), DriverError> {
+        if slice.stream.ctx != self.ctx {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_CONTEXT));
+        }
+        if slice.is_empty() {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE));
+        }
+        self.bind_to_thread()
     }
-}
 
-/// `Vec<T>` on a cuda device. You can allocate and modify this with [CudaStream].
-///
-/// This object is thread safe.
-#[derive(Debug)]
+    /// Enqueues `f` to run on a driver thread once all previously submitted work on this
+    /// stream has completed. Work submitted to this stream afterwards waits for `f` to return.
+    /// This does not block the calling thread.
+    ///
+    /// **`f` must not call any CUDA API** (including anything in this crate that talks to the driver,
+    /// like allocating, copying, or synchronizing). Doing so may return
+    /// [sys::cudaError_enum::CUDA_ERROR_NOT_PERMITTED] or deadlock. Use `f` to notify another thread
+    /// (e.g. through a channel), and do the CUDA work there.
+    ///
+    /// `f` is called exactly once, even if this stream is dropped before the work completes, and is
+    /// freed right after it runs. If enqueuing fails `f` is dropped without being called.
+    /// A panic inside `f` aborts the process, since it cannot unwind into the driver.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__EXEC.html)
+    pub fn launch_host_fn<F: FnOnce() + Send + 'static>(&self, f: F) -> Result<(), DriverError> {
+        unsafe extern "C" fn trampoline<F: FnOnce()>(arg: *mut std::ffi::c_void) {
+            let f = unsafe { Box::from_raw(arg as *mut F) };
+            f();
+        }
+
+        self.bind_to_thread()?;
+        let arg = Box::into_raw(Box::new(f));
+        let res = unsafe {
+            result::stream::launch_host_function(self.cu_stream, trampoline::<F>, arg as *mut _)
+        };
+        if res.is_err() {
+            // the driver never took ownership, so we have to free it
+            drop(unsafe { Box::from_raw(arg) });
+        }
+        res
+    }
+
+    /// Ensures this stream waits for the current workload in `other` to complete.
+    /// This is shorthand for `self.wait(other.record_event())`
+    pub fn join(&self, other: &CudaStream) -> Result<(), DriverError> {
+        self.wait(&other.record_event(None)?)
+    }
+
+    /// Creates `n` new streams that all wait on the work currently in `self`, i.e. a fan out.
+    /// Use [CudaStream::join_all()] to fan back in.
+    ///
+    /// This is like calling [CudaStream::fork()] `n` times, but only records a single event on `self`.
+    pub fn fork_many(&self, n: usize) -> Result<Vec<Arc<Self>>, DriverError> {
+        let event = self.record_event(None)?;
+        (0..n)
+            .map(|_| {
+                let stream = self.ctx.new_stream()?;
+                stream.wait(&event)?;
+                Ok(stream)
+            })
+            .collect()
+    }
+
+    /// Ensures this stream waits for the current workload in each of `others` to complete,
+    /// e.g. to fan back in after [CudaStream::fork_many()].
+    pub fn join_all(&self, others: &[Arc<CudaStream>]) -> Result<(), DriverError> {
+        for other in others.iter() {
+            self.join(other)?;
+        }
+        Ok(())
+    }
+}
+
+/// How [CudaStream::wait_with_flags()] waits on an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum EventWaitFlags {
+    /// A regular wait.
+    #[default]
+    Default,
+    /// During stream capture, adds an external event wait node to the graph instead of a
+    /// dependency on the event's captured work. Outside of capture this is the same as [EventWaitFlags::Default].
+    External,
+}
+
+impl From<EventWaitFlags> for sys::CUevent_wait_flags {
+    fn from(flags: EventWaitFlags) -> Self {
+        match flags {
+            EventWaitFlags::Default => sys::CUevent_wait_flags::CU_EVENT_WAIT_DEFAULT,
+            EventWaitFlags::External => sys::CUevent_wait_flags::CU_EVENT_WAIT_EXTERNAL,
+        }
+    }
+}
+
+/// The comparison [CudaStream::wait_value_u32()] waits on, between the value in memory and the given value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WaitFlags {
+    /// Wait until `memory >= value` (as a cyclic comparison of unsigned integers).
+    Geq,
+    /// Wait until `memory == value`.
+    Eq,
+    /// Wait until `(memory & value) != 0`.
+    And,
+    /// Wait until `!(memory | value) != 0`. Not supported on all devices.
+    Nor,
+}
+
+impl From<WaitFlags> for sys::CUstreamWaitValue_flags {
+    fn from(flags: WaitFlags) -> Self {
+        match flags {
+            WaitFlags::Geq => sys::CUstreamWaitValue_flags::CU_STREAM_WAIT_VALUE_GEQ,
+            WaitFlags::Eq => sys::CUstreamWaitValue_flags::CU_STREAM_WAIT_VALUE_EQ,
+            WaitFlags::And => sys::CUstreamWaitValue_flags::CU_STREAM_WAIT_VALUE_AND,
+            WaitFlags::Nor => sys::CUstreamWaitValue_flags::CU_STREAM_WAIT_VALUE_NOR,
+        }
+    }
+}
+
+/// `Vec<T>` on a cuda device. You can allocate and modify this with [CudaStream].
+///
+/// This object is thread safe.
+#[derive(Debug)]
 pub struct CudaSlice<T> {
     pub(crate) cu_device_ptr: sys::CUdeviceptr,
     pub(crate) len: usize,
     pub(crate) read: Option<CudaEvent>,
     pub(crate) write: Option<CudaEvent>,
     pub(crate) stream: Arc<CudaStream>,
+    pub(crate) owner: SliceOwner,
     pub(crate) marker: PhantomData<*const T>,
 }
 
+/// What is responsible for freeing the memory of a [CudaSlice] when it is dropped.
+#[derive(Debug)]
+pub(crate) enum SliceOwner {
+    /// Allocated by us, freed on [CudaSlice::stream].
+    Stream,
+    /// Allocated by us with this many guard bytes before and after the slice,
+    /// see [CudaContext::set_alloc_guard_bytes()].
+    Guarded(usize),
+    /// Allocated by us, starting this many bytes before the slice.
+    /// See [CudaContext::alloc_aligned()].
+    Offset(usize),
+    /// Opened with [CudaContext::import_ipc_handle()], closed instead of freed.
+    IpcImport,
+    /// A global variable of a module from [CudaModule::get_global()]. Never freed,
+    /// but the module is kept alive until the slice is dropped.
+    Module(#[allow(unused)] Arc<CudaModule>),
+}
+
 unsafe impl<T> Send for CudaSlice<T> {}
 unsafe impl<T> Sync for CudaSlice<T> {}
 
@@ -516,7 +1356,92 @@ impl<T> Drop for CudaSlice<T> {
         if let Some(write) = self.write.as_ref() {
             ctx.record_err(self.stream.wait(write));
         }
-        ctx.record_err(unsafe { result::free_async(self.cu_device_ptr, self.stream.cu_stream) });
+        match self.owner {
+            SliceOwner::Stream => ctx.record_err(unsafe {
+                result::free_async(self.cu_device_ptr, self.stream.cu_stream)
+            }),
+            SliceOwner::Guarded(offset) | SliceOwner::Offset(offset) => ctx.record_err(unsafe {
+                result::free_async(self.cu_device_ptr - offset as u64, self.stream.cu_stream)
+            }),
+            SliceOwner::IpcImport => {
+                // NOTE: closing is not stream ordered, so pending work has to finish first
+                ctx.record_err(self.stream.synchronize());
+                ctx.record_err(unsafe { result::ipc::close_mem_handle(self.cu_device_ptr) });
+            }
+            SliceOwner::Module(_) => (),
+        }
+    }
+}
+
+impl CudaContext {
+    /// Frees all of `slices`, and waits until the memory has actually been released.
+    ///
+    /// Dropping a [CudaSlice] never blocks: its memory is freed with `cuMemFreeAsync` on
+    /// [CudaSlice::stream()], so it is only released once prior work on that stream completes.
+    /// Dropping many slices is therefore already cheap. This additionally synchronizes each distinct
+    /// stream of `slices` **once** at the end (instead of once per slice), which is useful when the
+    /// memory must be available again before continuing, e.g. to tear down a model before loading
+    /// the next one.
+    ///
+    /// Memory freed on a stream goes back to that device's memory pool, so it may still be reserved
+    /// by the pool, see [crate::driver::MemPool::trim_to()] to return it to the OS.
+    pub fn free_batch<T>(&self, slices: Vec<CudaSlice<T>>) -> Result<(), DriverError> {
+        self.bind_to_thread()?;
+        let mut streams: Vec<Arc<CudaStream>> = Vec::new();
+        for slice in slices {
+            if !streams.iter().any(|s| Arc::ptr_eq(s, &slice.stream)) {
+                streams.push(slice.stream.clone());
+            }
+            drop(slice);
+        }
+        for stream in streams {
+            stream.synchronize()?;
+        }
+        Ok(())
+    }
+
+    /// Allocates a [CudaSlice] with `len` elements of type `T` on [CudaContext::default_stream()],
+    /// whose device pointer is a multiple of `align` bytes. Useful for vectorized loads
+    /// (e.g. `float4` needs 16 bytes) or tensor core paths that want larger alignments.
+    ///
+    /// This over-allocates by `align - 1` bytes and frees the original allocation on drop.
+    /// These slices are never padded with guard bytes, see [CudaContext::set_alloc_guard_bytes()].
+    ///
+    /// Returns [sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE] if `align` is not a power of two.
+    ///
+    /// # Safety
+    /// This is unsafe because the memory is unset, like [CudaStream::alloc()].
+    pub unsafe fn alloc_aligned<T: DeviceRepr>(
+        self: &Arc<Self>,
+        len: usize,
+        align: usize,
+    ) -> Result<CudaSlice<T>, DriverError> {
+        if !align.is_power_of_two() {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE));
+        }
+        let stream = self.default_stream();
+        stream.bind_to_thread()?;
+        let num_bytes = len * std::mem::size_of::<T>() + align - 1;
+        let base = if self.has_async_alloc {
+            result::malloc_async(stream.cu_stream, num_bytes)?
+        } else {
+            result::malloc_sync(num_bytes)?
+        };
+        let cu_device_ptr = base.next_multiple_of(align as u64);
+        let (read, write) = if self.is_event_tracking() {
+            (Some(self.new_event(None)?), Some(self.new_event(None)?))
+        } else {
+            (None, None)
+        };
+        Ok(CudaSlice {
+            cu_device_ptr,
+            len,
+            read,
+            write,
+            stream,
+            owner: SliceOwner::Offset((cu_device_ptr - base) as usize),
+            marker: PhantomData,
+        })
     }
 }
 
@@ -554,6 +1479,13 @@ impl<T> CudaSlice<T> {
 
 impl<T: DeviceRepr> CudaSlice<T> {
     /// Allocates copy of self and schedules a device to device copy of memory.
+    ///
+    /// Both the allocation and the copy are asynchronous on [CudaSlice::stream()], which the new slice
+    /// also belongs to. Work submitted afterwards to that stream (e.g. an in-place kernel on `self`) is
+    /// ordered after the copy, so the clone is a snapshot of `self` at the time of this call. Other streams
+    /// are synchronized through the usual event tracking, see [CudaStream::clone_dtod()].
+    ///
+    /// [Clone] is implemented by unwrapping this.
     pub fn try_clone(&self) -> Result<Self, result::DriverError> {
         self.stream.clone_dtod(self)
     }
@@ -631,7 +1563,20 @@ pub struct CudaViewMut<'a, T> {
 }
 
 impl<T> CudaSlice<T> {
-    pub fn as_view_mut(&self) -> CudaViewMut<'_, T> {
+    /// Creates a [CudaViewMut] of the whole slice. This mutably borrows `self`, so
+    /// there can only be one mutable view at a time:
+    /// ```rust,compile_fail
+    /// # use cudarc::driver::safe::{CudaContext, CudaSlice, CudaViewMut};
+    /// # fn do_something(view: CudaViewMut<u8>, view2: CudaViewMut<u8>) {}
+    /// # let ctx = CudaContext::new(0).unwrap();
+    /// # let stream = ctx.default_stream();
+    /// let mut slice = stream.alloc_zeros::<u8>(100).unwrap();
+    /// let view1 = slice.as_view_mut();
+    /// // cannot borrow twice from slice
+    /// let view2 = slice.as_view_mut();
+    /// do_something(view1, view2);
+    /// ```
+    pub fn as_view_mut(&mut self) -> CudaViewMut<'_, T> {
         CudaViewMut {
             ptr: self.cu_device_ptr,
             len: self.len,
@@ -746,6 +1691,7 @@ unsafe impl DeviceRepr for u128 {}
 unsafe impl DeviceRepr for usize {}
 unsafe impl DeviceRepr for f32 {}
 unsafe impl DeviceRepr for f64 {}
+unsafe impl<T: DeviceRepr, const N: usize> DeviceRepr for [T; N] {}
 #[cfg(feature = "f16")]
 unsafe impl DeviceRepr for half::f16 {}
 #[cfg(feature = "f16")]
@@ -754,14 +1700,21 @@ unsafe impl DeviceRepr for half::bf16 {}
 /// Base trait for abstracting over [CudaSlice]/[CudaView]/[CudaViewMut].
 ///
 /// Don't use this directly - use [DevicePtr]/[DevicePtrMut].
+///
+/// Implementors only need to provide [DeviceSlice::len()] and [DeviceSlice::stream()],
+/// [DeviceSlice::num_bytes()] and [DeviceSlice::is_empty()] are derived from the length.
 pub trait DeviceSlice<T> {
+    /// The number of elements of `T`.
     fn len(&self) -> usize;
+    /// The number of bytes, i.e. `self.len() * size_of::<T>()`.
     fn num_bytes(&self) -> usize {
         self.len() * std::mem::size_of::<T>()
     }
+    /// True if there are no elements.
     fn is_empty(&self) -> bool {
         self.len() == 0
     }
+    /// The stream the memory is ordered on.
     fn stream(&self) -> &Arc<CudaStream>;
 }
 
@@ -1057,12 +2010,26 @@ impl CudaContext {
     pub unsafe fn alloc_pinned<T: DeviceRepr>(
         self: &Arc<Self>,
         len: usize,
+    ) -> Result<PinnedHostSlice<T>, DriverError> {
+        self.alloc_pinned_with_flags(len, sys::CU_MEMHOSTALLOC_WRITECOMBINED)
+    }
+
+    /// Allocates page locked host memory with the specified `flags`, which is a bitwise or of:
+    /// - [sys::CU_MEMHOSTALLOC_PORTABLE]: the memory is pinned for all contexts, not just this one.
+    /// - [sys::CU_MEMHOSTALLOC_DEVICEMAP]: the memory is mapped into the device address space.
+    /// - [sys::CU_MEMHOSTALLOC_WRITECOMBINED]: faster host to device copies, but very slow reads from the host.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html#group__CUDA__MEM_1g572ca4011bfcb25034888a14d4e035b9)
+    ///
+    /// # Safety
+    /// 1. This is unsafe because the memory is unset after this call.
+    pub unsafe fn alloc_pinned_with_flags<T: DeviceRepr>(
+        self: &Arc<Self>,
+        len: usize,
+        flags: std::ffi::c_uint,
     ) -> Result<PinnedHostSlice<T>, DriverError> {
         self.bind_to_thread()?;
-        let ptr = result::malloc_host(
-            len * std::mem::size_of::<T>(),
-            sys::CU_MEMHOSTALLOC_WRITECOMBINED,
-        )?;
+        let ptr = result::malloc_host(len * std::mem::size_of::<T>(), flags)?;
         let ptr = ptr as *mut T;
         assert!(!ptr.is_null());
         assert!(len * std::mem::size_of::<T>() < isize::MAX as usize);
@@ -1123,6 +2090,25 @@ impl<T: ValidAsZeroBits> PinnedHostSlice<T> {
     }
 }
 
+/// Waits for any scheduled work to complete, recording any error in the context
+/// (see [CudaContext::check_err()]). Use [PinnedHostSlice::as_slice()] to handle the error directly.
+impl<T: ValidAsZeroBits> std::ops::Deref for PinnedHostSlice<T> {
+    type Target = [T];
+    fn deref(&self) -> &Self::Target {
+        self.event.ctx.record_err(self.event.synchronize());
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+/// Waits for any scheduled work to complete, recording any error in the context
+/// (see [CudaContext::check_err()]). Use [PinnedHostSlice::as_mut_slice()] to handle the error directly.
+impl<T: ValidAsZeroBits> std::ops::DerefMut for PinnedHostSlice<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.event.ctx.record_err(self.event.synchronize());
+        unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+}
+
 impl<T> HostSlice<T> for PinnedHostSlice<T> {
     fn len(&self) -> usize {
         self.len
@@ -1153,7 +2139,7 @@ impl<T> HostSlice<T> for PinnedHostSlice<T> {
 impl CudaStream {
     /// Allocates an empty [CudaSlice] with 0 length.
     pub fn null<T>(self: &Arc<Self>) -> Result<CudaSlice<T>, result::DriverError> {
-        self.ctx.bind_to_thread()?;
+        self.bind_to_thread()?;
         let cu_device_ptr = if self.ctx.has_async_alloc {
             unsafe { result::malloc_async(self.cu_stream, 0) }?
         } else {
@@ -1165,6 +2151,7 @@ impl CudaStream {
             read: None,
             write: None,
             stream: self.clone(),
+            owner: SliceOwner::Stream,
             marker: PhantomData,
         })
     }
@@ -1176,12 +2163,20 @@ impl CudaStream {
         self: &Arc<Self>,
         len: usize,
     ) -> Result<CudaSlice<T>, DriverError> {
-        self.ctx.bind_to_thread()?;
-        let cu_device_ptr = if self.ctx.has_async_alloc {
-            result::malloc_async(self.cu_stream, len * std::mem::size_of::<T>())?
+        self.bind_to_thread()?;
+        let guard = self.ctx.alloc_guard_bytes();
+        let num_bytes = len * std::mem::size_of::<T>() + 2 * guard;
+        let mut cu_device_ptr = if self.ctx.has_async_alloc {
+            result::malloc_async(self.cu_stream, num_bytes)?
         } else {
-            result::malloc_sync(len * std::mem::size_of::<T>())?
+            result::malloc_sync(num_bytes)?
         };
+        if guard > 0 {
+            let end = cu_device_ptr + (num_bytes - guard) as u64;
+            result::memset_d8_async(cu_device_ptr, ALLOC_GUARD_SENTINEL, guard, self.cu_stream)?;
+            result::memset_d8_async(end, ALLOC_GUARD_SENTINEL, guard, self.cu_stream)?;
+            cu_device_ptr += guard as u64;
+        }
         let (read, write) = if self.ctx.is_event_tracking() {
             (
                 Some(self.ctx.new_event(None)?),
@@ -1196,6 +2191,11 @@ impl CudaStream {
             read,
             write,
             stream: self.clone(),
+            owner: if guard > 0 {
+                SliceOwner::Guarded(guard)
+            } else {
+                SliceOwner::Stream
+            },
             marker: PhantomData,
         })
     }
@@ -1215,12 +2215,62 @@ impl CudaStream {
         self: &Arc<Self>,
         dst: &mut Dst,
     ) -> Result<(), DriverError> {
+        self.bind_to_thread()?;
         let num_bytes = dst.num_bytes();
         let (dptr, _record) = dst.device_ptr_mut(self);
         unsafe { result::memset_d8_async(dptr, 0, num_bytes, self.cu_stream) }?;
         Ok(())
     }
 
+    /// Sets every element of `dst` to `value`, by copying the bit pattern of `value`.
+    ///
+    /// - 1, 2 and 4 byte types (e.g. `u8`, `f16`, `u32`, `f32`) use a single
+    ///   `cuMemsetD8Async`/`cuMemsetD16Async`/`cuMemsetD32Async`.
+    /// - Types whose size is a multiple of 4 bytes (e.g. `f64`, `u64`) use one strided
+    ///   `cuMemsetD2D32Async` per 4-byte word of `T`.
+    /// - Any other size returns `CUDA_ERROR_NOT_SUPPORTED`.
+    ///
+    /// # Safety
+    /// `T` must not contain padding bytes, since the bytes of `value` are read on the host.
+    pub unsafe fn memset<T: DeviceRepr, Dst: DevicePtrMut<T>>(
+        self: &Arc<Self>,
+        dst: &mut Dst,
+        value: T,
+    ) -> Result<(), DriverError> {
+        self.bind_to_thread()?;
+        let size = std::mem::size_of::<T>();
+        if size != 1 && size != 2 && size % 4 != 0 {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_NOT_SUPPORTED));
+        }
+        let len = dst.len();
+        if len == 0 {
+            return Ok(());
+        }
+        let bytes = std::slice::from_raw_parts(&value as *const T as *const u8, size);
+        let (dptr, _record) = dst.device_ptr_mut(self);
+        unsafe {
+            match size {
+                1 => result::memset_d8_async(dptr, bytes[0], len, self.cu_stream),
+                2 => {
+                    let us = u16::from_ne_bytes([bytes[0], bytes[1]]);
+                    result::memset_d16_async(dptr, us, len, self.cu_stream)
+                }
+                4 => {
+                    let ui = u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                    result::memset_d32_async(dptr, ui, len, self.cu_stream)
+                }
+                _ => {
+                    for (i, word) in bytes.chunks_exact(4).enumerate() {
+                        let ui = u32::from_ne_bytes([word[0], word[1], word[2], word[3]]);
+                        let word_ptr = dptr + (i * 4) as sys::CUdeviceptr;
+                        result::memset_d2d32_async(word_ptr, size, ui, 1, len, self.cu_stream)?;
+                    }
+                    Ok(())
+                }
+            }
+        }
+    }
+
     /// Copy a `[T]`/`Vec<T>`/[`PinnedHostSlice<T>`] to a new [`CudaSlice`].
     pub fn memcpy_stod<T: DeviceRepr, Src: HostSlice<T> + ?Sized>(
         self: &Arc<Self>,
@@ -1231,18 +2281,92 @@ impl CudaStream {
         Ok(dst)
     }
 
+    /// Copy each of `srcs` to a new [`CudaSlice`], returned in the same order. All copies are
+    /// ordered on this stream.
+    ///
+    /// If any allocation or copy fails, the slices allocated so far are freed and the error is returned.
+    pub fn memcpy_stod_many<T: DeviceRepr, Src: HostSlice<T> + ?Sized>(
+        self: &Arc<Self>,
+        srcs: &[&Src],
+    ) -> Result<Vec<CudaSlice<T>>, DriverError> {
+        srcs.iter().map(|src| self.memcpy_stod(*src)).collect()
+    }
+
+    /// Allocates a new [CudaSlice] with the items of `iter`, without collecting them into a host `Vec` first.
+    ///
+    /// The items are written into two small pinned staging buffers in turn, so filling one buffer
+    /// overlaps with the copy out of the other. The length of `iter` sizes the device allocation up front.
+    ///
+    /// Returns [sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE] if `iter` yields fewer items than its
+    /// reported length. Any items beyond the reported length are ignored.
+    pub fn alloc_from_iter<T: DeviceRepr, I>(
+        self: &Arc<Self>,
+        iter: I,
+    ) -> Result<CudaSlice<T>, DriverError>
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        const STAGING_BYTES: usize = 1 << 20;
+
+        let mut iter = iter.into_iter();
+        let len = iter.len();
+        let mut dst = unsafe { self.alloc::<T>(len) }?;
+        if len == 0 {
+            return Ok(dst);
+        }
+
+        let chunk_len = (STAGING_BYTES / std::mem::size_of::<T>().max(1)).clamp(1, len);
+        let mut staging = [unsafe { self.ctx.alloc_pinned::<T>(chunk_len) }?, unsafe {
+            self.ctx.alloc_pinned::<T>(chunk_len)
+        }?];
+        for (i, start) in (0..len).step_by(chunk_len).enumerate() {
+            let n = chunk_len.min(len - start);
+            let buf = &mut staging[i % 2];
+            // the previous copy out of this buffer must finish before overwriting it
+            buf.event.synchronize()?;
+            for j in 0..n {
+                let item = iter
+                    .next()
+                    .ok_or(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE))?;
+                unsafe { buf.ptr.add(j).write(item) };
+            }
+            let src = unsafe { std::slice::from_raw_parts(buf.ptr, n) };
+            let mut dst = dst.slice_mut(start..start + n);
+            let (dst, _record_dst) = dst.device_ptr_mut(self);
+            unsafe { result::memcpy_htod_async(dst, src, self.cu_stream) }?;
+            buf.event.record(self)?;
+        }
+        Ok(dst)
+    }
+
     /// Copy a `[T]`/`Vec<T>`/[`PinnedHostSlice<T>`] into an existing [`CudaSlice`]/[`CudaViewMut`].
     pub fn memcpy_htod<T: DeviceRepr, Src: HostSlice<T> + ?Sized, Dst: DevicePtrMut<T>>(
         self: &Arc<Self>,
         src: &Src,
         dst: &mut Dst,
     ) -> Result<(), DriverError> {
+        self.bind_to_thread()?;
         assert!(dst.len() >= src.len());
         let (src, _record_src) = unsafe { src.stream_synced_slice(self) };
         let (dst, _record_dst) = dst.device_ptr_mut(self);
         unsafe { result::memcpy_htod_async(dst, src, self.cu_stream) }
     }
 
+    /// Like [CudaStream::memcpy_htod()] from a [`PinnedHostSlice<T>`], but also records a
+    /// [CudaEvent] (with timing disabled) right after the copy is enqueued, and returns it.
+    ///
+    /// Pass the event to [CudaStream::wait()] on another stream to make it depend on the copy
+    /// without synchronizing the host, e.g. for double buffering.
+    pub fn memcpy_htod_with_event<T: DeviceRepr, Dst: DevicePtrMut<T>>(
+        self: &Arc<Self>,
+        src: &PinnedHostSlice<T>,
+        dst: &mut Dst,
+    ) -> Result<CudaEvent, DriverError> {
+        self.memcpy_htod(src, dst)?;
+        self.record_event(None)
+    }
+
     /// Copy a [`CudaSlice`]/[`CudaView`] to a new [`Vec<T>`].
     pub fn memcpy_dtov<T: DeviceRepr, Src: DevicePtr<T>>(
         self: &Arc<Self>,
@@ -1257,24 +2381,87 @@ impl CudaStream {
         Ok(dst)
     }
 
+    /// Copy only the elements of `src` in `range` to a new [`Vec<T>`], e.g. to read back a small
+    /// window of a large buffer. This is shorthand for `self.memcpy_dtov(&src.slice(range))`.
+    ///
+    /// This synchronizes the stream, so the returned vec is valid immediately.
+    /// Returns [sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE] if `range` is not within `src`.
+    pub fn memcpy_dtov_range<T: DeviceRepr>(
+        self: &Arc<Self>,
+        src: &CudaSlice<T>,
+        range: impl RangeBounds<usize>,
+    ) -> Result<Vec<T>, DriverError> {
+        let src = src
+            .try_slice(range)
+            .ok_or(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE))?;
+        self.memcpy_dtov(&src)
+    }
+
+    /// Copy the single element `src[index]` of a [`CudaSlice`]/[`CudaView`] to the host.
+    ///
+    /// This synchronizes the stream, so the returned value is valid immediately.
+    /// Returns [sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE] if `index` is out of bounds.
+    pub fn memcpy_dtoh_element<T: DeviceRepr, Src: DevicePtr<T>>(
+        self: &Arc<Self>,
+        src: &Src,
+        index: usize,
+    ) -> Result<T, DriverError> {
+        if index >= src.len() {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE));
+        }
+        let mut dst = Vec::with_capacity(1);
+        #[allow(clippy::uninit_vec)]
+        unsafe {
+            dst.set_len(1)
+        };
+        {
+            let (src, _record_src) = src.device_ptr(self);
+            let src = src + (index * std::mem::size_of::<T>()) as sys::CUdeviceptr;
+            unsafe { result::memcpy_dtoh_async(&mut dst, src, self.cu_stream) }?;
+        }
+        self.synchronize()?;
+        Ok(dst.pop().unwrap())
+    }
+
     /// Copy a [`CudaSlice`]/[`CudaView`] to a existing `[T]`/[`Vec<T>`]/[`PinnedHostSlice<T>`].
     pub fn memcpy_dtoh<T: DeviceRepr, Src: DevicePtr<T>, Dst: HostSlice<T> + ?Sized>(
         self: &Arc<Self>,
         src: &Src,
         dst: &mut Dst,
     ) -> Result<(), DriverError> {
+        self.bind_to_thread()?;
         assert!(dst.len() >= src.len());
         let (src, _record_src) = src.device_ptr(self);
         let (dst, _record_dst) = unsafe { dst.stream_synced_mut_slice(self) };
         unsafe { result::memcpy_dtoh_async(dst, src, self.cu_stream) }
     }
 
+    /// Asynchronously copy a [`CudaSlice`]/[`CudaView`] into an existing [`PinnedHostSlice<T>`]
+    /// of the **same** length. This does not synchronize the stream, so compute submitted
+    /// afterwards can overlap with the transfer.
+    ///
+    /// The copy is recorded on `dst`, so [PinnedHostSlice::as_slice()] and friends wait for it
+    /// to finish before giving access to the data.
+    ///
+    /// Returns [sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE] if the lengths differ.
+    pub fn memcpy_dtoh_pinned<T: DeviceRepr, Src: DevicePtr<T>>(
+        self: &Arc<Self>,
+        src: &Src,
+        dst: &mut PinnedHostSlice<T>,
+    ) -> Result<(), DriverError> {
+        if src.len() != dst.len() {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE));
+        }
+        self.memcpy_dtoh(src, dst)
+    }
+
     /// Copy a [`CudaSlice`]/[`CudaView`] to a existing [`CudaSlice`]/[`CudaViewMut`].
     pub fn memcpy_dtod<T, Src: DevicePtr<T>, Dst: DevicePtrMut<T>>(
         self: &Arc<Self>,
         src: &Src,
         dst: &mut Dst,
     ) -> Result<(), DriverError> {
+        self.bind_to_thread()?;
         assert!(dst.len() >= src.len());
         let num_bytes = src.num_bytes();
         let (src, _record_src) = src.device_ptr(self);
@@ -1282,6 +2469,143 @@ impl CudaStream {
         unsafe { result::memcpy_dtod_async(dst, src, num_bytes, self.cu_stream) }
     }
 
+    /// Copies `src[src_range]` into `dst[dst_offset..dst_offset + src_range.len()]`.
+    ///
+    /// Returns [sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE] if either range does not fit
+    /// in its slice, or if the two regions overlap (which can only happen if the slices alias the
+    /// same allocation, e.g. via [CudaStream::upgrade_device_ptr()]). `cuMemcpyDtoDAsync` does not
+    /// support overlapping copies, so for those copy through a temporary buffer instead.
+    pub fn memcpy_dtod_range<T, Src: DevicePtr<T>, Dst: DevicePtrMut<T>>(
+        self: &Arc<Self>,
+        src: &Src,
+        src_range: Range<usize>,
+        dst: &mut Dst,
+        dst_offset: usize,
+    ) -> Result<(), DriverError> {
+        self.bind_to_thread()?;
+        let len = src_range.len();
+        if src_range.start > src_range.end
+            || src_range.end > src.len()
+            || dst_offset
+                .checked_add(len)
+                .map_or(true, |end| end > dst.len())
+        {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE));
+        }
+        let size = std::mem::size_of::<T>();
+        let num_bytes = len * size;
+        let (src_ptr, _record_src) = src.device_ptr(self);
+        let (dst_ptr, _record_dst) = dst.device_ptr_mut(self);
+        let src_ptr = src_ptr + (src_range.start * size) as u64;
+        let dst_ptr = dst_ptr + (dst_offset * size) as u64;
+        if num_bytes > 0
+            && src_ptr < dst_ptr + num_bytes as u64
+            && dst_ptr < src_ptr + num_bytes as u64
+        {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE));
+        }
+        unsafe { result::memcpy_dtod_async(dst_ptr, src_ptr, num_bytes, self.cu_stream) }
+    }
+
+    /// Allocates a new [CudaSlice] holding all of `slices` one after another, using one
+    /// device to device copy per slice. All copies are ordered on `self`.
+    ///
+    /// Returns [sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE] if `slices` is empty.
+    ///
+    /// ```rust
+    /// # use cudarc::driver::safe::CudaContext;
+    /// # let ctx = CudaContext::new(0).unwrap();
+    /// # let stream = ctx.default_stream();
+    /// let a = stream.memcpy_stod(&[1.0f32, 2.0]).unwrap();
+    /// let b = stream.memcpy_stod(&[3.0f32]).unwrap();
+    /// let ab = stream.concat(&[&a, &b]).unwrap();
+    /// assert_eq!(stream.memcpy_dtov(&ab).unwrap(), [1.0, 2.0, 3.0]);
+    /// ```
+    pub fn concat<T: DeviceRepr, Src: DevicePtr<T>>(
+        self: &Arc<Self>,
+        slices: &[&Src],
+    ) -> Result<CudaSlice<T>, DriverError> {
+        if slices.is_empty() {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE));
+        }
+        let len = slices.iter().map(|s| s.len()).sum();
+        let mut dst = unsafe { self.alloc::<T>(len) }?;
+        let mut offset = 0;
+        for src in slices {
+            self.memcpy_dtod(*src, &mut dst.slice_mut(offset..offset + src.len()))?;
+            offset += src.len();
+        }
+        Ok(dst)
+    }
+
+    /// Copy a [`CudaSlice`]/[`CudaView`] allocated in another [CudaContext] into a [`CudaSlice`]/[`CudaViewMut`]
+    /// of this stream's context, without going through host memory.
+    ///
+    /// The contexts are taken from the slices' streams (see [DeviceSlice::stream()]), and `dst` must belong
+    /// to the same context as `self`, otherwise this returns [sys::cudaError_enum::CUDA_ERROR_INVALID_CONTEXT].
+    /// If `dst.len() != src.len()` this returns [sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE].
+    ///
+    /// The copy is ordered after previous work on `src`'s stream, and future work on `src`'s
+    /// stream is ordered after the copy. The copy itself is recorded on `self` like [CudaStream::memcpy_dtod()].
+    ///
+    /// This works whether or not [CudaContext::enable_peer_access()] was called. Without peer access
+    /// the driver stages the copy through host memory, so it is not faster than copying through the host yourself.
+    pub fn memcpy_dtod_peer<T, Src: DevicePtr<T>, Dst: DevicePtrMut<T>>(
+        self: &Arc<Self>,
+        src: &Src,
+        dst: &mut Dst,
+    ) -> Result<(), DriverError> {
+        if dst.stream().ctx != self.ctx {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_CONTEXT));
+        }
+        if dst.len() != src.len() {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE));
+        }
+        let src_stream = src.stream().clone();
+        if src_stream.ctx == self.ctx {
+            return self.memcpy_dtod(src, dst);
+        }
+
+        let num_bytes = src.num_bytes();
+        let (src_ptr, _record_src) = src.device_ptr(&src_stream);
+
+        // make self wait on src's stream
+        let src_ready = src_stream.record_event(None)?;
+        self.bind_to_thread()?;
+        unsafe {
+            result::stream::wait_event(
+                self.cu_stream,
+                src_ready.cu_event,
+                sys::CUevent_wait_flags::CU_EVENT_WAIT_DEFAULT,
+            )
+        }?;
+
+        {
+            let (dst_ptr, _record_dst) = dst.device_ptr_mut(self);
+            unsafe {
+                result::memcpy_peer_async(
+                    dst_ptr,
+                    self.ctx.cu_ctx,
+                    src_ptr,
+                    src_stream.ctx.cu_ctx,
+                    num_bytes,
+                    self.cu_stream,
+                )
+            }?;
+        }
+
+        // make src's stream wait on the copy, so `src` isn't written/freed too early
+        let copied = self.record_event(None)?;
+        src_stream.ctx.bind_to_thread()?;
+        unsafe {
+            result::stream::wait_event(
+                src_stream.cu_stream,
+                copied.cu_event,
+                sys::CUevent_wait_flags::CU_EVENT_WAIT_DEFAULT,
+            )
+        }
+    }
+
     /// Copy a [`CudaSlice`]/[`CudaView`] to a new [`CudaSlice`].
     pub fn clone_dtod<T: DeviceRepr, Src: DevicePtr<T>>(
         self: &Arc<Self>,
@@ -1424,6 +2748,37 @@ impl<T> CudaSlice<T> {
         )
     }
 
+    /// Reinterprets the whole allocation as elements of type `U`, without copying.
+    /// Unlike [CudaSlice::transmute()] this consumes `self`, so the returned [CudaSlice]
+    /// takes over ownership of the memory. The new length is `self.num_bytes() / size_of::<U>()`.
+    ///
+    /// Returns [sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE] (and drops `self`) if the number
+    /// of bytes is not a multiple of `size_of::<U>()`, if `U` is zero sized, or if the device
+    /// pointer is not aligned to `align_of::<U>()`.
+    ///
+    /// # Safety
+    /// The memory may not be a valid interpretation for the type `U`.
+    pub unsafe fn transmute_into<U>(self) -> Result<CudaSlice<U>, DriverError> {
+        let num_bytes = self.num_bytes();
+        let size = std::mem::size_of::<U>();
+        if size == 0
+            || num_bytes % size != 0
+            || self.cu_device_ptr % std::mem::align_of::<U>() as u64 != 0
+        {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE));
+        }
+        let slice = std::mem::ManuallyDrop::new(self);
+        Ok(CudaSlice {
+            cu_device_ptr: slice.cu_device_ptr,
+            len: num_bytes / size,
+            read: std::ptr::read(&slice.read),
+            write: std::ptr::read(&slice.write),
+            stream: std::ptr::read(&slice.stream),
+            owner: std::ptr::read(&slice.owner),
+            marker: PhantomData,
+        })
+    }
+
     pub fn split_at(&self, mid: usize) -> (CudaView<'_, T>, CudaView<'_, T>) {
         self.try_split_at(mid).unwrap()
     }
@@ -1463,8 +2818,9 @@ impl<T> CudaSlice<T> {
         mid: usize,
     ) -> Option<(CudaViewMut<'_, T>, CudaViewMut<'_, T>)> {
         (mid <= self.len()).then(|| {
+            let len = self.len;
             let view = self.as_view_mut();
-            (view.resize(0, mid), view.resize(mid, self.len))
+            (view.resize(0, mid), view.resize(mid, len))
         })
     }
 }
@@ -1591,12 +2947,12 @@ impl<'a, T> CudaViewMut<'a, T> {
     /// Creates a [CudaViewMut] at the specified offset from the start of `self`.
     ///
     /// Panics if `range` and `0...self.len()` are not overlapping.
-    pub fn slice_mut(&mut self, bounds: impl RangeBounds<usize>) -> Self {
+    pub fn slice_mut(&mut self, bounds: impl RangeBounds<usize>) -> CudaViewMut<'_, T> {
         self.try_slice_mut(bounds).unwrap()
     }
 
     /// Fallible version of [CudaViewMut::slice_mut]
-    pub fn try_slice_mut(&mut self, bounds: impl RangeBounds<usize>) -> Option<Self> {
+    pub fn try_slice_mut(&mut self, bounds: impl RangeBounds<usize>) -> Option<CudaViewMut<'_, T>> {
         to_range(bounds, self.len).map(|(start, end)| self.resize(start, end))
     }
 
@@ -1615,14 +2971,31 @@ impl<'a, T> CudaViewMut<'a, T> {
     /// // split the view into two non-overlapping, mutable views
     /// let (mut view1, mut view2) = view.split_at_mut(25);
     /// do_something(view1, view2);
-    pub fn split_at_mut(&mut self, mid: usize) -> (Self, Self) {
-        self.try_split_at_mut(mid).unwrap()
-    }
-
-    /// Fallible version of [CudaViewMut::split_at_mut].
+    /// ```
     ///
-    /// Returns `None` if `mid > self.len`
-    pub fn try_split_at_mut(&mut self, mid: usize) -> Option<(Self, Self)> {
+    /// The halves borrow from `self`, so `self` cannot be split again while they are alive:
+    /// ```rust,compile_fail
+    /// # use cudarc::driver::safe::{CudaContext, CudaSlice, CudaViewMut};
+    /// # fn do_something(view: CudaViewMut<u8>, view2: CudaViewMut<u8>) {}
+    /// # let ctx = CudaContext::new(0).unwrap();
+    /// # let stream = ctx.default_stream();
+    /// let mut slice = stream.alloc_zeros::<u8>(100).unwrap();
+    /// let mut view = slice.slice_mut(0..50);
+    /// let (view1, _) = view.split_at_mut(25);
+    /// let (view2, _) = view.split_at_mut(25);
+    /// do_something(view1, view2);
+    /// ```
+    pub fn split_at_mut(&mut self, mid: usize) -> (CudaViewMut<'_, T>, CudaViewMut<'_, T>) {
+        self.try_split_at_mut(mid).unwrap()
+    }
+
+    /// Fallible version of [CudaViewMut::split_at_mut].
+    ///
+    /// Returns `None` if `mid > self.len`
+    pub fn try_split_at_mut(
+        &mut self,
+        mid: usize,
+    ) -> Option<(CudaViewMut<'_, T>, CudaViewMut<'_, T>)> {
         (mid <= self.len()).then(|| (self.resize(0, mid), self.resize(mid, self.len)))
     }
 
@@ -1658,7 +3031,7 @@ fn to_range(range: impl RangeBounds<usize>, len: usize) -> Option<(usize, usize)
         Bound::Excluded(&n) => n,
         Bound::Unbounded => len,
     };
-    (end <= len).then_some((start, end))
+    (start <= end && end <= len).then_some((start, end))
 }
 
 /// Wrapper around [sys::CUmodule]. Create with [CudaContext::load_module()].
@@ -1691,7 +3064,7 @@ impl CudaContext {
     ) -> Result<Arc<CudaModule>, result::DriverError> {
         self.bind_to_thread()?;
 
-        let cu_module = match ptx.0 {
+        let cu_module = match ptx.kind {
             crate::nvrtc::PtxKind::Image(image) => unsafe {
                 result::module::load_data(image.as_ptr() as *const _)
             },
@@ -1711,6 +3084,98 @@ impl CudaContext {
     }
 }
 
+impl CudaContext {
+    /// Load a module from a `.ptx`, `.cubin` or `.fatbin` file at `path` into this context.
+    /// The driver reads the file directly, so the contents are never copied into host memory by us.
+    ///
+    /// Returns [sys::cudaError_enum::CUDA_ERROR_FILE_NOT_FOUND] if the file does not exist,
+    /// and [sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE] if `path` is not valid utf8 or contains a null byte.
+    /// Any other error comes from the driver rejecting the contents of the file.
+    pub fn load_module_from_file<P: AsRef<std::path::Path>>(
+        self: &Arc<Self>,
+        path: P,
+    ) -> Result<Arc<CudaModule>, result::DriverError> {
+        let fname = path
+            .as_ref()
+            .to_str()
+            .and_then(|p| CString::new(p).ok())
+            .ok_or(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE))?;
+        self.bind_to_thread()?;
+        let cu_module = result::module::load(fname)?;
+        Ok(Arc::new(CudaModule {
+            cu_module,
+            ctx: self.clone(),
+        }))
+    }
+
+    /// Load a cubin from [crate::nvrtc::compile_cubin()] into this context. Unlike [CudaContext::load_module()],
+    /// this does not need to JIT compile anything.
+    ///
+    /// If the cubin was compiled for an incompatible architecture, this returns
+    /// [sys::cudaError_enum::CUDA_ERROR_NO_BINARY_FOR_GPU].
+    pub fn load_cubin(
+        self: &Arc<Self>,
+        cubin: crate::nvrtc::Cubin,
+    ) -> Result<Arc<CudaModule>, result::DriverError> {
+        self.bind_to_thread()?;
+        let cu_module = unsafe { result::module::load_data(cubin.image.as_ptr() as *const _) }?;
+        Ok(Arc::new(CudaModule {
+            cu_module,
+            ctx: self.clone(),
+        }))
+    }
+
+    /// Compiles `code` (the source of a `.cu` file) for this device's architecture and loads it,
+    /// caching the compiled cubin in `cache_dir`, so loading the same code again (e.g. on the next
+    /// program start) neither compiles nor JIT compiles anything.
+    ///
+    /// The cache key includes the source, the device architecture and the nvrtc version, so stale
+    /// cubins are not reused after editing the kernel or upgrading the toolkit.
+    /// See [crate::nvrtc::compile_cubin_cached()] for details.
+    ///
+    /// The installed nvrtc must support this device's architecture.
+    pub fn load_ptx_cached<P: AsRef<std::path::Path>>(
+        self: &Arc<Self>,
+        code: &str,
+        cache_dir: P,
+    ) -> Result<Arc<CudaModule>, LoadCachedError> {
+        let (major, minor) = self.compute_capability()?;
+        let opts = crate::nvrtc::CompileOptions::default()
+            .option(std::format!("--gpu-architecture=sm_{major}{minor}"));
+        let cubin = crate::nvrtc::compile_cubin_cached(code, opts, cache_dir)?;
+        Ok(self.load_cubin(cubin)?)
+    }
+}
+
+/// Error from [CudaContext::load_ptx_cached()], which can fail either compiling or loading the module.
+#[derive(Debug)]
+pub enum LoadCachedError {
+    Compile(crate::nvrtc::CompileError),
+    Driver(DriverError),
+}
+
+impl From<crate::nvrtc::CompileError> for LoadCachedError {
+    fn from(err: crate::nvrtc::CompileError) -> Self {
+        Self::Compile(err)
+    }
+}
+
+impl From<DriverError> for LoadCachedError {
+    fn from(err: DriverError) -> Self {
+        Self::Driver(err)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for LoadCachedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LoadCachedError {}
+
 /// Wrapper around [sys::CUfunction]. Used by [CudaStream::launch_builder] to execute kernels.
 #[derive(Debug, Clone)]
 pub struct CudaFunction {
@@ -1732,6 +3197,97 @@ impl CudaModule {
             module: self.clone(),
         })
     }
+
+    /// Returns a [CudaSlice] view of the `__device__` (or `__constant__`) global variable `name`
+    /// in this module, with `len` elements of type `T`. The slice can be written with the
+    /// usual copy methods such as [CudaStream::memcpy_htod()].
+    ///
+    /// The memory is owned by the module, so it is **not** freed when the slice is dropped.
+    /// The slice keeps the module loaded while it is alive. It is associated with the
+    /// default stream of the module's context.
+    ///
+    /// Returns [sys::cudaError_enum::CUDA_ERROR_NOT_FOUND] if there is no global named `name`,
+    /// and [sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE] if `len * size_of::<T>()`
+    /// is not the size of the global.
+    ///
+    /// # Safety
+    /// The global must actually hold `len` values of type `T`.
+    pub unsafe fn get_global<T: DeviceRepr>(
+        self: &Arc<Self>,
+        name: &str,
+        len: usize,
+    ) -> Result<CudaSlice<T>, DriverError> {
+        self.ctx.bind_to_thread()?;
+        let name_c = CString::new(name).unwrap();
+        let (cu_device_ptr, num_bytes) = result::module::get_global(self.cu_module, name_c)?;
+        if len * std::mem::size_of::<T>() != num_bytes {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE));
+        }
+        let (read, write) = if self.ctx.is_event_tracking() {
+            (
+                Some(self.ctx.new_event(None)?),
+                Some(self.ctx.new_event(None)?),
+            )
+        } else {
+            (None, None)
+        };
+        Ok(CudaSlice {
+            cu_device_ptr,
+            len,
+            read,
+            write,
+            stream: self.ctx.default_stream(),
+            owner: SliceOwner::Module(self.clone()),
+            marker: PhantomData,
+        })
+    }
+
+    /// Returns the names of all the kernels in this module. The names are mangled
+    /// unless the kernel was declared `extern "C"`.
+    ///
+    /// This requires CUDA 12.4 or newer (`cuModuleEnumerateFunctions`). When compiled for an
+    /// older version, this returns [sys::cudaError_enum::CUDA_ERROR_NOT_SUPPORTED].
+    #[cfg(not(any(
+        feature = "cuda-11040",
+        feature = "cuda-11050",
+        feature = "cuda-11060",
+        feature = "cuda-11070",
+        feature = "cuda-11080",
+        feature = "cuda-12000",
+        feature = "cuda-12010",
+        feature = "cuda-12020",
+        feature = "cuda-12030"
+    )))]
+    pub fn function_names(&self) -> Result<Vec<String>, DriverError> {
+        self.ctx.bind_to_thread()?;
+        let functions = unsafe { result::module::enumerate_functions(self.cu_module) }?;
+        functions
+            .into_iter()
+            .map(|f| {
+                let name = unsafe { result::function::get_name(f) }?;
+                Ok(name.to_string_lossy().into_owned())
+            })
+            .collect()
+    }
+
+    /// Returns the names of all the kernels in this module.
+    ///
+    /// This requires CUDA 12.4 or newer (`cuModuleEnumerateFunctions`), so this always
+    /// returns [sys::cudaError_enum::CUDA_ERROR_NOT_SUPPORTED].
+    #[cfg(any(
+        feature = "cuda-11040",
+        feature = "cuda-11050",
+        feature = "cuda-11060",
+        feature = "cuda-11070",
+        feature = "cuda-11080",
+        feature = "cuda-12000",
+        feature = "cuda-12010",
+        feature = "cuda-12020",
+        feature = "cuda-12030"
+    ))]
+    pub fn function_names(&self) -> Result<Vec<String>, DriverError> {
+        Err(DriverError(sys::cudaError_enum::CUDA_ERROR_NOT_SUPPORTED))
+    }
 }
 
 impl CudaFunction {
@@ -1778,6 +3334,27 @@ impl CudaFunction {
         Ok(num_blocks as u32)
     }
 
+    /// The largest grid (in blocks) that can be used with [crate::driver::LaunchArgs::launch_cooperative()] for `block_size`
+    /// threads per block and `dynamic_smem_size` bytes of dynamic shared memory.
+    ///
+    /// This is [CudaFunction::occupancy_max_active_blocks_per_multiprocessor()] times the number of multiprocessors.
+    pub fn max_cooperative_grid_size(
+        &self,
+        block_size: u32,
+        dynamic_smem_size: usize,
+    ) -> Result<u32, result::DriverError> {
+        let blocks_per_sm = self.occupancy_max_active_blocks_per_multiprocessor(
+            block_size,
+            dynamic_smem_size,
+            None,
+        )?;
+        let num_sms = self
+            .module
+            .ctx
+            .attribute(sys::CUdevice_attribute::CU_DEVICE_ATTRIBUTE_MULTIPROCESSOR_COUNT)?;
+        Ok(blocks_per_sm * num_sms as u32)
+    }
+
     #[cfg(not(any(
         feature = "cuda-11070",
         feature = "cuda-11060",
@@ -1838,6 +3415,91 @@ impl CudaFunction {
         Ok((min_grid_size as u32, block_size as u32))
     }
 
+    /// Suggests a [crate::driver::LaunchConfig] for `num_elems` elements (one thread per element), using the
+    /// block size that maximizes occupancy according to [cuOccupancyMaxPotentialBlockSize](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__OCCUPANCY.html).
+    ///
+    /// `dynamic_smem_size` is the dynamic shared memory used per block, and is also set as
+    /// [crate::driver::LaunchConfig::shared_mem_bytes]. A `block_size_limit` of 0 means no limit.
+    ///
+    /// Use [CudaFunction::suggested_launch_config_with_smem()] if the shared memory depends on the block size.
+    pub fn suggested_launch_config(
+        &self,
+        num_elems: u32,
+        dynamic_smem_size: usize,
+        block_size_limit: u32,
+    ) -> Result<crate::driver::LaunchConfig, result::DriverError> {
+        let (_, block_size) = unsafe {
+            result::occupancy::max_potential_block_size(
+                self.cu_function,
+                None,
+                dynamic_smem_size,
+                block_size_limit as std::ffi::c_int,
+            )
+        }?;
+        let block_size = block_size as u32;
+        Ok(crate::driver::LaunchConfig {
+            grid_dim: (num_elems.div_ceil(block_size), 1, 1),
+            block_dim: (block_size, 1, 1),
+            shared_mem_bytes: dynamic_smem_size as u32,
+        })
+    }
+
+    /// Same as [CudaFunction::suggested_launch_config()], but for kernels whose dynamic shared memory scales
+    /// with the block size. `block_size_to_dynamic_smem_size` maps a block size to the bytes of dynamic
+    /// shared memory per block.
+    ///
+    /// This tries every multiple of the warp size up to the function's max threads per block (or `block_size_limit`
+    /// if it is non-zero), and picks the one with the most active threads per multiprocessor, preferring larger blocks.
+    ///
+    /// If no block size can be launched, this returns [sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE].
+    pub fn suggested_launch_config_with_smem(
+        &self,
+        num_elems: u32,
+        block_size_to_dynamic_smem_size: impl Fn(u32) -> usize,
+        block_size_limit: u32,
+    ) -> Result<crate::driver::LaunchConfig, result::DriverError> {
+        let ctx = &self.module.ctx;
+        let warp_size =
+            ctx.attribute(sys::CUdevice_attribute::CU_DEVICE_ATTRIBUTE_WARP_SIZE)? as u32;
+        let max_threads = unsafe {
+            result::function::get_function_attribute(
+                self.cu_function,
+                CUfunction_attribute_enum::CU_FUNC_ATTRIBUTE_MAX_THREADS_PER_BLOCK,
+            )
+        }? as u32;
+        let limit = match block_size_limit {
+            0 => max_threads,
+            limit => limit.min(max_threads),
+        };
+
+        let mut best: Option<(u32, usize)> = None;
+        let mut best_occupancy = 0;
+        let mut block_size = limit - limit % warp_size;
+        while block_size > 0 {
+            let smem = block_size_to_dynamic_smem_size(block_size);
+            let num_blocks = unsafe {
+                result::occupancy::max_active_block_per_multiprocessor(
+                    self.cu_function,
+                    block_size as std::ffi::c_int,
+                    smem,
+                )
+            }? as u32;
+            if num_blocks * block_size > best_occupancy {
+                best_occupancy = num_blocks * block_size;
+                best = Some((block_size, smem));
+            }
+            block_size -= warp_size;
+        }
+
+        let (block_size, smem) =
+            best.ok_or(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE))?;
+        Ok(crate::driver::LaunchConfig {
+            grid_dim: (num_elems.div_ceil(block_size), 1, 1),
+            block_dim: (block_size, 1, 1),
+            shared_mem_bytes: smem as u32,
+        })
+    }
+
     #[cfg(not(any(
         feature = "cuda-11070",
         feature = "cuda-11060",
@@ -1872,6 +3534,75 @@ impl CudaFunction {
         Ok(cluster_size as u32)
     }
 
+    /// Get the value of a specific attribute of this [CudaFunction].
+    pub fn attribute(
+        &self,
+        attribute: CUfunction_attribute_enum,
+    ) -> Result<i32, result::DriverError> {
+        unsafe { result::function::get_function_attribute(self.cu_function, attribute) }
+    }
+
+    /// The number of registers used by each thread of this function
+    /// ([CUfunction_attribute_enum::CU_FUNC_ATTRIBUTE_NUM_REGS]).
+    pub fn num_registers(&self) -> Result<u32, result::DriverError> {
+        self.attribute(CUfunction_attribute_enum::CU_FUNC_ATTRIBUTE_NUM_REGS)
+            .map(|v| v as u32)
+    }
+
+    /// The bytes of statically allocated shared memory per block, excluding dynamic shared memory
+    /// ([CUfunction_attribute_enum::CU_FUNC_ATTRIBUTE_SHARED_SIZE_BYTES]).
+    pub fn shared_size_bytes(&self) -> Result<usize, result::DriverError> {
+        self.attribute(CUfunction_attribute_enum::CU_FUNC_ATTRIBUTE_SHARED_SIZE_BYTES)
+            .map(|v| v as usize)
+    }
+
+    /// The bytes of user allocated constant memory used by this function
+    /// ([CUfunction_attribute_enum::CU_FUNC_ATTRIBUTE_CONST_SIZE_BYTES]).
+    pub fn const_size_bytes(&self) -> Result<usize, result::DriverError> {
+        self.attribute(CUfunction_attribute_enum::CU_FUNC_ATTRIBUTE_CONST_SIZE_BYTES)
+            .map(|v| v as usize)
+    }
+
+    /// The bytes of local memory used by each thread, e.g. for register spills
+    /// ([CUfunction_attribute_enum::CU_FUNC_ATTRIBUTE_LOCAL_SIZE_BYTES]).
+    pub fn local_size_bytes(&self) -> Result<usize, result::DriverError> {
+        self.attribute(CUfunction_attribute_enum::CU_FUNC_ATTRIBUTE_LOCAL_SIZE_BYTES)
+            .map(|v| v as usize)
+    }
+
+    /// Allows launching this function with up to `num_bytes` of dynamic shared memory per block,
+    /// by setting [CUfunction_attribute_enum::CU_FUNC_ATTRIBUTE_MAX_DYNAMIC_SHARED_SIZE_BYTES].
+    ///
+    /// Launches that use more than 48KB of dynamic shared memory (see [crate::driver::LaunchConfig::shared_mem_bytes])
+    /// fail unless this is called before the launch. The maximum value for the device is
+    /// [sys::CUdevice_attribute::CU_DEVICE_ATTRIBUTE_MAX_SHARED_MEMORY_PER_BLOCK_OPTIN] minus the static shared
+    /// memory used by the function ([CUfunction_attribute_enum::CU_FUNC_ATTRIBUTE_SHARED_SIZE_BYTES]).
+    pub fn set_max_dynamic_shared_size(&self, num_bytes: u32) -> Result<(), result::DriverError> {
+        self.set_attribute(
+            CUfunction_attribute_enum::CU_FUNC_ATTRIBUTE_MAX_DYNAMIC_SHARED_SIZE_BYTES,
+            num_bytes as i32,
+        )
+    }
+
+    /// Sets the preferred percentage of the unified L1/shared memory to use as shared memory
+    /// ([CUfunction_attribute_enum::CU_FUNC_ATTRIBUTE_PREFERRED_SHARED_MEMORY_CARVEOUT]).
+    ///
+    /// `percent` is either in `0..=100`, or one of the [sys::CUshared_carveout] values, e.g.
+    /// `sys::CUshared_carveout::CU_SHAREDMEM_CARVEOUT_MAX_L1 as i32` for kernels that barely use shared memory.
+    /// This is only a hint, the driver rounds to a supported configuration.
+    ///
+    /// Returns [sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE] if `percent` is out of range.
+    pub fn set_shared_memory_carveout(&self, percent: i32) -> Result<(), result::DriverError> {
+        let default = sys::CUshared_carveout::CU_SHAREDMEM_CARVEOUT_DEFAULT as i32;
+        if percent != default && !(0..=100).contains(&percent) {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE));
+        }
+        self.set_attribute(
+            CUfunction_attribute_enum::CU_FUNC_ATTRIBUTE_PREFERRED_SHARED_MEMORY_CARVEOUT,
+            percent,
+        )
+    }
+
     /// Set the value of a specific attribute of this [CudaFunction].
     pub fn set_attribute(
         &self,
@@ -1894,6 +3625,13 @@ impl<T> CudaSlice<T> {
     /// Takes ownership of the underlying [sys::CUdeviceptr]. **It is up
     /// to the owner to free this value**.
     ///
+    /// For slices from [CudaContext::import_ipc_handle()], the owner must close it with
+    /// [result::ipc::close_mem_handle()] instead of freeing it.
+    ///
+    /// For slices allocated with guards (see [CudaContext::set_alloc_guard_bytes()]) or
+    /// with [CudaContext::alloc_aligned()], the allocation that must be freed starts before the
+    /// returned pointer.
+    ///
     /// Drops the underlying host_buf if there is one.
     pub fn leak(self) -> sys::CUdeviceptr {
         let ctx = &self.stream.ctx;
@@ -1914,6 +3652,9 @@ impl<T> CudaSlice<T> {
         // drop self.stream
         unsafe { Arc::decrement_strong_count(Arc::as_ptr(&self.stream)) };
 
+        // drop self.owner
+        drop(unsafe { std::ptr::read(&self.owner) });
+
         let ptr = self.cu_device_ptr;
         std::mem::forget(self);
         ptr
@@ -1948,6 +3689,7 @@ impl CudaStream {
             read,
             write,
             stream: self.clone(),
+            owner: SliceOwner::Stream,
             marker: PhantomData,
         }
     }
@@ -1960,101 +3702,583 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_transmutes() {
-        let ctx = CudaContext::new(0).unwrap();
-        let stream = ctx.default_stream();
-        let mut slice = stream.alloc_zeros::<u8>(100).unwrap();
-        assert!(unsafe { slice.transmute::<f32>(25) }.is_some());
-        assert!(unsafe { slice.transmute::<f32>(26) }.is_none());
-        assert!(unsafe { slice.transmute_mut::<f32>(25) }.is_some());
-        assert!(unsafe { slice.transmute_mut::<f32>(26) }.is_none());
-
-        {
-            let view = slice.slice(0..100);
-            assert!(unsafe { view.transmute::<f32>(25) }.is_some());
-            assert!(unsafe { view.transmute::<f32>(26) }.is_none());
+    fn test_peer_access() {
+        if CudaContext::device_count().unwrap() < 2 {
+            return;
         }
-
-        {
-            let mut view_mut = slice.slice_mut(0..100);
-            assert!(unsafe { view_mut.transmute::<f32>(25) }.is_some());
-            assert!(unsafe { view_mut.transmute::<f32>(26) }.is_none());
-            assert!(unsafe { view_mut.transmute_mut::<f32>(25) }.is_some());
-            assert!(unsafe { view_mut.transmute_mut::<f32>(26) }.is_none());
+        let ctx0 = CudaContext::new(0).unwrap();
+        let ctx1 = CudaContext::new(1).unwrap();
+        if !ctx0.can_access_peer(&ctx1).unwrap() {
+            return;
         }
+        ctx0.enable_peer_access(&ctx1).unwrap();
+        assert!(ctx0.enable_peer_access(&ctx1).is_err());
+        ctx0.disable_peer_access(&ctx1).unwrap();
+        assert!(ctx0.disable_peer_access(&ctx1).is_err());
     }
 
     #[test]
-    fn test_threading() {
-        let ctx1 = CudaContext::new(0).unwrap();
-        let ctx2 = ctx1.clone();
+    fn test_memcpy_dtod_peer() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let src = stream.memcpy_stod(&[1.0f32, 2.0, 3.0]).unwrap();
+        let mut dst = stream.alloc_zeros::<f32>(3).unwrap();
+        stream.memcpy_dtod_peer(&src, &mut dst).unwrap();
+        assert_eq!(stream.memcpy_dtov(&dst).unwrap(), [1.0, 2.0, 3.0]);
 
-        let thread1 = std::thread::spawn(move || {
-            ctx1.bind_to_thread()?;
-            ctx1.default_stream().alloc_zeros::<f32>(10)
-        });
-        let thread2 = std::thread::spawn(move || {
-            ctx2.bind_to_thread()?;
-            ctx2.default_stream().alloc_zeros::<f32>(10)
-        });
+        let mut too_long = stream.alloc_zeros::<f32>(4).unwrap();
+        assert_eq!(
+            stream.memcpy_dtod_peer(&src, &mut too_long),
+            Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE))
+        );
 
-        let _: crate::driver::CudaSlice<f32> = thread1.join().unwrap().unwrap();
-        let _: crate::driver::CudaSlice<f32> = thread2.join().unwrap().unwrap();
+        if CudaContext::device_count().unwrap() < 2 {
+            return;
+        }
+        let ctx1 = CudaContext::new(1).unwrap();
+        let stream1 = ctx1.default_stream();
+        let mut dst1 = stream1.alloc_zeros::<f32>(3).unwrap();
+        stream1.memcpy_dtod_peer(&src, &mut dst1).unwrap();
+        assert_eq!(stream1.memcpy_dtov(&dst1).unwrap(), [1.0, 2.0, 3.0]);
     }
 
     #[test]
-    fn test_post_build_arc_count() {
+    fn test_load_module_from_file() {
         let ctx = CudaContext::new(0).unwrap();
-        assert_eq!(Arc::strong_count(&ctx), 1);
+        let ptx = crate::nvrtc::compile_ptx("extern \"C\" __global__ void kernel() { }").unwrap();
+        let path = std::env::temp_dir().join("cudarc_test_load_module_from_file.ptx");
+        std::fs::write(&path, ptx.to_src()).unwrap();
+        let module = ctx.load_module_from_file(&path).unwrap();
+        module.load_function("kernel").unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            ctx.load_module_from_file(&path).unwrap_err(),
+            DriverError(sys::cudaError_enum::CUDA_ERROR_FILE_NOT_FOUND)
+        );
     }
 
     #[test]
-    fn test_post_alloc_arc_counts() {
+    #[cfg(not(any(
+        feature = "cuda-11040",
+        feature = "cuda-11050",
+        feature = "cuda-11060",
+        feature = "cuda-11070",
+        feature = "cuda-11080",
+        feature = "cuda-12000",
+        feature = "cuda-12010",
+        feature = "cuda-12020",
+        feature = "cuda-12030"
+    )))]
+    fn test_module_function_names() {
         let ctx = CudaContext::new(0).unwrap();
-        assert_eq!(Arc::strong_count(&ctx), 1);
-        let stream = ctx.default_stream();
-        assert_eq!(Arc::strong_count(&ctx), 2);
-        let t = stream.alloc_zeros::<f32>(1).unwrap();
-        assert_eq!(Arc::strong_count(&ctx), 4);
-        assert_eq!(Arc::strong_count(&stream), 2);
-        drop(t);
-        assert_eq!(Arc::strong_count(&ctx), 2);
-        assert_eq!(Arc::strong_count(&stream), 1);
-        drop(stream);
-        assert_eq!(Arc::strong_count(&ctx), 1);
+        let ptx = crate::nvrtc::compile_ptx(
+            "extern \"C\" __global__ void first() { }
+            extern \"C\" __global__ void second() { }",
+        )
+        .unwrap();
+        let module = ctx.load_module(ptx).unwrap();
+        let mut names = module.function_names().unwrap();
+        names.sort();
+        assert_eq!(names, ["first", "second"]);
     }
 
     #[test]
-    #[ignore = "must be executed by itself"]
-    fn test_post_alloc_memory() {
+    fn test_module_get_global() {
+        use crate::driver::PushKernelArg;
         let ctx = CudaContext::new(0).unwrap();
         let stream = ctx.default_stream();
+        let ptx = crate::nvrtc::compile_ptx(
+            "__constant__ float table[4];
+            extern \"C\" __global__ void lookup(float *out) {
+                out[threadIdx.x] = table[threadIdx.x];
+            }",
+        )
+        .unwrap();
+        let module = ctx.load_module(ptx).unwrap();
+        let f = module.load_function("lookup").unwrap();
 
-        let (free1, total1) = result::mem_get_info().unwrap();
-
-        let t = stream.memcpy_stod(&[0.0f32; 5]).unwrap();
-        let (free2, total2) = result::mem_get_info().unwrap();
-        assert_eq!(total1, total2);
-        assert!(free2 < free1);
+        assert_eq!(
+            unsafe { module.get_global::<f32>("table", 3) }.unwrap_err(),
+            DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE)
+        );
+        assert_eq!(
+            unsafe { module.get_global::<f32>("missing", 4) }.unwrap_err(),
+            DriverError(sys::cudaError_enum::CUDA_ERROR_NOT_FOUND)
+        );
 
-        drop(t);
-        ctx.synchronize().unwrap();
+        let mut table = unsafe { module.get_global::<f32>("table", 4) }.unwrap();
+        stream
+            .memcpy_htod(&[1.0f32, 2.0, 3.0, 4.0], &mut table)
+            .unwrap();
+        let mut out = stream.alloc_zeros::<f32>(4).unwrap();
+        let cfg = crate::driver::LaunchConfig {
+            grid_dim: (1, 1, 1),
+            block_dim: (4, 1, 1),
+            shared_mem_bytes: 0,
+        };
+        let mut builder = stream.launch_builder(&f);
+        builder.arg(&mut out);
+        unsafe { builder.launch(cfg) }.unwrap();
+        assert_eq!(stream.memcpy_dtov(&out).unwrap(), [1.0, 2.0, 3.0, 4.0]);
 
-        let (free3, total3) = result::mem_get_info().unwrap();
-        assert_eq!(total2, total3);
-        assert!(free3 > free2);
-        assert_eq!(free3, free1);
+        // the global keeps the module loaded
+        drop(f);
+        drop(module);
+        assert_eq!(stream.memcpy_dtov(&table).unwrap(), [1.0, 2.0, 3.0, 4.0]);
     }
 
     #[test]
-    fn test_ctx_copy_to_views() {
+    fn test_function_resource_usage() {
         let ctx = CudaContext::new(0).unwrap();
-        let stream = ctx.default_stream();
+        let ptx = crate::nvrtc::compile_ptx(
+            "extern \"C\" __global__ void empty() { }
+            extern \"C\" __global__ void uses_smem(float *out) {
+                __shared__ float buf[256];
+                buf[threadIdx.x] = threadIdx.x;
+                __syncthreads();
+                out[threadIdx.x] = buf[255 - threadIdx.x];
+            }",
+        )
+        .unwrap();
+        let module = ctx.load_module(ptx).unwrap();
 
-        let smalls = [
-            stream.memcpy_stod(&[-1.0f32, -0.8]).unwrap(),
-            stream.memcpy_stod(&[-0.6, -0.4]).unwrap(),
-            stream.memcpy_stod(&[-0.2, 0.0]).unwrap(),
+        let empty = module.load_function("empty").unwrap();
+        assert_eq!(empty.shared_size_bytes().unwrap(), 0);
+        assert_eq!(empty.local_size_bytes().unwrap(), 0);
+        assert_eq!(empty.const_size_bytes().unwrap(), 0);
+
+        let f = module.load_function("uses_smem").unwrap();
+        assert!(f.num_registers().unwrap() > 0);
+        assert_eq!(f.shared_size_bytes().unwrap(), 256 * 4);
+    }
+
+    #[test]
+    fn test_shared_memory_carveout() {
+        let ctx = CudaContext::new(0).unwrap();
+        let ptx = crate::nvrtc::compile_ptx("extern \"C\" __global__ void kernel() { }").unwrap();
+        let f = ctx
+            .load_module(ptx)
+            .unwrap()
+            .load_function("kernel")
+            .unwrap();
+        let attr = CUfunction_attribute_enum::CU_FUNC_ATTRIBUTE_PREFERRED_SHARED_MEMORY_CARVEOUT;
+
+        f.set_shared_memory_carveout(25).unwrap();
+        assert_eq!(f.attribute(attr).unwrap(), 25);
+        f.set_shared_memory_carveout(sys::CUshared_carveout::CU_SHAREDMEM_CARVEOUT_MAX_L1 as i32)
+            .unwrap();
+        assert_eq!(f.attribute(attr).unwrap(), 0);
+        f.set_shared_memory_carveout(sys::CUshared_carveout::CU_SHAREDMEM_CARVEOUT_DEFAULT as i32)
+            .unwrap();
+
+        for percent in [-2, 101] {
+            assert_eq!(
+                f.set_shared_memory_carveout(percent).unwrap_err(),
+                DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE)
+            );
+        }
+    }
+
+    #[test]
+    fn test_device_attributes() {
+        let ctx = CudaContext::new(0).unwrap();
+        let (major, minor) = ctx.compute_capability().unwrap();
+        assert!(major >= 3);
+        assert_eq!(
+            ctx.attribute(DeviceAttribute::ComputeCapabilityMinor)
+                .unwrap() as u32,
+            minor
+        );
+        assert!(ctx.multiprocessor_count().unwrap() > 0);
+        assert_eq!(
+            ctx.attribute(DeviceAttribute::WarpSize).unwrap(),
+            ctx.attribute(sys::CUdevice_attribute::CU_DEVICE_ATTRIBUTE_WARP_SIZE)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_device_name_and_uuid() {
+        let ctx = CudaContext::new(0).unwrap();
+        let name = ctx.name().unwrap();
+        assert!(!name.is_empty());
+        assert!(!name.contains('\0'));
+        assert_ne!(ctx.uuid().unwrap(), [0; 16]);
+        assert_eq!(ctx.uuid().unwrap(), ctx.uuid().unwrap());
+    }
+
+    #[test]
+    fn test_launch_host_fn() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.new_stream().unwrap();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        let a = stream.alloc_zeros::<f32>(1 << 20).unwrap();
+        let mut b = stream.alloc_zeros::<f32>(1 << 20).unwrap();
+        stream.memcpy_dtod(&a, &mut b).unwrap();
+        for i in 0..3 {
+            let tx = tx.clone();
+            stream.launch_host_fn(move || tx.send(i).unwrap()).unwrap();
+        }
+        // the callbacks still run after the stream itself is dropped
+        drop(stream);
+        drop(tx);
+
+        assert_eq!(rx.iter().collect::<Vec<_>>(), [0, 1, 2]);
+    }
+
+    #[test]
+    #[cfg(not(any(
+        feature = "cuda-11040",
+        feature = "cuda-11050",
+        feature = "cuda-11060",
+        feature = "cuda-11070",
+        feature = "cuda-11080"
+    )))]
+    fn test_event_is_complete() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.new_stream().unwrap();
+
+        // nothing recorded yet
+        let event = ctx.new_event(None).unwrap();
+        assert!(event.is_complete().unwrap());
+
+        let mut flag = stream.alloc_zeros::<u32>(1).unwrap();
+        stream.synchronize().unwrap();
+        stream.wait_value_u32(&flag, 1, WaitFlags::Eq).unwrap();
+        event.record(&stream).unwrap();
+        assert!(!event.is_complete().unwrap());
+
+        let signal = ctx.new_stream().unwrap();
+        signal.write_value_u32(&mut flag, 1).unwrap();
+        event.synchronize().unwrap();
+        assert!(event.is_complete().unwrap());
+    }
+
+    #[test]
+    #[cfg(not(any(
+        feature = "cuda-11040",
+        feature = "cuda-11050",
+        feature = "cuda-11060",
+        feature = "cuda-11070",
+        feature = "cuda-11080"
+    )))]
+    fn test_stream_is_idle() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.new_stream().unwrap();
+        assert!(stream.is_idle().unwrap());
+
+        let mut flag = stream.alloc_zeros::<u32>(1).unwrap();
+        stream.synchronize().unwrap();
+        stream.wait_value_u32(&flag, 1, WaitFlags::Eq).unwrap();
+        assert!(!stream.is_idle().unwrap());
+
+        let signal = ctx.new_stream().unwrap();
+        signal.write_value_u32(&mut flag, 1).unwrap();
+        stream.synchronize().unwrap();
+        assert!(stream.is_idle().unwrap());
+    }
+
+    #[test]
+    #[cfg(not(any(
+        feature = "cuda-11040",
+        feature = "cuda-11050",
+        feature = "cuda-11060",
+        feature = "cuda-11070",
+        feature = "cuda-11080"
+    )))]
+    fn test_synchronize_timeout() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.new_stream().unwrap();
+        let timeout = std::time::Duration::from_millis(50);
+        assert!(stream.synchronize_timeout(timeout).unwrap());
+
+        let mut flag = stream.alloc_zeros::<u32>(1).unwrap();
+        stream.synchronize().unwrap();
+        stream.wait_value_u32(&flag, 1, WaitFlags::Eq).unwrap();
+        let start = std::time::Instant::now();
+        assert!(!stream.synchronize_timeout(timeout).unwrap());
+        assert!(start.elapsed() >= timeout);
+
+        let signal = ctx.new_stream().unwrap();
+        signal.write_value_u32(&mut flag, 1).unwrap();
+        assert!(stream
+            .synchronize_timeout(std::time::Duration::from_secs(10))
+            .unwrap());
+    }
+
+    #[test]
+    fn test_wait_with_flags() {
+        let ctx = CudaContext::new(0).unwrap();
+        let producer = ctx.new_stream().unwrap();
+        let consumer = ctx.new_stream().unwrap();
+
+        let a = producer.memcpy_stod(&[3u32; 1024]).unwrap();
+        let event = producer.record_event(None).unwrap();
+        consumer
+            .wait_with_flags(&event, EventWaitFlags::External)
+            .unwrap();
+        let mut b = consumer.alloc_zeros::<u32>(1024).unwrap();
+        consumer.memcpy_dtod(&a, &mut b).unwrap();
+        assert_eq!(consumer.memcpy_dtov(&b).unwrap(), [3; 1024]);
+    }
+
+    #[test]
+    #[cfg(not(any(
+        feature = "cuda-11040",
+        feature = "cuda-11050",
+        feature = "cuda-11060",
+        feature = "cuda-11070",
+        feature = "cuda-11080"
+    )))]
+    fn test_wait_write_value() {
+        let ctx = CudaContext::new(0).unwrap();
+        let producer = ctx.new_stream().unwrap();
+        let consumer = ctx.new_stream().unwrap();
+        let mut flag = producer.alloc_zeros::<u32>(1).unwrap();
+        let src = producer.memcpy_stod(&[1.0f32, 2.0]).unwrap();
+        producer.synchronize().unwrap();
+
+        let mut dst = consumer.alloc_zeros::<f32>(2).unwrap();
+        consumer.synchronize().unwrap();
+        consumer.wait_value_u32(&flag, 1, WaitFlags::Geq).unwrap();
+        consumer.memcpy_dtod(&src, &mut dst).unwrap();
+
+        producer.write_value_u32(&mut flag, 1).unwrap();
+        consumer.synchronize().unwrap();
+        assert_eq!(consumer.memcpy_dtov(&dst).unwrap(), [1.0, 2.0]);
+
+        let empty = producer.alloc_zeros::<u32>(0).unwrap();
+        assert_eq!(
+            producer.wait_value_u32(&empty, 0, WaitFlags::Eq),
+            Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE))
+        );
+    }
+
+    #[test]
+    fn test_mem_info() {
+        let ctx = CudaContext::new(0).unwrap();
+        let (free, total) = ctx.mem_info().unwrap();
+        assert!(free > 0);
+        assert!(free <= total);
+        assert!(ctx.free_mem().unwrap() <= total);
+    }
+
+    #[test]
+    fn test_out_of_memory_classification() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let (_, total) = ctx.mem_info().unwrap();
+        let err = unsafe { stream.alloc::<u8>(2 * total) }.unwrap_err();
+        assert!(err.is_out_of_memory());
+        assert!(!err.is_context_destroyed());
+    }
+
+    #[test]
+    fn test_error_name() {
+        let err = DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE);
+        assert_eq!(err.error_name(), "CUDA_ERROR_INVALID_VALUE");
+        assert!(!err.error_string().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_primary_ctx_refcount() {
+        let a = CudaContext::primary(0).unwrap();
+        let b = CudaContext::primary(0).unwrap();
+        assert_eq!(a.cu_ctx(), b.cu_ctx());
+        drop(a);
+        let (_, active) = unsafe { result::primary_ctx::get_state(b.cu_device()) }.unwrap();
+        assert!(active);
+        let stream = b.default_stream();
+        let buf = stream.memcpy_stod(&[1.0f32, 2.0, 3.0]).unwrap();
+        assert_eq!(stream.memcpy_dtov(&buf).unwrap(), [1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_take_recorded_error() {
+        let ctx = CudaContext::new(0).unwrap();
+        ctx.check_err().unwrap();
+        assert!(!ctx.has_recorded_error());
+        assert_eq!(ctx.take_recorded_error(), None);
+
+        ctx.record_err::<()>(Err(DriverError(
+            sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE,
+        )));
+        ctx.record_err::<()>(Ok(()));
+        ctx.record_err::<()>(Err(DriverError(
+            sys::cudaError_enum::CUDA_ERROR_OUT_OF_MEMORY,
+        )));
+        assert!(ctx.has_recorded_error());
+        assert_eq!(
+            ctx.take_recorded_error(),
+            Some(DriverError(sys::cudaError_enum::CUDA_ERROR_OUT_OF_MEMORY))
+        );
+        assert!(!ctx.has_recorded_error());
+        assert_eq!(ctx.take_recorded_error(), None);
+    }
+
+    #[test]
+    #[ignore = "must be executed by itself"]
+    fn test_reset() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let buf = stream.alloc_zeros::<f32>(1024).unwrap();
+        assert_eq!(
+            unsafe { ctx.clone().reset() }.err(),
+            Some(DriverError(
+                sys::cudaError_enum::CUDA_ERROR_CONTEXT_ALREADY_IN_USE
+            ))
+        );
+        drop(buf);
+        drop(stream);
+        let ctx = unsafe { ctx.reset() }.unwrap();
+        let stream = ctx.default_stream();
+        let buf = stream.memcpy_stod(&[1u32, 2, 3]).unwrap();
+        assert_eq!(stream.memcpy_dtov(&buf).unwrap(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_new_with_flags() {
+        let flags = ContextFlags::BLOCKING_SYNC | ContextFlags::MAP_HOST;
+        assert!(flags.contains(ContextFlags::MAP_HOST));
+        assert!(!flags.contains(ContextFlags::SPIN_SYNC));
+        let ctx = CudaContext::new_with_flags(0, flags).unwrap();
+        assert!(ctx.flags().unwrap().contains(flags));
+        let stream = ctx.default_stream();
+        let a = stream.alloc_zeros::<f32>(1024).unwrap();
+        stream.synchronize().unwrap();
+        drop(a);
+        let ctx = CudaContext::new_with_flags(0, ContextFlags::AUTO).unwrap();
+        assert!(!ctx.flags().unwrap().contains(ContextFlags::BLOCKING_SYNC));
+    }
+
+    #[test]
+    fn test_stream_priority() {
+        let ctx = CudaContext::new(0).unwrap();
+        let range = ctx.stream_priority_range().unwrap();
+        assert!(!range.is_empty());
+        let stream = ctx.new_stream_with_priority(range.start).unwrap();
+        assert_eq!(stream.priority().unwrap(), range.start);
+        assert!(ctx.new_stream_with_priority(range.end).is_err());
+    }
+
+    #[test]
+    fn test_transmutes() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let mut slice = stream.alloc_zeros::<u8>(100).unwrap();
+        assert!(unsafe { slice.transmute::<f32>(25) }.is_some());
+        assert!(unsafe { slice.transmute::<f32>(26) }.is_none());
+        assert!(unsafe { slice.transmute_mut::<f32>(25) }.is_some());
+        assert!(unsafe { slice.transmute_mut::<f32>(26) }.is_none());
+
+        {
+            let view = slice.slice(0..100);
+            assert!(unsafe { view.transmute::<f32>(25) }.is_some());
+            assert!(unsafe { view.transmute::<f32>(26) }.is_none());
+        }
+
+        {
+            let mut view_mut = slice.slice_mut(0..100);
+            assert!(unsafe { view_mut.transmute::<f32>(25) }.is_some());
+            assert!(unsafe { view_mut.transmute::<f32>(26) }.is_none());
+            assert!(unsafe { view_mut.transmute_mut::<f32>(25) }.is_some());
+            assert!(unsafe { view_mut.transmute_mut::<f32>(26) }.is_none());
+        }
+    }
+
+    #[test]
+    fn test_transmute_into() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let slice = stream.memcpy_stod(&[1.0f32, -2.0]).unwrap();
+        let bytes = unsafe { slice.transmute_into::<u8>() }.unwrap();
+        assert_eq!(bytes.len(), 8);
+        let bits = unsafe { bytes.transmute_into::<u32>() }.unwrap();
+        assert_eq!(
+            stream.memcpy_dtov(&bits).unwrap(),
+            [1.0f32.to_bits(), (-2.0f32).to_bits()]
+        );
+
+        let odd = stream.alloc_zeros::<u8>(6).unwrap();
+        assert_eq!(
+            unsafe { odd.transmute_into::<u32>() }.unwrap_err(),
+            DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE)
+        );
+    }
+
+    #[test]
+    fn test_threading() {
+        let ctx1 = CudaContext::new(0).unwrap();
+        let ctx2 = ctx1.clone();
+
+        let thread1 = std::thread::spawn(move || {
+            ctx1.bind_to_thread()?;
+            ctx1.default_stream().alloc_zeros::<f32>(10)
+        });
+        let thread2 = std::thread::spawn(move || {
+            ctx2.bind_to_thread()?;
+            ctx2.default_stream().alloc_zeros::<f32>(10)
+        });
+
+        let _: crate::driver::CudaSlice<f32> = thread1.join().unwrap().unwrap();
+        let _: crate::driver::CudaSlice<f32> = thread2.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_post_build_arc_count() {
+        let ctx = CudaContext::new(0).unwrap();
+        assert_eq!(Arc::strong_count(&ctx), 1);
+    }
+
+    #[test]
+    fn test_post_alloc_arc_counts() {
+        let ctx = CudaContext::new(0).unwrap();
+        assert_eq!(Arc::strong_count(&ctx), 1);
+        let stream = ctx.default_stream();
+        assert_eq!(Arc::strong_count(&ctx), 2);
+        let t = stream.alloc_zeros::<f32>(1).unwrap();
+        assert_eq!(Arc::strong_count(&ctx), 4);
+        assert_eq!(Arc::strong_count(&stream), 2);
+        drop(t);
+        assert_eq!(Arc::strong_count(&ctx), 2);
+        assert_eq!(Arc::strong_count(&stream), 1);
+        drop(stream);
+        assert_eq!(Arc::strong_count(&ctx), 1);
+    }
+
+    #[test]
+    #[ignore = "must be executed by itself"]
+    fn test_post_alloc_memory() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+
+        let (free1, total1) = result::mem_get_info().unwrap();
+
+        let t = stream.memcpy_stod(&[0.0f32; 5]).unwrap();
+        let (free2, total2) = result::mem_get_info().unwrap();
+        assert_eq!(total1, total2);
+        assert!(free2 < free1);
+
+        drop(t);
+        ctx.synchronize().unwrap();
+
+        let (free3, total3) = result::mem_get_info().unwrap();
+        assert_eq!(total2, total3);
+        assert!(free3 > free2);
+        assert_eq!(free3, free1);
+    }
+
+    #[test]
+    fn test_ctx_copy_to_views() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+
+        let smalls = [
+            stream.memcpy_stod(&[-1.0f32, -0.8]).unwrap(),
+            stream.memcpy_stod(&[-0.6, -0.4]).unwrap(),
+            stream.memcpy_stod(&[-0.2, 0.0]).unwrap(),
             stream.memcpy_stod(&[0.2, 0.4]).unwrap(),
             stream.memcpy_stod(&[0.6, 0.8]).unwrap(),
         ];
@@ -2073,6 +4297,100 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_views_do_not_free() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let mut slice = stream.memcpy_stod(&[1u32, 2, 3, 4]).unwrap();
+
+        assert!(slice.try_slice(2..5).is_none());
+        assert!(slice.try_slice_mut(5..).is_none());
+
+        {
+            let view = slice.slice(1..3);
+            assert_eq!(stream.memcpy_dtov(&view).unwrap(), [2, 3]);
+        }
+        {
+            let mut view = slice.slice_mut(2..);
+            stream.memset_zeros(&mut view).unwrap();
+        }
+        assert_eq!(stream.memcpy_dtov(&slice).unwrap(), [1, 2, 0, 0]);
+    }
+
+    #[test]
+    fn test_split_at_mut() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let mut slice = stream.memcpy_stod(&[1u32, 2, 3, 4, 5]).unwrap();
+
+        assert!(slice.try_split_at_mut(6).is_none());
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = slice.try_slice_mut(3..2);
+        assert!(reversed.is_none());
+
+        {
+            let (mut left, mut right) = slice.split_at_mut(2);
+            assert_eq!(left.len(), 2);
+            assert_eq!(right.len(), 3);
+            stream.memset_zeros(&mut left).unwrap();
+            let (_, mut last) = right.split_at_mut(2);
+            stream.memcpy_htod(&[9u32], &mut last).unwrap();
+        }
+        assert_eq!(stream.memcpy_dtov(&slice).unwrap(), [0, 0, 3, 4, 9]);
+    }
+
+    #[test]
+    fn test_memcpy_dtod_range() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let src = stream.memcpy_stod(&[1u32, 2, 3, 4]).unwrap();
+        let mut dst = stream.alloc_zeros::<u32>(5).unwrap();
+
+        stream.memcpy_dtod_range(&src, 1..3, &mut dst, 3).unwrap();
+        assert_eq!(stream.memcpy_dtov(&dst).unwrap(), [0, 0, 0, 2, 3]);
+
+        let invalid = DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE);
+        assert_eq!(
+            stream.memcpy_dtod_range(&src, 2..5, &mut dst, 0),
+            Err(invalid)
+        );
+        assert_eq!(
+            stream.memcpy_dtod_range(&src, 0..3, &mut dst, 3),
+            Err(invalid)
+        );
+
+        // alias `src` to create an overlapping copy
+        let mut alias = unsafe { stream.upgrade_device_ptr::<u32>(src.cu_device_ptr, src.len()) };
+        assert_eq!(
+            stream.memcpy_dtod_range(&src, 0..2, &mut alias, 1),
+            Err(invalid)
+        );
+        stream.memcpy_dtod_range(&src, 0..2, &mut alias, 2).unwrap();
+        alias.leak();
+        assert_eq!(stream.memcpy_dtov(&src).unwrap(), [1, 2, 1, 2]);
+    }
+
+    #[test]
+    fn test_concat() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let a = stream.memcpy_stod(&[1u32, 2]).unwrap();
+        let b = stream.alloc_zeros::<u32>(0).unwrap();
+        let c = stream.memcpy_stod(&[3u32, 4, 5]).unwrap();
+
+        let abc = stream.concat(&[&a, &b, &c]).unwrap();
+        assert_eq!(stream.memcpy_dtov(&abc).unwrap(), [1, 2, 3, 4, 5]);
+
+        let views = [a.slice(1..), c.slice(..1)];
+        let parts = stream.concat(&[&views[0], &views[1]]).unwrap();
+        assert_eq!(stream.memcpy_dtov(&parts).unwrap(), [2, 3]);
+
+        assert_eq!(
+            stream.concat::<u32, CudaSlice<u32>>(&[]).unwrap_err(),
+            DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE)
+        );
+    }
+
     #[test]
     fn test_leak_and_upgrade() {
         let ctx = CudaContext::new(0).unwrap();
@@ -2110,6 +4428,425 @@ mod tests {
         let _out = ctx0.default_stream().memcpy_dtov(&slice).unwrap();
     }
 
+    #[test]
+    fn test_memcpy_dtoh_element() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.new_stream().unwrap();
+        let a = stream.memcpy_stod(&[1.0f64, 2.0, 3.0, 4.0]).unwrap();
+        assert_eq!(stream.memcpy_dtoh_element(&a, 0).unwrap(), 1.0);
+        assert_eq!(stream.memcpy_dtoh_element(&a, 3).unwrap(), 4.0);
+        assert_eq!(stream.memcpy_dtoh_element(&a.slice(1..3), 1).unwrap(), 3.0);
+        assert_eq!(
+            stream.memcpy_dtoh_element(&a, 4).err(),
+            Some(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE))
+        );
+        assert!(stream.memcpy_dtoh_element(&a.slice(1..3), 2).is_err());
+    }
+
+    #[test]
+    fn test_wrap_borrowed_stream() {
+        let ctx = CudaContext::new(0).unwrap();
+        ctx.bind_to_thread().unwrap();
+        let raw = result::stream::create(result::stream::StreamKind::NonBlocking).unwrap();
+
+        let stream = unsafe { ctx.wrap_borrowed_stream(raw) }.unwrap();
+        assert_eq!(stream.cu_stream(), raw);
+        assert!(ctx.is_in_multi_stream_mode());
+        let a = stream.memcpy_stod(&[1u32, 2, 3]).unwrap();
+        assert_eq!(stream.memcpy_dtov(&a).unwrap(), [1, 2, 3]);
+        drop(a);
+        drop(stream);
+
+        // still usable after the wrapper is gone
+        unsafe { result::stream::synchronize(raw) }.unwrap();
+        unsafe { result::stream::destroy(raw) }.unwrap();
+
+        assert!(unsafe { ctx.wrap_borrowed_stream(std::ptr::null_mut()) }.is_err());
+    }
+
+    #[test]
+    fn test_context_synchronize() {
+        let ctx = CudaContext::new(0).unwrap();
+        let streams: Vec<_> = (0..4).map(|_| ctx.new_stream().unwrap()).collect();
+        let slices: Vec<_> = streams
+            .iter()
+            .map(|s| s.alloc_zeros::<u8>(1 << 26).unwrap())
+            .collect();
+        ctx.synchronize().unwrap();
+        for stream in streams.iter() {
+            assert!(stream.is_idle().unwrap());
+        }
+        drop(slices);
+    }
+
+    #[test]
+    fn test_per_thread_stream() {
+        let ctx = CudaContext::new(0).unwrap();
+        let handles: Vec<_> = (0..4u32)
+            .map(|i| {
+                let ctx = ctx.clone();
+                std::thread::spawn(move || {
+                    let stream = ctx.per_thread_stream().unwrap();
+                    assert_eq!(stream.cu_stream(), result::stream::per_thread());
+                    let a = stream.memcpy_stod(&[i; 1024]).unwrap();
+                    let mut b = stream.alloc_zeros::<u32>(1024).unwrap();
+                    stream.memcpy_dtod(&a, &mut b).unwrap();
+                    assert_eq!(stream.memcpy_dtov(&b).unwrap(), [i; 1024]);
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(ctx.num_streams.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_device_slice_defaults() {
+        fn sizes<T, S: DeviceSlice<T>>(s: &S) -> (usize, usize, bool) {
+            (s.len(), s.num_bytes(), s.is_empty())
+        }
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let mut a = stream.alloc_zeros::<u32>(10).unwrap();
+        assert_eq!(sizes(&a), (10, 40, false));
+        assert_eq!(sizes(&a.slice(2..5)), (3, 12, false));
+        assert_eq!(sizes(&a.slice_mut(5..5)), (0, 0, true));
+        let b = stream.alloc_zeros::<u8>(0).unwrap();
+        assert_eq!(sizes(&b), (0, 0, true));
+    }
+
+    #[test]
+    fn test_load_ptx_cached() {
+        use crate::driver::{LaunchConfig, PushKernelArg};
+        const SRC: &str =
+            "extern \"C\" __global__ void kernel(float *x) { x[threadIdx.x] = 2.0f; }";
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let cache_dir =
+            std::env::temp_dir().join(std::format!("cudarc-module-cache-{}", std::process::id()));
+        std::fs::remove_dir_all(&cache_dir).ok();
+
+        for _ in 0..2 {
+            let module = ctx.load_ptx_cached(SRC, &cache_dir).unwrap();
+            let f = module.load_function("kernel").unwrap();
+            let mut x = stream.alloc_zeros::<f32>(4).unwrap();
+            let mut builder = stream.launch_builder(&f);
+            builder.arg(&mut x);
+            unsafe { builder.launch(LaunchConfig::for_num_elems(4)) }.unwrap();
+            assert_eq!(stream.memcpy_dtov(&x).unwrap(), [2.0; 4]);
+            assert_eq!(std::fs::read_dir(&cache_dir).unwrap().count(), 1);
+        }
+        assert!(matches!(
+            ctx.load_ptx_cached("not cuda", &cache_dir),
+            Err(LoadCachedError::Compile(_))
+        ));
+        std::fs::remove_dir_all(&cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_stream_bind_to_thread() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.new_stream().unwrap();
+        unsafe { result::ctx::set_current(std::ptr::null_mut()) }.unwrap();
+        stream.bind_to_thread().unwrap();
+        assert_eq!(result::ctx::get_current().unwrap(), Some(ctx.cu_ctx));
+        let a = stream.memcpy_stod(&[1u32, 2, 3]).unwrap();
+        stream.synchronize().unwrap();
+        assert_eq!(stream.memcpy_dtov(&a).unwrap(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_stream_across_threads() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.new_stream().unwrap();
+        let a = stream.memcpy_stod(&[1u32, 2, 3]).unwrap();
+        // a new thread has no current context, so every call must bind it
+        let b = std::thread::spawn({
+            let stream = stream.clone();
+            move || {
+                assert!(result::ctx::get_current().unwrap().is_none());
+                let mut b = stream.alloc_zeros::<u32>(3).unwrap();
+                stream.memcpy_dtod(&a, &mut b).unwrap();
+                b
+            }
+        })
+        .join()
+        .unwrap();
+        assert_eq!(stream.memcpy_dtov(&b).unwrap(), [1, 2, 3]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn test_per_thread_stream_on_other_thread_panics() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.per_thread_stream().unwrap();
+        stream.synchronize().unwrap();
+        let result = std::thread::spawn(move || stream.synchronize()).join();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_free_batch() {
+        let ctx = CudaContext::new(0).unwrap();
+        let streams = [ctx.new_stream().unwrap(), ctx.new_stream().unwrap()];
+        let slices: Vec<CudaSlice<f32>> = (0..2000)
+            .map(|i| streams[i % 2].alloc_zeros(i + 1).unwrap())
+            .collect();
+        ctx.free_batch(slices).unwrap();
+        for stream in streams.iter() {
+            assert!(stream.is_idle().unwrap());
+            assert_eq!(Arc::strong_count(stream), 1);
+        }
+        assert!(!ctx.has_recorded_error());
+        ctx.free_batch(Vec::<CudaSlice<u8>>::new()).unwrap();
+    }
+
+    #[test]
+    fn test_alloc_guards() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let unguarded = stream.alloc_zeros::<u32>(10).unwrap();
+
+        ctx.set_alloc_guard_bytes(100);
+        assert_eq!(ctx.alloc_guard_bytes(), 256);
+        let a = stream.alloc_zeros::<u32>(10).unwrap();
+        let b = stream.memcpy_stod(&[1u32; 10]).unwrap();
+        assert_eq!(a.cu_device_ptr % 256, 0);
+        assert_eq!(stream.memcpy_dtov(&b).unwrap(), [1; 10]);
+        assert!(ctx.check_guards(&a).unwrap());
+        assert!(ctx.check_guards(&b).unwrap());
+        assert!(ctx.check_guards(&unguarded).is_err());
+
+        // simulate a kernel writing one element past the end
+        unsafe { result::memset_d8_sync(b.cu_device_ptr + 40, 0, 4) }.unwrap();
+        assert!(!ctx.check_guards(&b).unwrap());
+        assert!(ctx.check_guards(&a).unwrap());
+
+        if CudaContext::device_count().unwrap() >= 2 {
+            let other = CudaContext::new(1).unwrap();
+            assert_eq!(
+                other.check_guards(&a),
+                Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_CONTEXT))
+            );
+        }
+
+        ctx.set_alloc_guard_bytes(0);
+        drop((a, b, unguarded));
+        stream.synchronize().unwrap();
+        assert!(!ctx.has_recorded_error());
+    }
+
+    #[test]
+    fn test_alloc_aligned() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        for align in [1, 16, 256, 4096, 1 << 16] {
+            let mut a = unsafe { ctx.alloc_aligned::<f32>(100, align) }.unwrap();
+            assert_eq!(a.cu_device_ptr % align as u64, 0);
+            assert_eq!(a.len(), 100);
+            stream.memcpy_htod(&[2.0f32; 100], &mut a).unwrap();
+            assert_eq!(stream.memcpy_dtov(&a).unwrap(), [2.0; 100]);
+        }
+        assert!(unsafe { ctx.alloc_aligned::<f32>(100, 0) }.is_err());
+        assert!(unsafe { ctx.alloc_aligned::<f32>(100, 48) }.is_err());
+        stream.synchronize().unwrap();
+        assert!(!ctx.has_recorded_error());
+    }
+
+    #[test]
+    fn test_fork_many_join_all() {
+        let ctx = CudaContext::new(0).unwrap();
+        let main = ctx.new_stream().unwrap();
+
+        let n = 1 << 20;
+        let num_bytes = n * std::mem::size_of::<u32>();
+        let a = main.alloc_zeros::<u32>(n).unwrap();
+        let outs: Vec<CudaSlice<u32>> = (0..3).map(|_| main.alloc_zeros(n).unwrap()).collect();
+        main.synchronize().unwrap();
+
+        // raw pointers bypass event tracking, so only fork/join order the work below
+        let a_ptr = a.device_ptr(&main).0;
+        let out_ptrs: Vec<_> = outs.iter().map(|o| o.device_ptr(&main).0).collect();
+
+        unsafe { result::memset_d32_async(a_ptr, 7, n, main.cu_stream()) }.unwrap();
+        let forks = main.fork_many(3).unwrap();
+        assert_eq!(forks.len(), 3);
+        for (fork, &out) in forks.iter().zip(out_ptrs.iter()) {
+            assert_ne!(fork.cu_stream(), main.cu_stream());
+            unsafe { result::memcpy_dtod_async(out, a_ptr, num_bytes, fork.cu_stream()) }.unwrap();
+        }
+        main.join_all(&forks).unwrap();
+        unsafe { result::memset_d32_async(a_ptr, 0, n, main.cu_stream()) }.unwrap();
+        main.synchronize().unwrap();
+
+        for out in outs.iter() {
+            assert_eq!(main.memcpy_dtov(out).unwrap(), vec![7; n]);
+        }
+        assert_eq!(main.memcpy_dtov(&a).unwrap(), vec![0; n]);
+    }
+
+    #[test]
+    fn test_raw_handles() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.new_stream().unwrap();
+        let mut a = stream.alloc_zeros::<u32>(256).unwrap();
+        stream.synchronize().unwrap();
+
+        // what external code would do with the raw handles
+        unsafe {
+            result::ctx::set_current(ctx.cu_ctx()).unwrap();
+            let (ptr, _record) = a.device_ptr_mut(&stream);
+            result::memset_d32_async(ptr, 5, 256, stream.cu_stream()).unwrap();
+        }
+        assert_eq!(stream.memcpy_dtov(&a).unwrap(), [5; 256]);
+    }
+
+    #[test]
+    fn test_alloc_from_iter() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.new_stream().unwrap();
+
+        // spans several staging chunks, with a partial last chunk
+        let n = 3 * (1 << 18) + 5;
+        let a = stream.alloc_from_iter((0..n).map(|i| i as u32)).unwrap();
+        assert_eq!(a.len(), n);
+        let host = stream.memcpy_dtov(&a).unwrap();
+        assert!(host.iter().enumerate().all(|(i, &x)| x == i as u32));
+
+        let b = stream.alloc_from_iter([1.0f32, 2.0, 3.0]).unwrap();
+        assert_eq!(stream.memcpy_dtov(&b).unwrap(), [1.0, 2.0, 3.0]);
+
+        let empty = stream.alloc_from_iter(std::iter::empty::<f64>()).unwrap();
+        assert!(empty.is_empty());
+
+        struct Liar(usize);
+        impl Iterator for Liar {
+            type Item = u8;
+            fn next(&mut self) -> Option<u8> {
+                self.0 = self.0.checked_sub(1)?;
+                Some(0)
+            }
+        }
+        impl ExactSizeIterator for Liar {
+            fn len(&self) -> usize {
+                self.0 + 1
+            }
+        }
+        assert_eq!(
+            stream.alloc_from_iter(Liar(4)).err(),
+            Some(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE))
+        );
+    }
+
+    #[test]
+    fn test_try_clone_is_snapshot() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.new_stream().unwrap();
+        let mut a = stream.memcpy_stod(&[1u32; 1024]).unwrap();
+        let snapshot = a.try_clone().unwrap();
+        assert!(Arc::ptr_eq(snapshot.stream(), a.stream()));
+        assert_eq!(snapshot.len(), a.len());
+        stream.memset_zeros(&mut a).unwrap();
+        assert_eq!(stream.memcpy_dtov(&snapshot).unwrap(), [1; 1024]);
+        assert_eq!(stream.memcpy_dtov(&a).unwrap(), [0; 1024]);
+    }
+
+    #[test]
+    fn test_memcpy_dtov_range() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.new_stream().unwrap();
+        let host: Vec<u32> = (0..1024).collect();
+        let a = stream.memcpy_stod(&host).unwrap();
+        assert_eq!(
+            stream.memcpy_dtov_range(&a, 100..104).unwrap(),
+            [100, 101, 102, 103]
+        );
+        assert_eq!(
+            stream.memcpy_dtov_range(&a, 1020..).unwrap(),
+            [1020, 1021, 1022, 1023]
+        );
+        assert_eq!(stream.memcpy_dtov_range(&a, ..).unwrap(), host);
+        assert!(stream.memcpy_dtov_range(&a, 5..5).unwrap().is_empty());
+        assert_eq!(
+            stream.memcpy_dtov_range(&a, 1000..1025).err(),
+            Some(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE))
+        );
+        assert!(stream.memcpy_dtov_range(&a, 2000..).is_err());
+    }
+
+    #[test]
+    fn test_memcpy_dtoh_pinned() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.new_stream().unwrap();
+        let src = stream.memcpy_stod(&[1u32, 2, 3, 4]).unwrap();
+        let mut dst = unsafe { ctx.alloc_pinned::<u32>(4) }.unwrap();
+        stream.memcpy_dtoh_pinned(&src, &mut dst).unwrap();
+        assert_eq!(dst.as_slice().unwrap(), [1, 2, 3, 4]);
+
+        let mut short = unsafe { ctx.alloc_pinned::<u32>(3) }.unwrap();
+        assert_eq!(
+            stream.memcpy_dtoh_pinned(&src, &mut short).err(),
+            Some(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE))
+        );
+        stream
+            .memcpy_dtoh_pinned(&src.slice(1..), &mut short)
+            .unwrap();
+        assert_eq!(short.as_slice().unwrap(), [2, 3, 4]);
+    }
+
+    #[test]
+    fn test_memset() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+
+        let mut a = stream.alloc_zeros::<u8>(7).unwrap();
+        unsafe { stream.memset(&mut a, 0xab) }.unwrap();
+        assert_eq!(stream.memcpy_dtov(&a).unwrap(), [0xab; 7]);
+
+        let mut b = stream.alloc_zeros::<i16>(5).unwrap();
+        unsafe { stream.memset(&mut b, -2) }.unwrap();
+        assert_eq!(stream.memcpy_dtov(&b).unwrap(), [-2; 5]);
+
+        let mut c = stream.alloc_zeros::<f32>(100).unwrap();
+        unsafe { stream.memset(&mut c.slice_mut(10..20), 1.5) }.unwrap();
+        let c_host = stream.memcpy_dtov(&c).unwrap();
+        for (i, &x) in c_host.iter().enumerate() {
+            assert_eq!(x, if (10..20).contains(&i) { 1.5 } else { 0.0 });
+        }
+
+        let mut d = stream.alloc_zeros::<f64>(33).unwrap();
+        unsafe { stream.memset(&mut d, -0.1) }.unwrap();
+        assert_eq!(stream.memcpy_dtov(&d).unwrap(), [-0.1; 33]);
+
+        #[derive(Clone, Copy)]
+        #[repr(C)]
+        struct Rgb([u8; 3]);
+        unsafe impl DeviceRepr for Rgb {}
+        unsafe impl ValidAsZeroBits for Rgb {}
+        let mut e = stream.alloc_zeros::<Rgb>(4).unwrap();
+        assert_eq!(
+            unsafe { stream.memset(&mut e, Rgb([1, 2, 3])) }.err(),
+            Some(DriverError(sys::cudaError_enum::CUDA_ERROR_NOT_SUPPORTED))
+        );
+    }
+
+    #[test]
+    fn test_memcpy_stod_many() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let a = [1u32, 2, 3];
+        let b = vec![4u32; 100];
+        let slices = stream.memcpy_stod_many(&[&a[..], &b[..]]).unwrap();
+        assert_eq!(slices.len(), 2);
+        assert_eq!(stream.memcpy_dtov(&slices[0]).unwrap(), a);
+        assert_eq!(stream.memcpy_dtov(&slices[1]).unwrap(), b);
+        assert!(stream
+            .memcpy_stod_many::<u32, [u32]>(&[])
+            .unwrap()
+            .is_empty());
+    }
+
     #[test]
     fn test_htod_copy_pinned() {
         let truth = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
@@ -2123,6 +4860,41 @@ mod tests {
         assert_eq!(&host, &truth);
     }
 
+    #[test]
+    fn test_htod_copy_pinned_portable_async() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.new_stream().unwrap();
+        let mut pinned =
+            unsafe { ctx.alloc_pinned_with_flags::<u32>(1000, sys::CU_MEMHOSTALLOC_PORTABLE) }
+                .unwrap();
+        for (i, x) in pinned.iter_mut().enumerate() {
+            *x = i as u32;
+        }
+        let mut dst = stream.alloc_zeros::<u32>(1000).unwrap();
+        stream.memcpy_htod(&pinned, &mut dst).unwrap();
+        let host = stream.memcpy_dtov(&dst).unwrap();
+        assert_eq!(&host[..], &pinned[..]);
+    }
+
+    #[test]
+    fn test_htod_copy_pinned_with_event() {
+        let ctx = CudaContext::new(0).unwrap();
+        let copy_stream = ctx.new_stream().unwrap();
+        let work_stream = ctx.new_stream().unwrap();
+        let mut pinned = unsafe { ctx.alloc_pinned::<u32>(1000) }.unwrap();
+        for (i, x) in pinned.iter_mut().enumerate() {
+            *x = i as u32;
+        }
+        let mut dst = copy_stream.alloc_zeros::<u32>(1000).unwrap();
+        let event = copy_stream
+            .memcpy_htod_with_event(&pinned, &mut dst)
+            .unwrap();
+        assert!(event.elapsed_ms(&event).is_err());
+        work_stream.wait(&event).unwrap();
+        let host = work_stream.memcpy_dtov(&dst).unwrap();
+        assert_eq!(&host[..], &pinned[..]);
+    }
+
     #[test]
     fn test_pinned_copy_is_faster() {
         let ctx = CudaContext::new(0).unwrap();
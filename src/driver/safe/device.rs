@@ -0,0 +1,89 @@
+use crate::driver::{result, sys, DriverError};
+
+/// The number of CUDA devices available to this process (respecting `CUDA_VISIBLE_DEVICES`).
+///
+/// Initializes the driver if needed (`cuInit` is safe to call repeatedly and from multiple threads),
+/// but does not create a context, so it is cheap to call before picking a device.
+///
+/// Returns `Ok(0)` rather than an error if the driver is installed but there are no devices.
+///
+/// ```no_run
+/// # fn main() -> Result<(), cudarc::driver::DriverError> {
+/// for ordinal in 0..cudarc::driver::device_count()? {
+///     println!("{ordinal}: {}", cudarc::driver::device_name(ordinal)?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn device_count() -> Result<usize, DriverError> {
+    match result::init() {
+        Err(DriverError(sys::cudaError_enum::CUDA_ERROR_NO_DEVICE)) => return Ok(0),
+        res => res?,
+    }
+    Ok(result::device::get_count()? as usize)
+}
+
+/// The name of the device at `ordinal`, without creating a context on it. See [device_count()].
+///
+/// Returns [sys::cudaError_enum::CUDA_ERROR_INVALID_DEVICE] if `ordinal` is out of range.
+pub fn device_name(ordinal: usize) -> Result<String, DriverError> {
+    result::init()?;
+    let ordinal = i32::try_from(ordinal)
+        .map_err(|_| DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_DEVICE))?;
+    let cu_device = result::device::get(ordinal)?;
+    result::device::get_name(cu_device)
+}
+
+/// The latest CUDA version supported by the installed driver, e.g. `12020` for 12.2.
+/// Use [decode_version()] to split it into `(major, minor)`.
+///
+/// This can be used to check for features that need a minimum driver (e.g. stream ordered
+/// allocation needs 11.2) before using them, and to give clearer "upgrade your driver" errors.
+/// The version of the CUDA runtime is in [crate::runtime::result::version] (with the `runtime` feature).
+///
+/// ```no_run
+/// # fn main() -> Result<(), cudarc::driver::DriverError> {
+/// let (major, minor) = cudarc::driver::decode_version(cudarc::driver::driver_version()?);
+/// if (major, minor) < (12, 0) {
+///     eprintln!("Found a driver for CUDA {major}.{minor}, please upgrade to at least 12.0");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn driver_version() -> Result<i32, DriverError> {
+    result::driver_version()
+}
+
+/// Splits a CUDA version like `12020` (as returned by [driver_version()]) into `(major, minor)`, e.g. `(12, 2)`.
+pub fn decode_version(version: i32) -> (i32, i32) {
+    (version / 1000, (version % 1000) / 10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver::CudaContext;
+
+    #[test]
+    fn test_device_count_and_name() {
+        let count = device_count().unwrap();
+        assert!(count > 0);
+        assert_eq!(count, CudaContext::device_count().unwrap() as usize);
+        let ctx = CudaContext::new(0).unwrap();
+        assert_eq!(device_name(0).unwrap(), ctx.name().unwrap());
+        assert_eq!(
+            device_name(count).unwrap_err(),
+            DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_DEVICE)
+        );
+        assert!(device_name(usize::MAX).is_err());
+    }
+
+    #[test]
+    fn test_driver_version() {
+        assert_eq!(decode_version(12020), (12, 2));
+        assert_eq!(decode_version(11080), (11, 8));
+        assert_eq!(decode_version(12090), (12, 9));
+        let (major, _minor) = decode_version(driver_version().unwrap());
+        assert!(major >= 11);
+    }
+}
@@ -1,21 +1,38 @@
 use std::fs::File;
 use std::mem::ManuallyDrop;
 use std::ops::Range;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::vec::Vec;
 
-use super::{CudaContext, CudaEvent, CudaStream, DevicePtr, DeviceSlice, SyncOnDrop};
+use super::{CudaContext, CudaEvent, CudaStream, DevicePtr, DevicePtrMut, DeviceSlice, SyncOnDrop};
 use crate::driver::{result, sys, DriverError};
 
 /// An abstraction for imported external memory.
 ///
 /// This struct can be created via [`CudaContext::import_external_memory()`].
-/// The imported external memory will be destroyed when this struct is dropped.
+/// The imported external memory will be destroyed when this struct is dropped,
+/// which only happens after every [MappedBuffer] created from it is dropped.
 #[derive(Debug)]
 pub struct ExternalMemory {
     external_memory: sys::CUexternalMemory,
     size: u64,
     ctx: Arc<CudaContext>,
-    _file: ManuallyDrop<File>,
+    mapped: Mutex<Vec<Range<usize>>>,
+    _handle: ExternalMemoryHandle,
+}
+
+unsafe impl Send for ExternalMemory {}
+unsafe impl Sync for ExternalMemory {}
+
+/// Where the OS handle of an [ExternalMemory] came from, which determines
+/// whether we are responsible for releasing it.
+#[derive(Debug)]
+#[allow(unused)]
+enum ExternalMemoryHandle {
+    /// Imported via [`CudaContext::import_external_memory()`].
+    Owned(ManuallyDrop<File>),
+    /// Imported from a raw fd/handle that the caller still manages.
+    Borrowed,
 }
 
 impl Drop for ExternalMemory {
@@ -38,11 +55,12 @@ impl Drop for ExternalMemory {
         // > Ownership of this handle is not transferred to CUDA after the import operation,
         // > so the application must release the handle using the appropriate system call.
         //
-        // Therefore, we manually drop the file when we are on Windows.
+        // Therefore, we manually drop the file when we are on Windows. Borrowed handles are
+        // still owned by the caller, so we never release those.
         #[cfg(windows)]
-        unsafe {
-            ManuallyDrop::<File>::drop(&mut self._file)
-        };
+        if let ExternalMemoryHandle::Owned(file) = &mut self._handle {
+            unsafe { ManuallyDrop::<File>::drop(file) };
+        }
     }
 }
 
@@ -73,30 +91,166 @@ impl CudaContext {
             external_memory,
             size,
             ctx: self.clone(),
-            _file: ManuallyDrop::new(file),
+            mapped: Mutex::new(Vec::new()),
+            _handle: ExternalMemoryHandle::Owned(ManuallyDrop::new(file)),
+        })
+    }
+
+    /// Import external memory from a raw file descriptor without taking ownership of it.
+    ///
+    /// Unlike [`CudaContext::import_external_memory()`], the returned [ExternalMemory] never
+    /// closes `fd`.
+    ///
+    /// **Note**: per the CUDA docs, ownership of the file descriptor is still transferred to
+    /// the CUDA driver when the import succeeds. The caller must not use, close, or import
+    /// `fd` again afterwards.
+    ///
+    /// # Safety
+    /// 1. `fd` must be a valid file descriptor for an exported memory object.
+    /// 2. `size` must be the size of the external memory in bytes.
+    #[cfg(unix)]
+    pub unsafe fn import_external_memory_from_fd(
+        self: &Arc<Self>,
+        fd: std::os::fd::RawFd,
+        size: u64,
+    ) -> Result<ExternalMemory, DriverError> {
+        self.bind_to_thread()?;
+        let external_memory =
+            unsafe { result::external_memory::import_external_memory_opaque_fd(fd, size) }?;
+        Ok(ExternalMemory {
+            external_memory,
+            size,
+            ctx: self.clone(),
+            mapped: Mutex::new(Vec::new()),
+            _handle: ExternalMemoryHandle::Borrowed,
+        })
+    }
+
+    /// Import external memory from a raw win32 handle without taking ownership of it.
+    ///
+    /// Unlike [`CudaContext::import_external_memory()`], the returned [ExternalMemory] never
+    /// closes `handle`, so the caller remains responsible for releasing it.
+    ///
+    /// # Safety
+    /// 1. `handle` must be a valid handle for an exported memory object, and must outlive
+    ///    the returned [ExternalMemory].
+    /// 2. `size` must be the size of the external memory in bytes.
+    #[cfg(windows)]
+    pub unsafe fn import_external_memory_from_handle(
+        self: &Arc<Self>,
+        handle: std::os::windows::io::RawHandle,
+        size: u64,
+    ) -> Result<ExternalMemory, DriverError> {
+        self.bind_to_thread()?;
+        let external_memory =
+            unsafe { result::external_memory::import_external_memory_opaque_win32(handle, size) }?;
+        Ok(ExternalMemory {
+            external_memory,
+            size,
+            ctx: self.clone(),
+            mapped: Mutex::new(Vec::new()),
+            _handle: ExternalMemoryHandle::Borrowed,
         })
     }
 }
 
 impl ExternalMemory {
+    /// The size of the external memory in bytes.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
     /// Map the whole external memory to get mapped buffer.
-    pub fn map_all(self) -> Result<MappedBuffer, DriverError> {
+    pub fn map_all(self: &Arc<Self>) -> Result<MappedBuffer, DriverError> {
         let size = self.size as usize;
         self.map_range(0..size)
     }
 
     /// Map a range of the external memory to a mapped buffer.
     ///
-    /// Only one mapped buffer is allowed at a time.
-    /// This is more restrictive than it necessarily needs to be,
-    /// but it makes enforcing safety easier.
+    /// This can be called multiple times to map different sub-ranges of the same
+    /// external memory. Each [MappedBuffer] holds a reference to `self`, so the
+    /// external memory outlives all of its mappings.
+    ///
+    /// If `range` overlaps with a mapping that is still alive, this returns
+    /// [sys::cudaError_enum::CUDA_ERROR_ALREADY_MAPPED].
     ///
     /// # Panics
     /// This function will panic if the range is invalid,
     /// such as when the start or end is larger than the size.
-    pub fn map_range(self, range: Range<usize>) -> Result<MappedBuffer, DriverError> {
+    pub fn map_range(self: &Arc<Self>, range: Range<usize>) -> Result<MappedBuffer, DriverError> {
+        let device_ptr = self.map(&range)?;
+        let event = self.ctx.new_event(None)?;
+        let stream = self.ctx.default_stream();
+        Ok(MappedBuffer {
+            device_ptr,
+            range,
+            external_memory: self.clone(),
+            event,
+            stream,
+        })
+    }
+
+    /// Map the whole external memory to get a writable mapped buffer.
+    pub fn map_all_mut(self: &Arc<Self>) -> Result<MappedBufferMut, DriverError> {
+        let size = self.size as usize;
+        self.map_range_mut(0..size)
+    }
+
+    /// Same as [ExternalMemory::map_all_mut()], but also schedules a memset of the
+    /// whole region to 0 on `stream`.
+    ///
+    /// Useful when the other API has not written to the memory yet.
+    pub fn map_all_mut_zeroed(
+        self: &Arc<Self>,
+        stream: &CudaStream,
+    ) -> Result<MappedBufferMut, DriverError> {
+        let mut buf = self.map_all_mut()?;
+        buf.memset_bytes(0, stream)?;
+        Ok(buf)
+    }
+
+    /// Map a range of the external memory to a writable mapped buffer.
+    ///
+    /// Unlike [ExternalMemory::map_range()], the returned [MappedBufferMut] implements
+    /// [DevicePtrMut], so kernels & memcpys can write into it. Reads & writes are
+    /// tracked with events the same way [super::CudaSlice] tracks them.
+    ///
+    /// If `range` overlaps with a mapping that is still alive, this returns
+    /// [sys::cudaError_enum::CUDA_ERROR_ALREADY_MAPPED].
+    ///
+    /// # Panics
+    /// This function will panic if the range is invalid,
+    /// such as when the start or end is larger than the size.
+    pub fn map_range_mut(
+        self: &Arc<Self>,
+        range: Range<usize>,
+    ) -> Result<MappedBufferMut, DriverError> {
+        let device_ptr = self.map(&range)?;
+        let read = self.ctx.new_event(None)?;
+        let write = self.ctx.new_event(None)?;
+        let stream = self.ctx.default_stream();
+        Ok(MappedBufferMut {
+            device_ptr,
+            range,
+            external_memory: self.clone(),
+            read,
+            write,
+            stream,
+        })
+    }
+
+    fn map(&self, range: &Range<usize>) -> Result<sys::CUdeviceptr, DriverError> {
         assert!(range.start as u64 <= self.size);
         assert!(range.end as u64 <= self.size);
+        let mut mapped = self.mapped.lock().unwrap();
+        if mapped
+            .iter()
+            .any(|other| range.start < other.end && other.start < range.end)
+        {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_ALREADY_MAPPED));
+        }
+        self.ctx.bind_to_thread()?;
         let device_ptr = unsafe {
             result::external_memory::get_mapped_buffer(
                 self.external_memory,
@@ -104,15 +258,16 @@ impl ExternalMemory {
                 range.len() as u64,
             )
         }?;
-        let event = self.ctx.new_event(None)?;
-        let stream = self.ctx.default_stream();
-        Ok(MappedBuffer {
-            device_ptr,
-            len: range.len(),
-            external_memory: self,
-            event,
-            stream,
-        })
+        mapped.push(range.clone());
+        Ok(device_ptr)
+    }
+
+    fn unmap(&self, device_ptr: sys::CUdeviceptr, range: &Range<usize>) {
+        self.ctx
+            .record_err(unsafe { result::memory_free(device_ptr) });
+        if let Ok(mut mapped) = self.mapped.lock() {
+            mapped.retain(|other| other != range);
+        }
     }
 }
 
@@ -123,24 +278,58 @@ impl ExternalMemory {
 #[derive(Debug)]
 pub struct MappedBuffer {
     device_ptr: sys::CUdeviceptr,
-    len: usize,
-    external_memory: ExternalMemory,
+    range: Range<usize>,
+    external_memory: Arc<ExternalMemory>,
     event: CudaEvent,
     stream: Arc<CudaStream>,
 }
 
+unsafe impl Send for MappedBuffer {}
+unsafe impl Sync for MappedBuffer {}
+
 impl Drop for MappedBuffer {
     fn drop(&mut self) {
         let ctx = &self.external_memory.ctx;
         ctx.record_err(ctx.bind_to_thread());
         ctx.record_err(self.stream.wait(&self.event));
-        ctx.record_err(unsafe { result::memory_free(self.device_ptr) })
+        self.external_memory.unmap(self.device_ptr, &self.range);
+    }
+}
+
+impl MappedBuffer {
+    /// The external memory this buffer was mapped from.
+    pub fn external_memory(&self) -> &Arc<ExternalMemory> {
+        &self.external_memory
+    }
+
+    /// The underlying [sys::CUdeviceptr] of this mapping.
+    ///
+    /// Unlike [DevicePtr::device_ptr()], this does not record any event, so
+    /// **the caller is responsible for synchronizing** any use of the returned pointer
+    /// with other work on this buffer.
+    pub fn as_raw_ptr(&self) -> sys::CUdeviceptr {
+        self.device_ptr
+    }
+
+    /// The number of bytes in this mapping.
+    pub fn byte_len(&self) -> usize {
+        self.range.len()
+    }
+
+    /// The device pointer in the form expected by kernel launches (i.e. a pointer to the
+    /// [sys::CUdeviceptr]), suitable for passing in a `kernelParams` array.
+    ///
+    /// Like [Self::as_raw_ptr()], no events are recorded, so **the caller is responsible
+    /// for synchronization**. The returned pointer is only valid while `self` is alive
+    /// and not moved.
+    pub fn as_kernel_param(&self) -> *mut std::ffi::c_void {
+        (&self.device_ptr) as *const sys::CUdeviceptr as *mut _
     }
 }
 
 impl DeviceSlice<u8> for MappedBuffer {
     fn len(&self) -> usize {
-        self.len
+        self.range.len()
     }
     fn stream(&self) -> &Arc<CudaStream> {
         &self.stream
@@ -159,3 +348,171 @@ impl DevicePtr<u8> for MappedBuffer {
         )
     }
 }
+
+/// A writable version of [MappedBuffer].
+///
+/// This struct can be created via [`ExternalMemory::map_range_mut`] or [`ExternalMemory::map_all_mut`].
+/// The underlying mapped buffer will be freed when this struct is dropped.
+#[derive(Debug)]
+pub struct MappedBufferMut {
+    device_ptr: sys::CUdeviceptr,
+    range: Range<usize>,
+    external_memory: Arc<ExternalMemory>,
+    read: CudaEvent,
+    write: CudaEvent,
+    stream: Arc<CudaStream>,
+}
+
+unsafe impl Send for MappedBufferMut {}
+unsafe impl Sync for MappedBufferMut {}
+
+impl Drop for MappedBufferMut {
+    fn drop(&mut self) {
+        let ctx = &self.external_memory.ctx;
+        ctx.record_err(ctx.bind_to_thread());
+        ctx.record_err(self.stream.wait(&self.read));
+        ctx.record_err(self.stream.wait(&self.write));
+        self.external_memory.unmap(self.device_ptr, &self.range);
+    }
+}
+
+impl MappedBufferMut {
+    /// The external memory this buffer was mapped from.
+    pub fn external_memory(&self) -> &Arc<ExternalMemory> {
+        &self.external_memory
+    }
+
+    /// The underlying [sys::CUdeviceptr] of this mapping.
+    ///
+    /// Unlike [DevicePtr::device_ptr()], this does not record any event, so
+    /// **the caller is responsible for synchronizing** any use of the returned pointer
+    /// with other work on this buffer.
+    pub fn as_raw_ptr(&self) -> sys::CUdeviceptr {
+        self.device_ptr
+    }
+
+    /// The number of bytes in this mapping.
+    pub fn byte_len(&self) -> usize {
+        self.range.len()
+    }
+
+    /// The device pointer in the form expected by kernel launches (i.e. a pointer to the
+    /// [sys::CUdeviceptr]), suitable for passing in a `kernelParams` array.
+    ///
+    /// Like [Self::as_raw_ptr()], no events are recorded, so **the caller is responsible
+    /// for synchronization**. The returned pointer is only valid while `self` is alive
+    /// and not moved.
+    pub fn as_kernel_param(&self) -> *mut std::ffi::c_void {
+        (&self.device_ptr) as *const sys::CUdeviceptr as *mut _
+    }
+}
+
+impl MappedBufferMut {
+    /// Sets every byte of this buffer to `value` using [result::memset_d8_async] on `stream`.
+    ///
+    /// The write is recorded, so dropping this buffer still waits for the memset to finish.
+    pub fn memset_bytes(&mut self, value: u8, stream: &CudaStream) -> Result<(), DriverError> {
+        stream.bind_to_thread()?;
+        let num_bytes = self.range.len();
+        let (dptr, _record) = self.device_ptr_mut(stream);
+        unsafe { result::memset_d8_async(dptr, value, num_bytes, stream.cu_stream) }
+    }
+}
+
+impl DeviceSlice<u8> for MappedBufferMut {
+    fn len(&self) -> usize {
+        self.range.len()
+    }
+    fn stream(&self) -> &Arc<CudaStream> {
+        &self.stream
+    }
+}
+
+impl DevicePtr<u8> for MappedBufferMut {
+    fn device_ptr<'a>(&'a self, stream: &'a CudaStream) -> (sys::CUdeviceptr, SyncOnDrop<'a>) {
+        stream.ctx.record_err(stream.wait(&self.write));
+        (
+            self.device_ptr,
+            SyncOnDrop::Record(Some((&self.read, stream))),
+        )
+    }
+}
+
+impl DevicePtrMut<u8> for MappedBufferMut {
+    fn device_ptr_mut<'a>(
+        &'a mut self,
+        stream: &'a CudaStream,
+    ) -> (sys::CUdeviceptr, SyncOnDrop<'a>) {
+        stream.ctx.record_err(stream.wait(&self.read));
+        stream.ctx.record_err(stream.wait(&self.write));
+        (
+            self.device_ptr,
+            SyncOnDrop::Record(Some((&self.write, stream))),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Allocates device memory with `cuMemCreate` and exports it as an opaque file descriptor,
+    /// the same kind of handle vulkan exports. Returns the fd, its size, and the allocation handle
+    /// that must be released with `cuMemRelease`.
+    #[cfg(unix)]
+    fn export_device_memory(ctx: &CudaContext) -> (File, u64, sys::CUmemGenericAllocationHandle) {
+        use std::os::fd::FromRawFd;
+        ctx.bind_to_thread().unwrap();
+        let mut prop: sys::CUmemAllocationProp = unsafe { std::mem::zeroed() };
+        prop.type_ = sys::CUmemAllocationType::CU_MEM_ALLOCATION_TYPE_PINNED;
+        prop.requestedHandleTypes =
+            sys::CUmemAllocationHandleType::CU_MEM_HANDLE_TYPE_POSIX_FILE_DESCRIPTOR;
+        prop.location.type_ = sys::CUmemLocationType::CU_MEM_LOCATION_TYPE_DEVICE;
+        prop.location.id = ctx.ordinal() as i32;
+        unsafe {
+            let mut size = 0;
+            sys::cuMemGetAllocationGranularity(
+                &mut size,
+                &prop,
+                sys::CUmemAllocationGranularity_flags::CU_MEM_ALLOC_GRANULARITY_MINIMUM,
+            )
+            .result()
+            .unwrap();
+            let mut handle = 0;
+            sys::cuMemCreate(&mut handle, size, &prop, 0)
+                .result()
+                .unwrap();
+            let mut fd: std::os::fd::RawFd = -1;
+            sys::cuMemExportToShareableHandle(
+                &mut fd as *mut _ as *mut _,
+                handle,
+                sys::CUmemAllocationHandleType::CU_MEM_HANDLE_TYPE_POSIX_FILE_DESCRIPTOR,
+                0,
+            )
+            .result()
+            .unwrap();
+            (File::from_raw_fd(fd), size as u64, handle)
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_map_all_mut_zeroed() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let (file, size, handle) = export_device_memory(&ctx);
+        let memory = Arc::new(unsafe { ctx.import_external_memory(file, size) }.unwrap());
+
+        let mut buf = memory.map_all_mut_zeroed(&stream).unwrap();
+        assert_eq!(buf.len(), size as usize);
+        assert!(stream.memcpy_dtov(&buf).unwrap().iter().all(|&b| b == 0));
+
+        buf.memset_bytes(7, &stream).unwrap();
+        assert!(stream.memcpy_dtov(&buf).unwrap().iter().all(|&b| b == 7));
+
+        drop(buf);
+        drop(memory);
+        unsafe { sys::cuMemRelease(handle) }.result().unwrap();
+        assert!(!ctx.has_recorded_error());
+    }
+}
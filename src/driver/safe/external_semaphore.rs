@@ -0,0 +1,155 @@
+use std::fs::File;
+use std::mem::ManuallyDrop;
+use std::sync::Arc;
+
+use super::{CudaContext, CudaStream};
+use crate::driver::{result, sys, DriverError};
+
+/// An abstraction for an imported external semaphore.
+///
+/// This struct can be created via [`CudaContext::import_external_semaphore()`]
+/// or [`CudaContext::import_timeline_semaphore()`].
+/// The imported external semaphore will be destroyed when this struct is dropped.
+#[derive(Debug)]
+pub struct ExternalSemaphore {
+    external_semaphore: sys::CUexternalSemaphore,
+    timeline: bool,
+    ctx: Arc<CudaContext>,
+    _file: ManuallyDrop<File>,
+}
+
+unsafe impl Send for ExternalSemaphore {}
+unsafe impl Sync for ExternalSemaphore {}
+
+impl Drop for ExternalSemaphore {
+    fn drop(&mut self) {
+        let ctx = &self.ctx;
+        ctx.record_err(ctx.bind_to_thread());
+
+        ctx.record_err(unsafe {
+            result::external_semaphore::destroy_external_semaphore(self.external_semaphore)
+        });
+
+        // Same as [super::ExternalMemory], ownership of the file descriptor is transferred
+        // to the CUDA driver on UNIX, but the handle must be released by us on Windows.
+        #[cfg(windows)]
+        unsafe {
+            ManuallyDrop::<File>::drop(&mut self._file)
+        };
+    }
+}
+
+impl CudaContext {
+    /// Import a binary external semaphore from a [`File`].
+    ///
+    /// # Safety
+    /// `file` must refer to a binary semaphore exported from another API (e.g. vulkan).
+    #[cfg(any(unix, windows))]
+    pub unsafe fn import_external_semaphore(
+        self: &Arc<Self>,
+        file: File,
+    ) -> Result<ExternalSemaphore, DriverError> {
+        self.import_semaphore(file, false)
+    }
+
+    /// Import a timeline external semaphore from a [`File`].
+    ///
+    /// # Safety
+    /// `file` must refer to a timeline semaphore exported from another API (e.g. vulkan).
+    #[cfg(any(unix, windows))]
+    pub unsafe fn import_timeline_semaphore(
+        self: &Arc<Self>,
+        file: File,
+    ) -> Result<ExternalSemaphore, DriverError> {
+        self.import_semaphore(file, true)
+    }
+
+    #[cfg(any(unix, windows))]
+    unsafe fn import_semaphore(
+        self: &Arc<Self>,
+        file: File,
+        timeline: bool,
+    ) -> Result<ExternalSemaphore, DriverError> {
+        self.bind_to_thread()?;
+
+        #[cfg(unix)]
+        let external_semaphore = unsafe {
+            use std::os::fd::AsRawFd;
+            let type_ = if timeline {
+                sys::CUexternalSemaphoreHandleType::CU_EXTERNAL_SEMAPHORE_HANDLE_TYPE_TIMELINE_SEMAPHORE_FD
+            } else {
+                sys::CUexternalSemaphoreHandleType::CU_EXTERNAL_SEMAPHORE_HANDLE_TYPE_OPAQUE_FD
+            };
+            result::external_semaphore::import_external_semaphore_fd(file.as_raw_fd(), type_)
+        }?;
+        #[cfg(windows)]
+        let external_semaphore = unsafe {
+            use std::os::windows::io::AsRawHandle;
+            let type_ = if timeline {
+                sys::CUexternalSemaphoreHandleType::CU_EXTERNAL_SEMAPHORE_HANDLE_TYPE_TIMELINE_SEMAPHORE_WIN32
+            } else {
+                sys::CUexternalSemaphoreHandleType::CU_EXTERNAL_SEMAPHORE_HANDLE_TYPE_OPAQUE_WIN32
+            };
+            result::external_semaphore::import_external_semaphore_win32(file.as_raw_handle(), type_)
+        }?;
+        Ok(ExternalSemaphore {
+            external_semaphore,
+            timeline,
+            ctx: self.clone(),
+            _file: ManuallyDrop::new(file),
+        })
+    }
+}
+
+impl ExternalSemaphore {
+    /// The context this semaphore was imported into.
+    pub fn context(&self) -> &Arc<CudaContext> {
+        &self.ctx
+    }
+
+    /// Whether this was imported with [`CudaContext::import_timeline_semaphore()`].
+    pub fn is_timeline(&self) -> bool {
+        self.timeline
+    }
+
+    /// Schedules a signal of this semaphore on `stream`.
+    ///
+    /// For timeline semaphores the semaphore is set to `value`. For binary
+    /// semaphores `value` is ignored and the semaphore is set to the signaled state.
+    ///
+    /// If `stream` belongs to a different [CudaContext], this will fail with
+    /// [sys::cudaError_enum::CUDA_ERROR_INVALID_CONTEXT].
+    pub fn signal(&self, stream: &CudaStream, value: u64) -> Result<(), DriverError> {
+        if self.ctx != stream.ctx {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_CONTEXT));
+        }
+        self.ctx.bind_to_thread()?;
+        let value = if self.timeline { value } else { 0 };
+        unsafe {
+            result::external_semaphore::signal_async(
+                self.external_semaphore,
+                value,
+                stream.cu_stream,
+            )
+        }
+    }
+
+    /// Makes all future work submitted to `stream` wait on this semaphore.
+    ///
+    /// For timeline semaphores this waits until the semaphore reaches at least `value`.
+    /// For binary semaphores `value` is ignored and this waits until the semaphore is
+    /// signaled (and then resets it).
+    ///
+    /// If `stream` belongs to a different [CudaContext], this will fail with
+    /// [sys::cudaError_enum::CUDA_ERROR_INVALID_CONTEXT].
+    pub fn wait(&self, stream: &CudaStream, value: u64) -> Result<(), DriverError> {
+        if self.ctx != stream.ctx {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_CONTEXT));
+        }
+        self.ctx.bind_to_thread()?;
+        let value = if self.timeline { value } else { 0 };
+        unsafe {
+            result::external_semaphore::wait_async(self.external_semaphore, value, stream.cu_stream)
+        }
+    }
+}
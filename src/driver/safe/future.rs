@@ -0,0 +1,139 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+use super::CudaStream;
+use crate::driver::{result, sys, DriverError};
+
+/// A future that resolves once the work submitted to a [CudaStream] before
+/// [CudaStream::synchronize_async()] was called has completed. Requires the `tokio` feature.
+///
+/// This does not block the thread polling it, so it can be awaited on tokio without stalling
+/// the executor. It doesn't depend on tokio itself, so it works with any other executor too.
+/// The task is woken by a driver callback, there is no polling timer.
+///
+/// Dropping the future before it resolves is fine, the stream and its work are not affected.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct SynchronizeFuture {
+    shared: Arc<Mutex<Shared>>,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    result: Option<Result<(), DriverError>>,
+    waker: Option<Waker>,
+}
+
+impl CudaStream {
+    /// Returns a future that resolves once all work currently submitted to this stream has completed,
+    /// like a non-blocking [CudaStream::synchronize()]. Requires the `tokio` feature.
+    ///
+    /// The synchronization point is when this is called, not when the future is first polled,
+    /// so work submitted afterwards is not waited on. If the stream hits an error (e.g. an illegal
+    /// memory access in a kernel) the future resolves to that error.
+    ///
+    /// This can't be used while the stream is being captured into a graph.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__STREAM.html)
+    pub fn synchronize_async(&self) -> SynchronizeFuture {
+        unsafe extern "C" fn callback(
+            _stream: sys::CUstream,
+            status: sys::CUresult,
+            arg: *mut std::ffi::c_void,
+        ) {
+            let shared = unsafe { Arc::from_raw(arg as *const Mutex<Shared>) };
+            let waker = {
+                let mut shared = shared.lock().unwrap_or_else(|e| e.into_inner());
+                shared.result = Some(status.result());
+                shared.waker.take()
+            };
+            // NOTE: waking only schedules the task, it must not run CUDA calls on this thread
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        }
+
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let res = self.bind_to_thread().and_then(|_| {
+            let arg = Arc::into_raw(shared.clone());
+            let res =
+                unsafe { result::stream::add_callback(self.cu_stream, callback, arg as *mut _) };
+            if res.is_err() {
+                // the driver never took ownership, so we have to release it
+                drop(unsafe { Arc::from_raw(arg) });
+            }
+            res
+        });
+        if let Err(err) = res {
+            shared.lock().unwrap().result = Some(Err(err));
+        }
+        SynchronizeFuture { shared }
+    }
+}
+
+impl Future for SynchronizeFuture {
+    type Output = Result<(), DriverError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.shared.lock().unwrap_or_else(|e| e.into_inner());
+        match shared.result {
+            Some(result) => Poll::Ready(result),
+            None => {
+                match shared.waker.as_mut() {
+                    Some(waker) => waker.clone_from(cx.waker()),
+                    None => shared.waker = Some(cx.waker().clone()),
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver::CudaContext;
+    use std::task::Wake;
+
+    struct ThreadWaker(std::thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    /// A minimal executor, so the tests don't depend on an async runtime.
+    fn block_on<F: Future>(fut: F) -> F::Output {
+        let mut fut = std::pin::pin!(fut);
+        let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match fut.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => std::thread::park(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_synchronize_async() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.new_stream().unwrap();
+        let a = stream.alloc_zeros::<u8>(1 << 26).unwrap();
+        let fut = stream.synchronize_async();
+        block_on(fut).unwrap();
+        assert!(stream.is_idle().unwrap());
+        drop(a);
+
+        // dropping a pending future leaves the stream usable
+        let b = stream.memcpy_stod(&[1u32; 1024]).unwrap();
+        drop(stream.synchronize_async());
+        assert_eq!(stream.memcpy_dtov(&b).unwrap(), [1; 1024]);
+        block_on(stream.synchronize_async()).unwrap();
+    }
+}
@@ -2,11 +2,38 @@ use std::sync::Arc;
 
 use crate::driver::{result, sys};
 
-use super::{CudaStream, DriverError};
+use super::{CudaContext, CudaStream, DriverError, LaunchArgs, LaunchConfig};
 
-/// Represents a replay-able Cuda Graph. Create with [CudaStream::begin_capture()] and [CudaStream::end_capture()].
+/// How a [CudaStream::begin_capture()] interacts with potentially unsafe API calls
+/// from other threads. See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__STREAM.html#group__CUDA__STREAM_1g767167da0bbf07157dc20b6c258a2143)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CaptureMode {
+    /// Potentially unsafe API calls are prohibited on any thread while any
+    /// thread has an ongoing global capture.
+    Global,
+    /// Potentially unsafe API calls are prohibited only on the capturing thread.
+    ThreadLocal,
+    /// Potentially unsafe API calls are not prohibited at all.
+    Relaxed,
+}
+
+impl From<CaptureMode> for sys::CUstreamCaptureMode {
+    fn from(mode: CaptureMode) -> Self {
+        match mode {
+            CaptureMode::Global => sys::CUstreamCaptureMode::CU_STREAM_CAPTURE_MODE_GLOBAL,
+            CaptureMode::ThreadLocal => {
+                sys::CUstreamCaptureMode::CU_STREAM_CAPTURE_MODE_THREAD_LOCAL
+            }
+            CaptureMode::Relaxed => sys::CUstreamCaptureMode::CU_STREAM_CAPTURE_MODE_RELAXED,
+        }
+    }
+}
+
+/// A Cuda Graph. Either capture one with [CudaStream::begin_capture()] and [CudaStream::end_capture()],
+/// or build one explicitly with [CudaContext::new_graph()] and the `add_*_node` methods
+/// (e.g. [CudaGraph::add_kernel_node()]).
 ///
-/// Once created you can replay with [CudaGraph::launch()].
+/// Once created, turn it into a replay-able [CudaGraphExec] with [CudaGraph::instantiate()].
 ///
 /// # On Thread safety
 ///
@@ -21,64 +48,436 @@ use super::{CudaStream, DriverError};
 /// <https://docs.nvidia.com/cuda/cuda-driver-api/graphs-thread-safety.html#graphs-thread-safety>
 pub struct CudaGraph {
     cu_graph: sys::CUgraph,
-    cu_graph_exec: sys::CUgraphExec,
-    stream: Arc<CudaStream>,
+    ctx: Arc<CudaContext>,
 }
 
 impl Drop for CudaGraph {
     fn drop(&mut self) {
-        let ctx = &self.stream.ctx;
+        let ctx = &self.ctx;
+        ctx.record_err(ctx.bind_to_thread());
+        ctx.record_err(unsafe { result::graph::destroy(self.cu_graph) });
+    }
+}
 
-        let cu_graph_exec = std::mem::replace(&mut self.cu_graph_exec, std::ptr::null_mut());
-        if !cu_graph_exec.is_null() {
-            ctx.record_err(unsafe { result::graph::exec_destroy(cu_graph_exec) });
-        }
+/// A node of a [CudaGraph], returned by [CudaGraph::nodes()].
+///
+/// This is just a handle, and is only valid while the graph it belongs to is alive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GraphNode {
+    cu_graph_node: sys::CUgraphNode,
+}
 
-        let cu_graph = std::mem::replace(&mut self.cu_graph, std::ptr::null_mut());
-        if !cu_graph.is_null() {
-            ctx.record_err(unsafe { result::graph::destroy(cu_graph) });
-        }
+impl GraphNode {
+    /// The underlying [sys::CUgraphNode].
+    pub fn cu_graph_node(&self) -> sys::CUgraphNode {
+        self.cu_graph_node
+    }
+
+    /// What kind of work this node does.
+    pub fn node_type(&self) -> Result<sys::CUgraphNodeType, DriverError> {
+        unsafe { result::graph::node_get_type(self.cu_graph_node) }
+    }
+}
+
+/// Builds the kernel node params of the function & arguments in `args`.
+///
+/// `args` must outlive any use of the returned value.
+fn kernel_node_params(args: &mut LaunchArgs, cfg: LaunchConfig) -> sys::CUDA_KERNEL_NODE_PARAMS {
+    // zeroed so that the extra fields of the cuda 12 version of this struct are null
+    let mut params: sys::CUDA_KERNEL_NODE_PARAMS = unsafe { std::mem::zeroed() };
+    params.func = args.func.cu_function;
+    (params.gridDimX, params.gridDimY, params.gridDimZ) = cfg.grid_dim;
+    (params.blockDimX, params.blockDimY, params.blockDimZ) = cfg.block_dim;
+    params.sharedMemBytes = cfg.shared_mem_bytes;
+    params.kernelParams = args.args.as_mut_ptr();
+    params
+}
+
+/// An instantiated [CudaGraph] that can be replayed with [CudaGraphExec::launch()].
+///
+/// See [CudaGraph] for notes on thread safety, which apply here as well.
+pub struct CudaGraphExec {
+    cu_graph_exec: sys::CUgraphExec,
+    ctx: Arc<CudaContext>,
+}
+
+impl Drop for CudaGraphExec {
+    fn drop(&mut self) {
+        let ctx = &self.ctx;
+        ctx.record_err(ctx.bind_to_thread());
+        ctx.record_err(unsafe { result::graph::exec_destroy(self.cu_graph_exec) });
     }
 }
 
 impl CudaStream {
+    /// Starts capturing all work submitted to this stream into a [CudaGraph].
+    /// Finish with [CudaStream::end_capture()].
+    ///
+    /// Note that the legacy default stream cannot be captured, use [CudaContext::new_stream()].
+    ///
     /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__STREAM.html#group__CUDA__STREAM_1g767167da0bbf07157dc20b6c258a2143)
-    pub fn begin_capture(&self, mode: sys::CUstreamCaptureMode) -> Result<(), DriverError> {
-        self.ctx.bind_to_thread()?;
-        unsafe { result::stream::begin_capture(self.cu_stream, mode) }
+    pub fn begin_capture(&self, mode: CaptureMode) -> Result<(), DriverError> {
+        self.bind_to_thread()?;
+        unsafe { result::stream::begin_capture(self.cu_stream, mode.into()) }
     }
 
-    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__STREAM.html#group__CUDA__STREAM_1g03dab8b2ba76b00718955177a929970c)
+    /// Ends the capture started with [CudaStream::begin_capture()].
     ///
-    /// `flags` is passed to [cuGraphInstantiate](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__GRAPH.html#group__CUDA__GRAPH_1gb53b435e178cccfa37ac87285d2c3fa1)
-    pub fn end_capture(
-        self: &Arc<Self>,
-        flags: sys::CUgraphInstantiate_flags,
-    ) -> Result<Option<CudaGraph>, DriverError> {
-        self.ctx.bind_to_thread()?;
+    /// If the capture was invalidated this returns
+    /// [sys::cudaError_enum::CUDA_ERROR_STREAM_CAPTURE_INVALIDATED].
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__STREAM.html#group__CUDA__STREAM_1g03dab8b2ba76b00718955177a929970c)
+    pub fn end_capture(&self) -> Result<CudaGraph, DriverError> {
+        self.bind_to_thread()?;
         let cu_graph = unsafe { result::stream::end_capture(self.cu_stream) }?;
         if cu_graph.is_null() {
-            return Ok(None);
+            return Err(DriverError(
+                sys::cudaError_enum::CUDA_ERROR_STREAM_CAPTURE_INVALIDATED,
+            ));
         }
-        let cu_graph_exec = unsafe { result::graph::instantiate(cu_graph, flags) }?;
-        Ok(Some(CudaGraph {
+        Ok(CudaGraph {
             cu_graph,
-            cu_graph_exec,
-            stream: self.clone(),
-        }))
+            ctx: self.ctx.clone(),
+        })
     }
 
     /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__STREAM.html#group__CUDA__STREAM_1g37823c49206e3704ae23c7ad78560bca)
     pub fn capture_status(&self) -> Result<sys::CUstreamCaptureStatus, DriverError> {
-        self.ctx.bind_to_thread()?;
+        self.bind_to_thread()?;
         unsafe { result::stream::is_capturing(self.cu_stream) }
     }
 }
 
+impl CudaContext {
+    /// Creates an empty [CudaGraph] to add nodes to explicitly. Each `add_*_node` method takes the
+    /// nodes the new node depends on, and returns the new node so later nodes can depend on it.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__GRAPH.html)
+    pub fn new_graph(self: &Arc<Self>) -> Result<CudaGraph, DriverError> {
+        self.bind_to_thread()?;
+        let cu_graph = result::graph::create()?;
+        Ok(CudaGraph {
+            cu_graph,
+            ctx: self.clone(),
+        })
+    }
+}
+
 impl CudaGraph {
+    /// The context this graph was captured in.
+    pub fn context(&self) -> &Arc<CudaContext> {
+        &self.ctx
+    }
+
+    /// Adds a node that launches the function with the arguments added to `args`, after all of `deps`.
+    ///
+    /// The argument values are copied into the graph, but `args` is never launched itself.
+    ///
+    /// # Safety
+    /// 1. The arguments must match the kernel's signature, see [LaunchArgs::launch()].
+    /// 2. Any device memory referenced by the arguments must be valid whenever the graph is launched.
+    ///    Event tracking is not applied to graph nodes.
+    pub unsafe fn add_kernel_node(
+        &mut self,
+        args: &mut LaunchArgs,
+        cfg: LaunchConfig,
+        deps: &[GraphNode],
+    ) -> Result<GraphNode, DriverError> {
+        if self.ctx != args.stream.ctx {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_CONTEXT));
+        }
+        self.ctx.bind_to_thread()?;
+        let params = kernel_node_params(args, cfg);
+        let deps: Vec<_> = deps.iter().map(|n| n.cu_graph_node).collect();
+        let cu_graph_node = result::graph::add_kernel_node(self.cu_graph, &deps, &params)?;
+        Ok(GraphNode { cu_graph_node })
+    }
+
+    /// Adds a node that copies `num_bytes` from device memory `src` to device memory `dst`, after all of `deps`.
+    ///
+    /// Device pointers can be obtained with e.g. [crate::driver::DevicePtr::device_ptr()].
+    ///
+    /// # Safety
+    /// Both `src` and `dst` must be valid for `num_bytes` whenever the graph is launched.
+    pub unsafe fn add_memcpy_dtod_node(
+        &mut self,
+        dst: sys::CUdeviceptr,
+        src: sys::CUdeviceptr,
+        num_bytes: usize,
+        deps: &[GraphNode],
+    ) -> Result<GraphNode, DriverError> {
+        self.ctx.bind_to_thread()?;
+        let mut params: sys::CUDA_MEMCPY3D = std::mem::zeroed();
+        params.srcMemoryType = sys::CUmemorytype::CU_MEMORYTYPE_DEVICE;
+        params.srcDevice = src;
+        params.dstMemoryType = sys::CUmemorytype::CU_MEMORYTYPE_DEVICE;
+        params.dstDevice = dst;
+        params.WidthInBytes = num_bytes;
+        params.Height = 1;
+        params.Depth = 1;
+        let deps: Vec<_> = deps.iter().map(|n| n.cu_graph_node).collect();
+        let cu_graph_node =
+            result::graph::add_memcpy_node(self.cu_graph, &deps, &params, self.ctx.cu_ctx)?;
+        Ok(GraphNode { cu_graph_node })
+    }
+
+    /// Adds a node that sets `num_elements` elements of `element_size` bytes (1, 2 or 4) starting at
+    /// device memory `dst` to `value`, after all of `deps`.
+    ///
+    /// # Safety
+    /// `dst` must be valid for `num_elements * element_size` bytes whenever the graph is launched,
+    /// and aligned to `element_size`.
+    pub unsafe fn add_memset_node(
+        &mut self,
+        dst: sys::CUdeviceptr,
+        value: u32,
+        element_size: u32,
+        num_elements: usize,
+        deps: &[GraphNode],
+    ) -> Result<GraphNode, DriverError> {
+        self.ctx.bind_to_thread()?;
+        let params = sys::CUDA_MEMSET_NODE_PARAMS {
+            dst,
+            pitch: 0,
+            value,
+            elementSize: element_size,
+            width: num_elements,
+            height: 1,
+        };
+        let deps: Vec<_> = deps.iter().map(|n| n.cu_graph_node).collect();
+        let cu_graph_node =
+            result::graph::add_memset_node(self.cu_graph, &deps, &params, self.ctx.cu_ctx)?;
+        Ok(GraphNode { cu_graph_node })
+    }
+
+    /// All the nodes of this graph, e.g. to find the kernel nodes of a captured graph
+    /// for [CudaGraphExec::update_kernel_node_params()].
+    pub fn nodes(&self) -> Result<Vec<GraphNode>, DriverError> {
+        self.ctx.bind_to_thread()?;
+        let nodes = unsafe { result::graph::get_nodes(self.cu_graph) }?;
+        Ok(nodes
+            .into_iter()
+            .map(|cu_graph_node| GraphNode { cu_graph_node })
+            .collect())
+    }
+
+    /// Instantiates this graph with no flags. See [CudaGraph::instantiate_with_flags()].
+    pub fn instantiate(&self) -> Result<CudaGraphExec, DriverError> {
+        self.ctx.bind_to_thread()?;
+        let cu_graph_exec = unsafe { result::graph::instantiate_with_raw_flags(self.cu_graph, 0) }?;
+        Ok(CudaGraphExec {
+            cu_graph_exec,
+            ctx: self.ctx.clone(),
+        })
+    }
+
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__GRAPH.html#group__CUDA__GRAPH_1gb53b435e178cccfa37ac87285d2c3fa1)
+    pub fn instantiate_with_flags(
+        &self,
+        flags: sys::CUgraphInstantiate_flags,
+    ) -> Result<CudaGraphExec, DriverError> {
+        self.ctx.bind_to_thread()?;
+        let cu_graph_exec = unsafe { result::graph::instantiate(self.cu_graph, flags) }?;
+        Ok(CudaGraphExec {
+            cu_graph_exec,
+            ctx: self.ctx.clone(),
+        })
+    }
+}
+
+impl CudaGraphExec {
+    /// The context this graph was instantiated in.
+    pub fn context(&self) -> &Arc<CudaContext> {
+        &self.ctx
+    }
+
+    /// Replaces the function, launch config and arguments of the kernel `node`, using the function
+    /// and arguments added to `args`. This is much cheaper than capturing and instantiating a new graph
+    /// when e.g. only a pointer or scalar argument changes between replays.
+    ///
+    /// Only this [CudaGraphExec] is updated, the [CudaGraph] it was instantiated from is unchanged.
+    /// `args` is never launched, and its stream is only used to check the context.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__GRAPH.html)
+    ///
+    /// # Safety
+    /// 1. `node` must be a kernel node of the graph this was instantiated from.
+    /// 2. The arguments must match the kernel's signature, see [LaunchArgs::launch()].
+    /// 3. Event tracking is not applied to the arguments, so you must synchronize access to
+    ///    any device memory used by them.
+    pub unsafe fn update_kernel_node_params(
+        &self,
+        node: &GraphNode,
+        args: &mut LaunchArgs,
+        cfg: LaunchConfig,
+    ) -> Result<(), DriverError> {
+        if self.ctx != args.stream.ctx {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_CONTEXT));
+        }
+        self.ctx.bind_to_thread()?;
+        let params = kernel_node_params(args, cfg);
+        result::graph::exec_kernel_node_set_params(self.cu_graph_exec, node.cu_graph_node, &params)
+    }
+
+    /// Replays the graph on `stream`.
+    ///
+    /// If `stream` belongs to a different [CudaContext], this will fail with
+    /// [sys::cudaError_enum::CUDA_ERROR_INVALID_CONTEXT].
+    ///
     /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__GRAPH.html#group__CUDA__GRAPH_1g6b2dceb3901e71a390d2bd8b0491e471)
-    pub fn launch(&self) -> Result<(), DriverError> {
-        self.stream.ctx.bind_to_thread()?;
-        unsafe { result::graph::launch(self.cu_graph_exec, self.stream.cu_stream) }
+    pub fn launch(&self, stream: &CudaStream) -> Result<(), DriverError> {
+        if self.ctx != stream.ctx {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_CONTEXT));
+        }
+        self.ctx.bind_to_thread()?;
+        unsafe { result::graph::launch(self.cu_graph_exec, stream.cu_stream) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver::{DevicePtr, PushKernelArg};
+    use crate::nvrtc::compile_ptx;
+
+    #[test]
+    fn test_update_kernel_node_params() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.new_stream().unwrap();
+        let ptx = compile_ptx(
+            "
+extern \"C\" __global__ void fill(float *out, float value, int n) {
+    int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i < n) {
+        out[i] = value;
+    }
+}",
+        )
+        .unwrap();
+        let module = ctx.load_module(ptx).unwrap();
+        let fill = module.load_function("fill").unwrap();
+
+        let n = 100i32;
+        let cfg = LaunchConfig::for_num_elems(n as u32);
+        let mut a = stream.alloc_zeros::<f32>(n as usize).unwrap();
+        let mut b = stream.alloc_zeros::<f32>(n as usize).unwrap();
+        stream.synchronize().unwrap();
+
+        unsafe { ctx.disable_event_tracking() };
+        stream.begin_capture(CaptureMode::ThreadLocal).unwrap();
+        let one = 1.0f32;
+        unsafe {
+            stream
+                .launch_builder(&fill)
+                .arg(&mut a)
+                .arg(&one)
+                .arg(&n)
+                .launch(cfg)
+        }
+        .unwrap();
+        let graph = stream.end_capture().unwrap();
+        unsafe { ctx.enable_event_tracking() };
+
+        let nodes = graph.nodes().unwrap();
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(
+            nodes[0].node_type().unwrap(),
+            sys::CUgraphNodeType::CU_GRAPH_NODE_TYPE_KERNEL
+        );
+
+        let exec = graph.instantiate().unwrap();
+        exec.launch(&stream).unwrap();
+        stream.synchronize().unwrap();
+        assert_eq!(stream.memcpy_dtov(&a).unwrap(), vec![1.0; n as usize]);
+
+        let two = 2.0f32;
+        let mut args = stream.launch_builder(&fill);
+        args.arg(&mut b).arg(&two).arg(&n);
+        unsafe { exec.update_kernel_node_params(&nodes[0], &mut args, cfg) }.unwrap();
+        exec.launch(&stream).unwrap();
+        stream.synchronize().unwrap();
+        assert_eq!(stream.memcpy_dtov(&a).unwrap(), vec![1.0; n as usize]);
+        assert_eq!(stream.memcpy_dtov(&b).unwrap(), vec![2.0; n as usize]);
+    }
+
+    #[test]
+    fn test_explicit_graph() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.new_stream().unwrap();
+        let ptx = compile_ptx(
+            "
+extern \"C\" __global__ void add_one(float *x, int n) {
+    int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i < n) {
+        x[i] += 1.0f;
+    }
+}",
+        )
+        .unwrap();
+        let module = ctx.load_module(ptx).unwrap();
+        let add_one = module.load_function("add_one").unwrap();
+
+        let n = 256i32;
+        let cfg = LaunchConfig::for_num_elems(n as u32);
+        let mut a = stream.alloc_zeros::<f32>(n as usize).unwrap();
+        let b = stream.alloc_zeros::<f32>(n as usize).unwrap();
+        stream.synchronize().unwrap();
+        let a_ptr = a.device_ptr(&stream).0;
+        let b_ptr = b.device_ptr(&stream).0;
+        let num_bytes = n as usize * std::mem::size_of::<f32>();
+
+        // memset(a, 2.0) -> a += 1 -> copy a to b
+        let mut graph = ctx.new_graph().unwrap();
+        let memset =
+            unsafe { graph.add_memset_node(a_ptr, 2.0f32.to_bits(), 4, n as usize, &[]) }.unwrap();
+        let mut args = stream.launch_builder(&add_one);
+        args.arg(&mut a).arg(&n);
+        let kernel = unsafe { graph.add_kernel_node(&mut args, cfg, &[memset]) }.unwrap();
+        drop(args);
+        let memcpy =
+            unsafe { graph.add_memcpy_dtod_node(b_ptr, a_ptr, num_bytes, &[kernel]) }.unwrap();
+
+        let nodes = graph.nodes().unwrap();
+        assert_eq!(nodes.len(), 3);
+        for node in [memset, kernel, memcpy] {
+            assert!(nodes.contains(&node));
+        }
+        assert_eq!(
+            memcpy.node_type().unwrap(),
+            sys::CUgraphNodeType::CU_GRAPH_NODE_TYPE_MEMCPY
+        );
+
+        let exec = graph.instantiate().unwrap();
+        for _ in 0..3 {
+            exec.launch(&stream).unwrap();
+        }
+        stream.synchronize().unwrap();
+        assert_eq!(stream.memcpy_dtov(&b).unwrap(), vec![3.0; n as usize]);
+    }
+
+    #[test]
+    fn test_capture_and_replay() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.new_stream().unwrap();
+        let mut slice = stream.alloc_zeros::<u32>(100).unwrap();
+        stream.memcpy_htod(&[1u32; 100], &mut slice).unwrap();
+        stream.synchronize().unwrap();
+
+        // events recorded outside of the capture can't be waited on inside it
+        unsafe { ctx.disable_event_tracking() };
+        stream.begin_capture(CaptureMode::ThreadLocal).unwrap();
+        stream.memset_zeros(&mut slice).unwrap();
+        let graph = stream.end_capture().unwrap();
+        unsafe { ctx.enable_event_tracking() };
+
+        // capturing does not execute the work
+        assert_eq!(stream.memcpy_dtov(&slice).unwrap(), [1u32; 100]);
+
+        let exec = graph.instantiate().unwrap();
+        for _ in 0..10 {
+            exec.launch(&stream).unwrap();
+        }
+        stream.synchronize().unwrap();
+        assert_eq!(stream.memcpy_dtov(&slice).unwrap(), [0u32; 100]);
     }
 }
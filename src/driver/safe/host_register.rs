@@ -0,0 +1,131 @@
+use std::sync::Arc;
+
+use super::CudaContext;
+use crate::driver::{result, sys, DriverError};
+
+/// Existing host memory that has been page-locked with [CudaContext::register_host_memory()].
+///
+/// Transfers to and from registered memory are as fast as with [crate::driver::PinnedHostSlice],
+/// without having to copy the data into a fresh pinned allocation first.
+///
+/// The memory is unregistered on drop, it is **not** freed.
+#[derive(Debug)]
+pub struct HostRegistration {
+    ptr: *mut u8,
+    len: usize,
+    ctx: Arc<CudaContext>,
+}
+
+unsafe impl Send for HostRegistration {}
+unsafe impl Sync for HostRegistration {}
+
+impl Drop for HostRegistration {
+    fn drop(&mut self) {
+        let ctx = &self.ctx;
+        ctx.record_err(ctx.bind_to_thread());
+        ctx.record_err(unsafe { result::host_unregister(self.ptr as *mut _) });
+    }
+}
+
+impl CudaContext {
+    /// Page-locks `len` bytes of existing host memory starting at `ptr` (e.g. an mmap'd file or
+    /// a buffer owned by another library). `flags` is a combination of the `sys::CU_MEMHOSTREGISTER_*`
+    /// flags, e.g. [sys::CU_MEMHOSTREGISTER_DEVICEMAP] to be able to use [HostRegistration::device_pointer()].
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MEM.html)
+    ///
+    /// # Safety
+    /// 1. `ptr` must point to `len` bytes of valid host memory.
+    /// 2. The memory must **outlive** the returned [HostRegistration], and must not be freed,
+    ///    reallocated (e.g. by pushing to the `Vec` that owns it) or unmapped while it is registered.
+    /// 3. Any async copies involving the memory must complete before the registration is dropped.
+    pub unsafe fn register_host_memory(
+        self: &Arc<Self>,
+        ptr: *mut u8,
+        len: usize,
+        flags: std::ffi::c_uint,
+    ) -> Result<HostRegistration, DriverError> {
+        self.bind_to_thread()?;
+        result::host_register(ptr as *mut _, len, flags)?;
+        Ok(HostRegistration {
+            ptr,
+            len,
+            ctx: self.clone(),
+        })
+    }
+}
+
+impl HostRegistration {
+    /// The context this memory is registered with.
+    pub fn context(&self) -> &Arc<CudaContext> {
+        &self.ctx
+    }
+
+    /// The start of the registered host memory.
+    pub fn as_ptr(&self) -> *mut u8 {
+        self.ptr
+    }
+
+    /// Number of registered bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The device address the registered memory is mapped to, which kernels can read and write directly.
+    ///
+    /// The memory should have been registered with [sys::CU_MEMHOSTREGISTER_DEVICEMAP]. On systems without
+    /// unified addressing this returns an error otherwise.
+    pub fn device_pointer(&self) -> Result<sys::CUdeviceptr, DriverError> {
+        self.ctx.bind_to_thread()?;
+        unsafe { result::host_get_device_pointer(self.ptr as *mut _) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_host_memory() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+
+        let mut host = vec![0u32; 1024];
+        let reg = unsafe {
+            ctx.register_host_memory(
+                host.as_mut_ptr() as *mut u8,
+                host.len() * std::mem::size_of::<u32>(),
+                sys::CU_MEMHOSTREGISTER_DEVICEMAP,
+            )
+        }
+        .unwrap();
+        assert_eq!(reg.len(), 4096);
+
+        let src = stream.memcpy_stod(&[7u32; 1024]).unwrap();
+        let dptr = reg.device_pointer().unwrap();
+        unsafe {
+            use crate::driver::DevicePtr;
+            let (src_ptr, _record) = src.device_ptr(&stream);
+            result::memcpy_dtod_async(dptr, src_ptr, reg.len(), stream.cu_stream()).unwrap();
+        }
+        stream.synchronize().unwrap();
+        drop(reg);
+        assert_eq!(host, [7u32; 1024]);
+    }
+
+    #[test]
+    fn test_register_host_memory_htod() {
+        let ctx = CudaContext::new(0).unwrap();
+        let mut host: Vec<u8> = (0..4096).map(|i| i as u8).collect();
+        let reg = unsafe { ctx.register_host_memory(host.as_mut_ptr(), host.len(), 0) }.unwrap();
+        let stream = ctx.default_stream();
+        let mut dst = stream.alloc_zeros::<u8>(4096).unwrap();
+        stream.memcpy_htod(&host, &mut dst).unwrap();
+        assert_eq!(stream.memcpy_dtov(&dst).unwrap(), host);
+        drop(reg);
+    }
+}
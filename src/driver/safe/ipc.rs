@@ -0,0 +1,136 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use super::core::SliceOwner;
+use super::{CudaContext, CudaSlice, CudaStream, DeviceRepr, ValidAsZeroBits};
+use crate::driver::{result, sys, DriverError};
+
+/// A handle to a device allocation that can be sent to another process on the same node,
+/// created with [CudaSlice::export_ipc_handle()] and opened with [CudaContext::import_ipc_handle()].
+///
+/// This is plain bytes, so it can be sent over e.g. a unix socket with [CudaIpcMemHandle::as_bytes()]
+/// and [CudaIpcMemHandle::from_bytes()].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CudaIpcMemHandle(pub [u8; 64]);
+
+impl CudaIpcMemHandle {
+    /// The raw bytes of the handle.
+    pub fn as_bytes(&self) -> &[u8; 64] {
+        &self.0
+    }
+
+    /// Reconstructs a handle from [CudaIpcMemHandle::as_bytes()].
+    pub fn from_bytes(bytes: [u8; 64]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<sys::CUipcMemHandle> for CudaIpcMemHandle {
+    fn from(handle: sys::CUipcMemHandle) -> Self {
+        Self(handle.reserved.map(|b| b as u8))
+    }
+}
+
+impl From<CudaIpcMemHandle> for sys::CUipcMemHandle {
+    fn from(handle: CudaIpcMemHandle) -> Self {
+        sys::CUipcMemHandle {
+            reserved: handle.0.map(|b| b as _),
+        }
+    }
+}
+
+impl CudaStream {
+    /// Allocates a [CudaSlice] that can be shared with [CudaSlice::export_ipc_handle()].
+    ///
+    /// Unlike [CudaStream::alloc()], this never uses stream ordered allocation, because the driver
+    /// does not support exporting those allocations.
+    ///
+    /// # Safety
+    /// This is unsafe because the memory is unset.
+    pub unsafe fn alloc_exportable<T: DeviceRepr>(
+        self: &Arc<Self>,
+        len: usize,
+    ) -> Result<CudaSlice<T>, DriverError> {
+        self.bind_to_thread()?;
+        let cu_device_ptr = result::malloc_sync(len * std::mem::size_of::<T>())?;
+        Ok(self.upgrade_device_ptr(cu_device_ptr, len))
+    }
+
+    /// Same as [CudaStream::alloc_exportable()], but all values are zero'd out.
+    pub fn alloc_exportable_zeros<T: DeviceRepr + ValidAsZeroBits>(
+        self: &Arc<Self>,
+        len: usize,
+    ) -> Result<CudaSlice<T>, DriverError> {
+        let mut dst = unsafe { self.alloc_exportable(len) }?;
+        self.memset_zeros(&mut dst)?;
+        Ok(dst)
+    }
+}
+
+impl<T> CudaSlice<T> {
+    /// Creates a handle that another process can open with [CudaContext::import_ipc_handle()].
+    ///
+    /// The slice must have been allocated with [CudaStream::alloc_exportable()]. Memory from [CudaStream::alloc()]
+    /// is usually stream ordered (see [CudaContext::default_mem_pool()]), and the driver will return an error for it.
+    ///
+    /// This slice must stay alive while the other process uses it, and the other process must
+    /// synchronize with any work on this slice itself (e.g. after [CudaStream::synchronize()] here).
+    pub fn export_ipc_handle(&self) -> Result<CudaIpcMemHandle, DriverError> {
+        self.stream.ctx.bind_to_thread()?;
+        let handle = unsafe { result::ipc::get_mem_handle(self.cu_device_ptr) }?;
+        Ok(handle.into())
+    }
+}
+
+impl CudaContext {
+    /// Opens a [CudaIpcMemHandle] exported by another process as a [CudaSlice] with `len` elements
+    /// on [CudaContext::default_stream()]. Peer access to the exporting device is enabled lazily if needed.
+    ///
+    /// Dropping the returned slice closes the handle, it does not free the original allocation.
+    ///
+    /// # Safety
+    /// 1. `handle` must have been created by [CudaSlice::export_ipc_handle()] in **another** process.
+    /// 2. The exported slice must contain at least `len` elements of type `T`, and stay alive while this slice is used.
+    pub unsafe fn import_ipc_handle<T: DeviceRepr>(
+        self: &Arc<Self>,
+        handle: CudaIpcMemHandle,
+        len: usize,
+    ) -> Result<CudaSlice<T>, DriverError> {
+        self.bind_to_thread()?;
+        let cu_device_ptr = result::ipc::open_mem_handle(
+            handle.into(),
+            sys::CUipcMem_flags::CU_IPC_MEM_LAZY_ENABLE_PEER_ACCESS,
+        )?;
+        let (read, write) = if self.is_event_tracking() {
+            (Some(self.new_event(None)?), Some(self.new_event(None)?))
+        } else {
+            (None, None)
+        };
+        Ok(CudaSlice {
+            cu_device_ptr,
+            len,
+            read,
+            write,
+            stream: self.default_stream(),
+            owner: SliceOwner::IpcImport,
+            marker: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_ipc_handle() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let a = stream.alloc_exportable_zeros::<f32>(100).unwrap();
+        let handle = a.export_ipc_handle().unwrap();
+        let bytes = *handle.as_bytes();
+        assert_eq!(CudaIpcMemHandle::from_bytes(bytes), handle);
+        let raw: sys::CUipcMemHandle = handle.into();
+        assert_eq!(CudaIpcMemHandle::from(raw), handle);
+    }
+}
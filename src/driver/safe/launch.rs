@@ -20,7 +20,9 @@ pub struct LaunchConfig {
     /// (x, y, z) dimension of each thread block
     pub block_dim: (u32, u32, u32),
 
-    /// Dynamic shared-memory size per thread block in bytes
+    /// Dynamic shared-memory size per thread block in bytes.
+    ///
+    /// More than 48KB requires calling [CudaFunction::set_max_dynamic_shared_size()] first.
     pub shared_mem_bytes: u32,
 }
 
@@ -174,6 +176,37 @@ impl LaunchArgs<'_> {
         self
     }
 
+    #[inline(always)]
+    unsafe fn inner_launch(
+        &mut self,
+        cfg: LaunchConfig,
+    ) -> Result<Option<(CudaEvent, CudaEvent)>, DriverError> {
+        self.stream.bind_to_thread()?;
+        for &event in self.waits.iter() {
+            self.stream.wait(event)?;
+        }
+        let start_event = self
+            .flags
+            .map(|flags| self.stream.record_event(Some(flags)))
+            .transpose()?;
+        result::launch_kernel(
+            self.func.cu_function,
+            cfg.grid_dim,
+            cfg.block_dim,
+            cfg.shared_mem_bytes,
+            self.stream.cu_stream,
+            &mut self.args,
+        )?;
+        let end_event = self
+            .flags
+            .map(|flags| self.stream.record_event(Some(flags)))
+            .transpose()?;
+        for &event in self.records.iter() {
+            event.record(self.stream)?;
+        }
+        Ok(start_event.zip(end_event))
+    }
+
     /// Submits the configuration [CudaFunction] to execute asychronously on
     /// the configured device stream.
     ///
@@ -205,37 +238,6 @@ impl LaunchArgs<'_> {
     /// Since [LaunchArgs::launch()] properly records reads/writes for [CudaSlice]/[CudaView]/[CudaViewMut],
     /// and the drop implementation of [CudaSlice] waits on those events to finish,
     /// we will never encounter a use after free situation.
-    #[inline(always)]
-    unsafe fn inner_launch(
-        &mut self,
-        cfg: LaunchConfig,
-    ) -> Result<Option<(CudaEvent, CudaEvent)>, DriverError> {
-        self.stream.ctx.bind_to_thread()?;
-        for &event in self.waits.iter() {
-            self.stream.wait(event)?;
-        }
-        let start_event = self
-            .flags
-            .map(|flags| self.stream.record_event(Some(flags)))
-            .transpose()?;
-        result::launch_kernel(
-            self.func.cu_function,
-            cfg.grid_dim,
-            cfg.block_dim,
-            cfg.shared_mem_bytes,
-            self.stream.cu_stream,
-            &mut self.args,
-        )?;
-        let end_event = self
-            .flags
-            .map(|flags| self.stream.record_event(Some(flags)))
-            .transpose()?;
-        for &event in self.records.iter() {
-            event.record(self.stream)?;
-        }
-        Ok(start_event.zip(end_event))
-    }
-
     #[inline(always)]
     pub unsafe fn launch(
         &mut self,
@@ -253,16 +255,20 @@ impl LaunchArgs<'_> {
         result
     }
 
-    /// Launch a cooperative kernel.
-    ///
-    /// # Safety
-    /// See [LaunchArgs::launch()]
     #[inline(always)]
     unsafe fn inner_launch_cooperative(
         &mut self,
         cfg: LaunchConfig,
     ) -> Result<Option<(CudaEvent, CudaEvent)>, DriverError> {
-        self.stream.ctx.bind_to_thread()?;
+        if self
+            .stream
+            .ctx
+            .attribute(sys::CUdevice_attribute::CU_DEVICE_ATTRIBUTE_COOPERATIVE_LAUNCH)?
+            == 0
+        {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_NOT_SUPPORTED));
+        }
+        self.stream.bind_to_thread()?;
         for &event in self.waits.iter() {
             self.stream.wait(event)?;
         }
@@ -285,18 +291,22 @@ impl LaunchArgs<'_> {
         for &event in self.records.iter() {
             event.record(self.stream)?;
         }
-        let result = Ok(start_event.zip(end_event));
-        if self.stream.fuel_check {
-            match self.perform_fuel_check() {
-                Ok(()) => {}
-                Err(e) => {
-                    return Err(e);
-                }
-            }
-        }
-        result
+        Ok(start_event.zip(end_event))
     }
 
+    /// Launch a cooperative kernel, which allows grid wide synchronization (e.g. `grid.sync()` from cooperative groups).
+    ///
+    /// All blocks of the grid must be resident at the same time, so the grid can not be larger than
+    /// [CudaFunction::max_cooperative_grid_size()]. Otherwise the driver returns
+    /// [sys::cudaError_enum::CUDA_ERROR_COOPERATIVE_LAUNCH_TOO_LARGE].
+    ///
+    /// If the device does not support cooperative launches ([sys::CUdevice_attribute::CU_DEVICE_ATTRIBUTE_COOPERATIVE_LAUNCH] is 0),
+    /// this returns [sys::cudaError_enum::CUDA_ERROR_NOT_SUPPORTED].
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__EXEC.html)
+    ///
+    /// # Safety
+    /// See [LaunchArgs::launch()]
     #[inline(always)]
     pub unsafe fn launch_cooperative(
         &mut self,
@@ -352,6 +362,45 @@ impl LaunchArgs<'_> {
     }
 }
 
+/// Launches `func` on `stream` with `cfg` and the given arguments, in one expression.
+///
+/// This is shorthand for `stream.launch_builder(func).arg(a).arg(b)...launch(cfg)`, so each argument
+/// goes through [PushKernelArg] and must be a reference to a [DeviceRepr] type, or a (mutable) reference
+/// to a [CudaSlice]/[CudaView]/[CudaViewMut]. All arguments (including temporaries) are kept alive until
+/// the launch returns, and event tracking is the same as with [LaunchArgs::launch()].
+///
+/// Must be called inside an `unsafe` block, see [LaunchArgs::launch()] for the safety requirements.
+///
+/// ```no_run
+/// # use cudarc::driver::*;
+/// # fn run(stream: std::sync::Arc<CudaStream>, f: CudaFunction) -> Result<(), DriverError> {
+/// let mut out = stream.alloc_zeros::<f32>(100)?;
+/// let inp = stream.memcpy_stod(&[1.0f32; 100])?;
+/// let cfg = LaunchConfig::for_num_elems(100);
+/// unsafe { cudarc::launch!(stream, &f, cfg, &mut out, &inp, &100usize) }?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Arguments that aren't [DeviceRepr] are rejected at compile time:
+///
+/// ```compile_fail
+/// # use cudarc::driver::*;
+/// # fn run(stream: std::sync::Arc<CudaStream>, f: CudaFunction) -> Result<(), DriverError> {
+/// let name = String::from("not a kernel argument");
+/// unsafe { cudarc::launch!(stream, &f, LaunchConfig::for_num_elems(1), &name) }?;
+/// # Ok(())
+/// # }
+/// ```
+#[macro_export]
+macro_rules! launch {
+    ($stream:expr, $func:expr, $cfg:expr $(, $arg:expr)* $(,)?) => {{
+        #[allow(unused_imports)]
+        use $crate::driver::PushKernelArg as _;
+        $stream.launch_builder($func)$(.arg($arg))*.launch($cfg)
+    }};
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -414,6 +463,47 @@ extern \"C\" __global__ void kernel(const TensorMeta meta) {
         Ok(())
     }
 
+    #[test]
+    fn test_launch_cooperative() -> Result<(), DriverError> {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+
+        let ptx = compile_ptx_with_opts(
+            "
+extern \"C\" __global__ void kernel(unsigned int *out) {
+    atomicAdd(out, 1);
+}",
+            Default::default(),
+        )
+        .unwrap();
+        let module = ctx.load_module(ptx).unwrap();
+        let f = module.load_function("kernel").unwrap();
+
+        let mut out = stream.alloc_zeros::<u32>(1)?;
+        let cfg = LaunchConfig {
+            grid_dim: (f.max_cooperative_grid_size(32, 0)?, 1, 1),
+            block_dim: (32, 1, 1),
+            shared_mem_bytes: 0,
+        };
+        let res = unsafe {
+            stream
+                .launch_builder(&f)
+                .arg(&mut out)
+                .launch_cooperative(cfg)
+        };
+        if ctx.attribute(sys::CUdevice_attribute::CU_DEVICE_ATTRIBUTE_COOPERATIVE_LAUNCH)? == 0 {
+            assert_eq!(
+                res.unwrap_err(),
+                DriverError(sys::cudaError_enum::CUDA_ERROR_NOT_SUPPORTED)
+            );
+            return Ok(());
+        }
+        res?;
+        let out = stream.memcpy_dtov(&out)?;
+        assert_eq!(out[0], cfg.grid_dim.0 * 32);
+        Ok(())
+    }
+
     const SIN_CU: &str = "
 extern \"C\" __global__ void sin_kernel(float *out, const float *inp, size_t numel) {
     size_t i = blockIdx.x * blockDim.x + threadIdx.x;
@@ -422,6 +512,87 @@ extern \"C\" __global__ void sin_kernel(float *out, const float *inp, size_t num
     }
 }";
 
+    #[test]
+    fn test_suggested_launch_config() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+
+        let ptx = compile_ptx_with_opts(SIN_CU, Default::default()).unwrap();
+        let module = ctx.load_module(ptx).unwrap();
+        let sin_kernel = module.load_function("sin_kernel").unwrap();
+
+        let n = 100_000;
+        let cfg = sin_kernel.suggested_launch_config(n as u32, 0, 0).unwrap();
+        assert!(cfg.grid_dim.0 * cfg.block_dim.0 >= n as u32);
+
+        let cfg_smem = sin_kernel
+            .suggested_launch_config_with_smem(n as u32, |block_size| block_size as usize * 4, 256)
+            .unwrap();
+        assert!(cfg_smem.block_dim.0 <= 256);
+        assert_eq!(cfg_smem.shared_mem_bytes, cfg_smem.block_dim.0 * 4);
+
+        let a_dev = stream.memcpy_stod(&std::vec![0.0f32; n]).unwrap();
+        let mut b_dev = stream.alloc_zeros::<f32>(n).unwrap();
+        for cfg in [cfg, cfg_smem] {
+            unsafe {
+                stream
+                    .launch_builder(&sin_kernel)
+                    .arg(&mut b_dev)
+                    .arg(&a_dev)
+                    .arg(&n)
+                    .launch(cfg)
+            }
+            .unwrap();
+        }
+        stream.synchronize().unwrap();
+    }
+
+    #[test]
+    fn test_launch_with_large_dynamic_smem() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+
+        let ptx = compile_ptx_with_opts(
+            "
+extern \"C\" __global__ void kernel(float *out) {
+    extern __shared__ float buf[];
+    buf[threadIdx.x] = threadIdx.x;
+    __syncthreads();
+    out[threadIdx.x] = buf[threadIdx.x];
+}",
+            Default::default(),
+        )
+        .unwrap();
+        let module = ctx.load_module(ptx).unwrap();
+        let f = module.load_function("kernel").unwrap();
+
+        let max_smem = ctx
+            .attribute(
+                sys::CUdevice_attribute::CU_DEVICE_ATTRIBUTE_MAX_SHARED_MEMORY_PER_BLOCK_OPTIN,
+            )
+            .unwrap() as u32;
+        let static_smem = f
+            .attribute(sys::CUfunction_attribute::CU_FUNC_ATTRIBUTE_SHARED_SIZE_BYTES)
+            .unwrap() as u32;
+        let smem = max_smem - static_smem;
+        f.set_max_dynamic_shared_size(smem).unwrap();
+        assert_eq!(
+            f.attribute(sys::CUfunction_attribute::CU_FUNC_ATTRIBUTE_MAX_DYNAMIC_SHARED_SIZE_BYTES)
+                .unwrap() as u32,
+            smem
+        );
+
+        let mut out = stream.alloc_zeros::<f32>(32).unwrap();
+        let cfg = LaunchConfig {
+            grid_dim: (1, 1, 1),
+            block_dim: (32, 1, 1),
+            shared_mem_bytes: smem,
+        };
+        unsafe { stream.launch_builder(&f).arg(&mut out).launch(cfg) }.unwrap();
+        let out = stream.memcpy_dtov(&out).unwrap();
+        assert_eq!(out, (0..32).map(|i| i as f32).collect::<Vec<_>>());
+    }
+
     #[test]
     fn test_launch_with_mut_and_ref_cudarc() {
         let ctx = CudaContext::new(0).unwrap();
@@ -456,6 +627,76 @@ extern \"C\" __global__ void sin_kernel(float *out, const float *inp, size_t num
         drop(a_dev);
     }
 
+    #[test]
+    #[cfg(feature = "derive")]
+    fn test_launch_derived_device_repr() {
+        #[derive(Clone, Copy, crate::driver::DeviceRepr)]
+        #[repr(C)]
+        struct Affine {
+            scale: [f32; 2],
+            shift: f32,
+        }
+
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let ptx = compile_ptx_with_opts(
+            "
+struct Affine {
+    float scale[2];
+    float shift;
+};
+
+extern \"C\" __global__ void affine(float *out, const Affine a) {
+    out[threadIdx.x] = a.scale[threadIdx.x % 2] * threadIdx.x + a.shift;
+}",
+            Default::default(),
+        )
+        .unwrap();
+        let module = ctx.load_module(ptx).unwrap();
+        let f = module.load_function("affine").unwrap();
+
+        let mut out = stream.alloc_zeros::<f32>(4).unwrap();
+        let a = Affine {
+            scale: [1.0, 2.0],
+            shift: 0.5,
+        };
+        unsafe { crate::launch!(stream, &f, LaunchConfig::for_num_elems(4), &mut out, &a) }
+            .unwrap();
+        assert_eq!(stream.memcpy_dtov(&out).unwrap(), [0.5, 2.5, 2.5, 6.5]);
+    }
+
+    #[test]
+    fn test_launch_macro() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+
+        let ptx = compile_ptx_with_opts(SIN_CU, Default::default()).unwrap();
+        let module = ctx.load_module(ptx).unwrap();
+        let sin_kernel = module.load_function("sin_kernel").unwrap();
+
+        let a_host = [-1.0f32, -0.8, -0.6, -0.4, -0.2, 0.0, 0.2, 0.4, 0.6, 0.8];
+        let a_dev = stream.memcpy_stod(&a_host).unwrap();
+        let mut b_dev = stream.alloc_zeros::<f32>(a_host.len()).unwrap();
+
+        let n = a_host.len() as u32;
+        unsafe {
+            crate::launch!(
+                stream,
+                &sin_kernel,
+                LaunchConfig::for_num_elems(n),
+                &mut b_dev,
+                &a_dev,
+                &(n as usize),
+            )
+        }
+        .unwrap();
+
+        let b_host = stream.memcpy_dtov(&b_dev).unwrap();
+        for (a_i, b_i) in a_host.iter().zip(b_host.iter()) {
+            assert!((b_i - a_i.sin()).abs() <= 1e-6);
+        }
+    }
+
     #[test]
     fn test_large_launches() {
         let ctx = CudaContext::new(0).unwrap();
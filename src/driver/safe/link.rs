@@ -0,0 +1,205 @@
+use std::{
+    ffi::{c_void, CString},
+    sync::Arc,
+};
+
+use super::{CudaContext, CudaModule};
+use crate::driver::{result, sys, DriverError};
+use crate::nvrtc::{Cubin, Ptx, PtxKind};
+
+/// Size of each of the info and error log buffers of a [Linker].
+const LOG_BUFFER_SIZE: usize = 16 * 1024;
+
+/// Links multiple ptx/cubin inputs into a single [CudaModule], e.g. when kernels call device functions
+/// defined in a different `.cu` file. Create with [CudaContext::new_linker()].
+///
+/// The ptx has to be compiled with `relocatable_device_code: Some(true)`
+/// (see [crate::nvrtc::CompileOptions]) for cross references to resolve.
+///
+/// If [Linker::complete()] fails, [Linker::error_log()] describes why.
+#[derive(Debug)]
+pub struct Linker {
+    cu_link_state: sys::CUlinkState,
+    info_log: Box<[u8]>,
+    error_log: Box<[u8]>,
+    ctx: Arc<CudaContext>,
+}
+
+impl Drop for Linker {
+    fn drop(&mut self) {
+        let ctx = &self.ctx;
+        ctx.record_err(ctx.bind_to_thread());
+        ctx.record_err(unsafe { result::link::destroy(self.cu_link_state) });
+    }
+}
+
+impl CudaContext {
+    /// Creates a new [Linker] that captures the info and error logs of the JIT linker.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MODULE.html)
+    pub fn new_linker(self: &Arc<Self>) -> Result<Linker, DriverError> {
+        self.bind_to_thread()?;
+        let mut info_log = vec![0u8; LOG_BUFFER_SIZE].into_boxed_slice();
+        let mut error_log = vec![0u8; LOG_BUFFER_SIZE].into_boxed_slice();
+        let mut options = [
+            sys::CUjit_option::CU_JIT_INFO_LOG_BUFFER,
+            sys::CUjit_option::CU_JIT_INFO_LOG_BUFFER_SIZE_BYTES,
+            sys::CUjit_option::CU_JIT_ERROR_LOG_BUFFER,
+            sys::CUjit_option::CU_JIT_ERROR_LOG_BUFFER_SIZE_BYTES,
+            sys::CUjit_option::CU_JIT_LOG_VERBOSE,
+        ];
+        // integer option values are passed in place of the pointer
+        let mut values: [*mut c_void; 5] = [
+            info_log.as_mut_ptr() as *mut c_void,
+            std::ptr::null_mut::<u8>()
+                .wrapping_add(LOG_BUFFER_SIZE)
+                .cast(),
+            error_log.as_mut_ptr() as *mut c_void,
+            std::ptr::null_mut::<u8>()
+                .wrapping_add(LOG_BUFFER_SIZE)
+                .cast(),
+            std::ptr::null_mut::<u8>().wrapping_add(1).cast(),
+        ];
+        // the log buffers are heap allocated, so they don't move with the Linker
+        let cu_link_state = unsafe { result::link::create(&mut options, &mut values) }?;
+        Ok(Linker {
+            cu_link_state,
+            info_log,
+            error_log,
+            ctx: self.clone(),
+        })
+    }
+}
+
+impl Linker {
+    /// Adds ptx from [crate::nvrtc::compile_ptx()], [Ptx::from_src()] or [Ptx::from_file()].
+    pub fn add_ptx(&mut self, ptx: &Ptx) -> Result<(), DriverError> {
+        let input_type = sys::CUjitInputType::CU_JIT_INPUT_PTX;
+        self.ctx.bind_to_thread()?;
+        match &ptx.kind {
+            PtxKind::Image(image) => {
+                let image =
+                    unsafe { std::slice::from_raw_parts(image.as_ptr() as *const u8, image.len()) };
+                unsafe { result::link::add_data(self.cu_link_state, input_type, image, c"ptx") }
+            }
+            PtxKind::Src(src) => {
+                let src = CString::new(src.as_str())
+                    .map_err(|_| DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE))?;
+                unsafe {
+                    result::link::add_data(
+                        self.cu_link_state,
+                        input_type,
+                        src.as_bytes_with_nul(),
+                        c"ptx",
+                    )
+                }
+            }
+            PtxKind::File(path) => {
+                let path = path
+                    .to_str()
+                    .and_then(|p| CString::new(p).ok())
+                    .ok_or(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE))?;
+                unsafe { result::link::add_file(self.cu_link_state, input_type, &path) }
+            }
+        }
+    }
+
+    /// Adds a cubin, e.g. from [crate::nvrtc::compile_cubin()] or [Cubin::from_bytes()].
+    pub fn add_cubin(&mut self, cubin: &Cubin) -> Result<(), DriverError> {
+        self.ctx.bind_to_thread()?;
+        unsafe {
+            result::link::add_data(
+                self.cu_link_state,
+                sys::CUjitInputType::CU_JIT_INPUT_CUBIN,
+                &cubin.image,
+                c"cubin",
+            )
+        }
+    }
+
+    /// Links all the inputs added so far and loads the result as a [CudaModule].
+    ///
+    /// On failure (e.g. an undefined symbol) see [Linker::error_log()].
+    pub fn complete(&mut self) -> Result<Arc<CudaModule>, DriverError> {
+        self.ctx.bind_to_thread()?;
+        let (cubin, _size) = unsafe { result::link::complete(self.cu_link_state) }?;
+        let cu_module = unsafe { result::module::load_data(cubin) }?;
+        Ok(Arc::new(CudaModule {
+            cu_module,
+            ctx: self.ctx.clone(),
+        }))
+    }
+
+    /// Informational messages of the linker, e.g. register usage of each function.
+    pub fn info_log(&self) -> String {
+        log_to_string(&self.info_log)
+    }
+
+    /// Error messages of the linker.
+    pub fn error_log(&self) -> String {
+        log_to_string(&self.error_log)
+    }
+}
+
+fn log_to_string(log: &[u8]) -> String {
+    let end = log.iter().position(|&b| b == 0).unwrap_or(log.len());
+    String::from_utf8_lossy(&log[..end]).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver::{LaunchConfig, PushKernelArg};
+    use crate::nvrtc::{compile_ptx_with_opts, CompileOptions};
+
+    fn rdc() -> CompileOptions {
+        CompileOptions {
+            relocatable_device_code: Some(true),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_link_two_ptx() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+
+        let lib = compile_ptx_with_opts(
+            "extern \"C\" __device__ float twice(float x) { return 2.0f * x; }",
+            rdc(),
+        )
+        .unwrap();
+        let main = compile_ptx_with_opts(
+            "
+extern \"C\" __device__ float twice(float x);
+extern \"C\" __global__ void kernel(float *out) {
+    out[threadIdx.x] = twice(threadIdx.x);
+}",
+            rdc(),
+        )
+        .unwrap();
+
+        // the kernel alone does not link
+        let mut linker = ctx.new_linker().unwrap();
+        linker.add_ptx(&main).unwrap();
+        assert!(linker.complete().is_err());
+        assert!(linker.error_log().contains("twice"));
+
+        let mut linker = ctx.new_linker().unwrap();
+        linker.add_ptx(&main).unwrap();
+        linker.add_ptx(&lib).unwrap();
+        let module = linker.complete().unwrap();
+        drop(linker);
+
+        let f = module.load_function("kernel").unwrap();
+        let mut out = stream.alloc_zeros::<f32>(4).unwrap();
+        unsafe {
+            stream
+                .launch_builder(&f)
+                .arg(&mut out)
+                .launch(LaunchConfig::for_num_elems(4))
+        }
+        .unwrap();
+        assert_eq!(stream.memcpy_dtov(&out).unwrap(), [0.0, 2.0, 4.0, 6.0]);
+    }
+}
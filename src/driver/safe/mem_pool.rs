@@ -0,0 +1,180 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use super::core::SliceOwner;
+use super::{CudaContext, CudaSlice, CudaStream, DeviceRepr, ValidAsZeroBits};
+use crate::driver::{result, sys, DriverError};
+
+/// A stream ordered memory pool. Allocations from it are recycled cheaply instead of going
+/// through the driver allocator every time.
+///
+/// Create with [CudaContext::default_mem_pool()] or [CudaContext::new_mem_pool()], and allocate
+/// with [MemPool::alloc_async()]. The returned [CudaSlice] is freed back to the pool on drop.
+///
+/// Pools created with [CudaContext::new_mem_pool()] are destroyed when this is dropped. Any
+/// allocations still alive at that point remain valid, and their memory is released once they are freed.
+#[derive(Debug)]
+pub struct MemPool {
+    cu_pool: sys::CUmemoryPool,
+    ctx: Arc<CudaContext>,
+    owned: bool,
+}
+
+unsafe impl Send for MemPool {}
+unsafe impl Sync for MemPool {}
+
+impl Drop for MemPool {
+    fn drop(&mut self) {
+        if self.owned {
+            let ctx = &self.ctx;
+            ctx.record_err(ctx.bind_to_thread());
+            ctx.record_err(unsafe { result::mem_pool::destroy(self.cu_pool) });
+        }
+    }
+}
+
+impl CudaContext {
+    /// The default memory pool of this device, which is what [CudaStream::alloc()] uses.
+    ///
+    /// If the device does not support memory pools, this returns [sys::cudaError_enum::CUDA_ERROR_NOT_SUPPORTED].
+    pub fn default_mem_pool(self: &Arc<Self>) -> Result<MemPool, DriverError> {
+        if !self.has_async_alloc {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_NOT_SUPPORTED));
+        }
+        self.bind_to_thread()?;
+        let cu_pool = result::mem_pool::get_default(self.cu_device)?;
+        Ok(MemPool {
+            cu_pool,
+            ctx: self.clone(),
+            owned: false,
+        })
+    }
+
+    /// Creates a new memory pool with the given `props`.
+    ///
+    /// If the device does not support memory pools, this returns [sys::cudaError_enum::CUDA_ERROR_NOT_SUPPORTED].
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__MALLOC__ASYNC.html)
+    pub fn new_mem_pool(
+        self: &Arc<Self>,
+        props: sys::CUmemPoolProps,
+    ) -> Result<MemPool, DriverError> {
+        if !self.has_async_alloc {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_NOT_SUPPORTED));
+        }
+        self.bind_to_thread()?;
+        let cu_pool = result::mem_pool::create(&props)?;
+        Ok(MemPool {
+            cu_pool,
+            ctx: self.clone(),
+            owned: true,
+        })
+    }
+}
+
+impl MemPool {
+    /// The underlying memory pool object.
+    ///
+    /// # Safety
+    /// Do not destroy this value.
+    pub fn cu_pool(&self) -> sys::CUmemoryPool {
+        self.cu_pool
+    }
+
+    /// The context this pool belongs to.
+    pub fn context(&self) -> &Arc<CudaContext> {
+        &self.ctx
+    }
+
+    /// Allocates `len` elements of `T` from this pool, ordered on `stream`.
+    ///
+    /// If `stream` belongs to a different [CudaContext], this will fail with
+    /// [sys::cudaError_enum::CUDA_ERROR_INVALID_CONTEXT].
+    ///
+    /// # Safety
+    /// This is unsafe because the memory is unset.
+    pub unsafe fn alloc_async<T: DeviceRepr>(
+        &self,
+        len: usize,
+        stream: &Arc<CudaStream>,
+    ) -> Result<CudaSlice<T>, DriverError> {
+        if self.ctx != stream.ctx {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_CONTEXT));
+        }
+        self.ctx.bind_to_thread()?;
+        let cu_device_ptr = result::mem_pool::malloc_async(
+            stream.cu_stream,
+            len * std::mem::size_of::<T>(),
+            self.cu_pool,
+        )?;
+        let (read, write) = if self.ctx.is_event_tracking() {
+            (
+                Some(self.ctx.new_event(None)?),
+                Some(self.ctx.new_event(None)?),
+            )
+        } else {
+            (None, None)
+        };
+        Ok(CudaSlice {
+            cu_device_ptr,
+            len,
+            read,
+            write,
+            stream: stream.clone(),
+            owner: SliceOwner::Stream,
+            marker: PhantomData,
+        })
+    }
+
+    /// Same as [MemPool::alloc_async()], but all values are zero'd out.
+    pub fn alloc_zeros_async<T: DeviceRepr + ValidAsZeroBits>(
+        &self,
+        len: usize,
+        stream: &Arc<CudaStream>,
+    ) -> Result<CudaSlice<T>, DriverError> {
+        let mut dst = unsafe { self.alloc_async(len, stream) }?;
+        stream.memset_zeros(&mut dst)?;
+        Ok(dst)
+    }
+
+    /// How much memory the pool holds on to before trying to release memory back to the OS
+    /// at the next synchronization. Defaults to 0, so setting this avoids releasing and
+    /// re-acquiring memory every iteration.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__TYPES.html)
+    pub fn set_release_threshold(&self, num_bytes: u64) -> Result<(), DriverError> {
+        self.ctx.bind_to_thread()?;
+        let mut value: sys::cuuint64_t = num_bytes;
+        unsafe {
+            result::mem_pool::set_attribute(
+                self.cu_pool,
+                sys::CUmemPool_attribute::CU_MEMPOOL_ATTR_RELEASE_THRESHOLD,
+                (&mut value as *mut sys::cuuint64_t).cast(),
+            )
+        }
+    }
+
+    /// Releases unused memory back to the OS until the pool holds less than `min_bytes_to_keep`.
+    pub fn trim_to(&self, min_bytes_to_keep: usize) -> Result<(), DriverError> {
+        self.ctx.bind_to_thread()?;
+        unsafe { result::mem_pool::trim_to(self.cu_pool, min_bytes_to_keep) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_mem_pool_alloc() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.new_stream().unwrap();
+        let pool = ctx.default_mem_pool().unwrap();
+        pool.set_release_threshold(u64::MAX).unwrap();
+        for i in 0..10u32 {
+            let mut a = pool.alloc_zeros_async::<u32>(100, &stream).unwrap();
+            stream.memcpy_htod(&[i; 100], &mut a).unwrap();
+            assert_eq!(stream.memcpy_dtov(&a).unwrap(), [i; 100]);
+        }
+        pool.trim_to(0).unwrap();
+    }
+}
@@ -1,20 +1,86 @@
 //! Safe abstractions over [crate::driver::result] provided by [CudaSlice], [CudaContext], [CudaStream], and more.
 
+pub(crate) mod array;
 pub(crate) mod core;
+pub(crate) mod device;
 pub(crate) mod external_memory;
+pub(crate) mod external_semaphore;
+#[cfg(feature = "tokio")]
+pub(crate) mod future;
 pub(crate) mod graph;
+pub(crate) mod host_register;
+pub(crate) mod ipc;
 pub(crate) mod launch;
+pub(crate) mod link;
+pub(crate) mod mem_pool;
+pub(crate) mod pitched;
 pub(crate) mod profile;
+pub(crate) mod stream_pool;
+pub(crate) mod surface;
+pub(crate) mod texture;
 pub(crate) mod unified_memory;
 
+pub use self::array::{ArrayDescriptor, CudaArray};
 pub use self::core::{
-    CudaContext, CudaEvent, CudaFunction, CudaModule, CudaSlice, CudaStream, CudaView, CudaViewMut,
-    DevicePtr, DevicePtrMut, DeviceRepr, DeviceSlice, HostSlice, PinnedHostSlice, SyncOnDrop,
-    ValidAsZeroBits,
+    ContextFlags, CudaContext, CudaEvent, CudaFunction, CudaModule, CudaSlice, CudaStream,
+    CudaView, CudaViewMut, DeviceAttribute, DevicePtr, DevicePtrMut, DeviceRepr, DeviceSlice,
+    EventWaitFlags, HostSlice, LoadCachedError, PinnedHostSlice, SyncOnDrop, ValidAsZeroBits,
+    WaitFlags, ALLOC_GUARD_SENTINEL,
 };
-pub use self::external_memory::{ExternalMemory, MappedBuffer};
-pub use self::graph::CudaGraph;
+pub use self::device::{decode_version, device_count, device_name, driver_version};
+pub use self::external_memory::{ExternalMemory, MappedBuffer, MappedBufferMut};
+pub use self::external_semaphore::ExternalSemaphore;
+#[cfg(feature = "tokio")]
+pub use self::future::SynchronizeFuture;
+pub use self::graph::{CaptureMode, CudaGraph, CudaGraphExec, GraphNode};
+pub use self::host_register::HostRegistration;
+pub use self::ipc::CudaIpcMemHandle;
 pub use self::launch::{LaunchArgs, LaunchConfig, PushKernelArg};
+pub use self::link::Linker;
+pub use self::mem_pool::MemPool;
+pub use self::pitched::PitchedSlice;
 pub use self::profile::{profiler_start, profiler_stop, Profiler};
+pub use self::stream_pool::StreamPool;
+pub use self::surface::SurfaceObject;
+pub use self::texture::{AddressMode, FilterMode, TexelFormat, TextureDesc, TextureObject};
 pub use self::unified_memory::UnifiedSlice;
 pub use crate::driver::result::DriverError;
+
+/// Derives [DeviceRepr] for a `#[repr(C)]` struct that is [Copy] and whose fields are all [DeviceRepr],
+/// so it can be passed to kernels by value. Requires the `derive` feature.
+///
+/// ```no_run
+/// use cudarc::driver::DeviceRepr;
+///
+/// #[derive(Clone, Copy, DeviceRepr)]
+/// #[repr(C)]
+/// struct Params {
+///     scale: f32,
+///     offsets: [u32; 4],
+///     len: usize,
+/// }
+/// ```
+///
+/// Types that can't be copied to the device are rejected at compile time:
+///
+/// ```compile_fail
+/// use cudarc::driver::DeviceRepr;
+///
+/// #[derive(Clone, DeviceRepr)]
+/// #[repr(C)]
+/// struct Params {
+///     name: String,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// use cudarc::driver::DeviceRepr;
+///
+/// // not #[repr(C)]
+/// #[derive(Clone, Copy, DeviceRepr)]
+/// struct Params {
+///     scale: f32,
+/// }
+/// ```
+#[cfg(feature = "derive")]
+pub use cudarc_derive::DeviceRepr;
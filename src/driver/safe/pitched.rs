@@ -0,0 +1,224 @@
+use std::sync::Arc;
+
+use super::{
+    CudaSlice, CudaStream, DevicePtr, DevicePtrMut, DeviceRepr, DeviceSlice, HostSlice, SyncOnDrop,
+    ValidAsZeroBits,
+};
+use crate::driver::{result, sys, DriverError};
+
+/// A 2D allocation of `height` rows of `width` elements, where each row starts
+/// `pitch` bytes after the previous one. Create with [CudaStream::alloc_pitched()].
+///
+/// The padding at the end of each row keeps every row aligned, so that accesses to
+/// a row are coalesced. Kernels must index with the pitch, as element `(x, y)` is
+/// at byte offset `y * pitch + x * size_of::<T>()`.
+///
+/// As a [DevicePtr] this covers the padding too, so it has [PitchedSlice::height()] times
+/// `pitch / size_of::<T>()` elements.
+#[derive(Debug)]
+pub struct PitchedSlice<T> {
+    slice: CudaSlice<T>,
+    width: usize,
+    height: usize,
+    pitch: usize,
+}
+
+impl<T> PitchedSlice<T> {
+    /// The number of elements in each row, excluding padding.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The number of rows.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The number of bytes between the start of consecutive rows.
+    pub fn pitch(&self) -> usize {
+        self.pitch
+    }
+
+    /// The underlying allocation, including row padding.
+    pub fn as_slice(&self) -> &CudaSlice<T> {
+        &self.slice
+    }
+}
+
+impl<T> DeviceSlice<T> for PitchedSlice<T> {
+    fn len(&self) -> usize {
+        self.slice.len()
+    }
+    fn stream(&self) -> &Arc<CudaStream> {
+        self.slice.stream()
+    }
+}
+
+impl<T> DevicePtr<T> for PitchedSlice<T> {
+    fn device_ptr<'a>(&'a self, stream: &'a CudaStream) -> (sys::CUdeviceptr, SyncOnDrop<'a>) {
+        self.slice.device_ptr(stream)
+    }
+}
+
+impl<T> DevicePtrMut<T> for PitchedSlice<T> {
+    fn device_ptr_mut<'a>(
+        &'a mut self,
+        stream: &'a CudaStream,
+    ) -> (sys::CUdeviceptr, SyncOnDrop<'a>) {
+        self.slice.device_ptr_mut(stream)
+    }
+}
+
+impl CudaStream {
+    /// Allocates a [PitchedSlice] with `height` rows of `width` elements.
+    ///
+    /// If the pitch chosen by the driver is not a multiple of `size_of::<T>()`,
+    /// this returns [sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE].
+    ///
+    /// # Safety
+    /// This is unsafe because the memory is unset.
+    pub unsafe fn alloc_pitched<T: DeviceRepr>(
+        self: &Arc<Self>,
+        width: usize,
+        height: usize,
+    ) -> Result<PitchedSlice<T>, DriverError> {
+        let size = std::mem::size_of::<T>();
+        let element_size = match size {
+            0..=4 => 4,
+            5..=8 => 8,
+            _ => 16,
+        };
+        self.bind_to_thread()?;
+        let (cu_device_ptr, pitch) = result::malloc_pitch(width * size, height, element_size)?;
+        if size == 0 || pitch % size != 0 {
+            // frees the allocation
+            drop(self.upgrade_device_ptr::<T>(cu_device_ptr, 0));
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE));
+        }
+        let slice = self.upgrade_device_ptr(cu_device_ptr, (pitch / size) * height);
+        Ok(PitchedSlice {
+            slice,
+            width,
+            height,
+            pitch,
+        })
+    }
+
+    /// Allocates a [PitchedSlice] with `height` rows of `width` elements. All values
+    /// (including row padding) are zero'd out.
+    pub fn alloc_pitched_zeros<T: DeviceRepr + ValidAsZeroBits>(
+        self: &Arc<Self>,
+        width: usize,
+        height: usize,
+    ) -> Result<PitchedSlice<T>, DriverError> {
+        let mut dst = unsafe { self.alloc_pitched(width, height) }?;
+        self.memset_zeros(&mut dst)?;
+        Ok(dst)
+    }
+
+    /// Copies the `dst.height()` rows of `dst.width()` elements from `src` into `dst`.
+    /// Row `y` of `src` starts at `src[y * src_pitch]`, so `src_pitch` is the number of
+    /// **elements** between rows in `src`. Use `src_pitch = dst.width()` for tightly packed rows.
+    ///
+    /// Panics if `src_pitch < dst.width()` or if `src` is too short.
+    pub fn memcpy_2d_htod<T: DeviceRepr, Src: HostSlice<T> + ?Sized>(
+        self: &Arc<Self>,
+        src: &Src,
+        src_pitch: usize,
+        dst: &mut PitchedSlice<T>,
+    ) -> Result<(), DriverError> {
+        self.bind_to_thread()?;
+        assert!(src_pitch >= dst.width);
+        assert!(dst.height == 0 || src.len() >= (dst.height - 1) * src_pitch + dst.width);
+        let size = std::mem::size_of::<T>();
+        let (width, height, pitch) = (dst.width, dst.height, dst.pitch);
+        let (src, _record_src) = unsafe { src.stream_synced_slice(self) };
+        let (dst, _record_dst) = dst.device_ptr_mut(self);
+        let params = sys::CUDA_MEMCPY2D {
+            srcXInBytes: 0,
+            srcY: 0,
+            srcMemoryType: sys::CUmemorytype::CU_MEMORYTYPE_HOST,
+            srcHost: src.as_ptr() as *const _,
+            srcDevice: 0,
+            srcArray: std::ptr::null_mut(),
+            srcPitch: src_pitch * size,
+            dstXInBytes: 0,
+            dstY: 0,
+            dstMemoryType: sys::CUmemorytype::CU_MEMORYTYPE_DEVICE,
+            dstHost: std::ptr::null_mut(),
+            dstDevice: dst,
+            dstArray: std::ptr::null_mut(),
+            dstPitch: pitch,
+            WidthInBytes: width * size,
+            Height: height,
+        };
+        unsafe { result::memcpy_2d_async(&params, self.cu_stream) }
+    }
+
+    /// Copies the `src.height()` rows of `src.width()` elements from `src` into `dst`.
+    /// Row `y` is written to `dst[y * dst_pitch]`, so `dst_pitch` is the number of
+    /// **elements** between rows in `dst`. Use `dst_pitch = src.width()` for tightly packed rows.
+    ///
+    /// Panics if `dst_pitch < src.width()` or if `dst` is too short.
+    pub fn memcpy_2d_dtoh<T: DeviceRepr, Dst: HostSlice<T> + ?Sized>(
+        self: &Arc<Self>,
+        src: &PitchedSlice<T>,
+        dst: &mut Dst,
+        dst_pitch: usize,
+    ) -> Result<(), DriverError> {
+        self.bind_to_thread()?;
+        assert!(dst_pitch >= src.width);
+        assert!(src.height == 0 || dst.len() >= (src.height - 1) * dst_pitch + src.width);
+        let size = std::mem::size_of::<T>();
+        let (src_ptr, _record_src) = src.device_ptr(self);
+        let (dst, _record_dst) = unsafe { dst.stream_synced_mut_slice(self) };
+        let params = sys::CUDA_MEMCPY2D {
+            srcXInBytes: 0,
+            srcY: 0,
+            srcMemoryType: sys::CUmemorytype::CU_MEMORYTYPE_DEVICE,
+            srcHost: std::ptr::null(),
+            srcDevice: src_ptr,
+            srcArray: std::ptr::null_mut(),
+            srcPitch: src.pitch,
+            dstXInBytes: 0,
+            dstY: 0,
+            dstMemoryType: sys::CUmemorytype::CU_MEMORYTYPE_HOST,
+            dstHost: dst.as_mut_ptr() as *mut _,
+            dstDevice: 0,
+            dstArray: std::ptr::null_mut(),
+            dstPitch: dst_pitch * size,
+            WidthInBytes: src.width * size,
+            Height: src.height,
+        };
+        unsafe { result::memcpy_2d_async(&params, self.cu_stream) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::driver::CudaContext;
+
+    #[test]
+    fn test_pitched_round_trip() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+
+        let (width, height) = (3, 4);
+        let src: Vec<u16> = (0..(width * height) as u16).collect();
+        let mut dev = stream.alloc_pitched_zeros::<u16>(width, height).unwrap();
+        assert_eq!(dev.width(), width);
+        assert_eq!(dev.height(), height);
+        assert!(dev.pitch() >= width * std::mem::size_of::<u16>());
+
+        stream.memcpy_2d_htod(&src, width, &mut dev).unwrap();
+
+        // read back into a host buffer with a different pitch
+        let mut dst = vec![u16::MAX; 5 * height];
+        stream.memcpy_2d_dtoh(&dev, &mut dst, 5).unwrap();
+        stream.synchronize().unwrap();
+        for y in 0..height {
+            assert_eq!(dst[y * 5..y * 5 + width], src[y * width..(y + 1) * width]);
+            assert_eq!(dst[y * 5 + width..(y + 1) * 5], [u16::MAX; 2]);
+        }
+    }
+}
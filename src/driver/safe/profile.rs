@@ -1,3 +1,4 @@
+use super::CudaContext;
 use crate::driver::{result, sys};
 
 /// Calls [profiler_start()] in [Profiler::new()], and [profiler_stop()] in [Drop].
@@ -63,3 +64,46 @@ pub fn profiler_start() -> Result<(), result::DriverError> {
 pub fn profiler_stop() -> Result<(), result::DriverError> {
     unsafe { sys::cuProfilerStop() }.result()
 }
+
+impl CudaContext {
+    /// Binds this context to the calling thread and enables profile collection for it, see [profiler_start()].
+    ///
+    /// Run the program under `nsys profile --capture-range=cudaProfilerApi` (or `-c cudaProfilerApi`) so that
+    /// nsys only collects between this and [CudaContext::profiler_stop()], e.g. to skip warmup iterations.
+    /// Without `--capture-range`, nsys records the whole program and these calls only add markers.
+    ///
+    /// By default nsys ends the capture at the first stop, use `--capture-range-end=repeat` to capture every
+    /// start/stop pair.
+    pub fn profiler_start(&self) -> Result<(), result::DriverError> {
+        self.bind_to_thread()?;
+        profiler_start()
+    }
+
+    /// Binds this context to the calling thread and disables profile collection for it, see [profiler_stop()].
+    pub fn profiler_stop(&self) -> Result<(), result::DriverError> {
+        self.bind_to_thread()?;
+        profiler_stop()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiler_start_stop() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let _warmup = stream.alloc_zeros::<f32>(1024).unwrap();
+
+        ctx.profiler_start().unwrap();
+        // already started has no effect
+        ctx.profiler_start().unwrap();
+        let a = stream.memcpy_stod(&[1.0f32; 1024]).unwrap();
+        stream.synchronize().unwrap();
+        ctx.profiler_stop().unwrap();
+        ctx.profiler_stop().unwrap();
+
+        assert_eq!(stream.memcpy_dtov(&a).unwrap(), [1.0; 1024]);
+    }
+}
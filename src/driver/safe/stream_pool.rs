@@ -0,0 +1,135 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use super::{CudaContext, CudaStream};
+use crate::driver::{sys, DriverError};
+
+/// A fixed set of [CudaStream]s handed out round-robin, e.g. to spread independent copies
+/// and kernels across streams so they can overlap.
+///
+/// Create with [CudaContext::new_stream_pool()] or [CudaContext::new_stream_pool_with_priorities()].
+///
+/// This does no scheduling or dependency tracking of its own. Use [CudaStream::join()] or
+/// [CudaStream::wait()] to order work between streams, or [StreamPool::synchronize_all()].
+#[derive(Debug)]
+pub struct StreamPool {
+    streams: Vec<Arc<CudaStream>>,
+    next: AtomicUsize,
+}
+
+impl CudaContext {
+    /// Creates a [StreamPool] of `num_streams` new streams.
+    ///
+    /// Returns [sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE] if `num_streams` is 0.
+    pub fn new_stream_pool(
+        self: &Arc<Self>,
+        num_streams: usize,
+    ) -> Result<StreamPool, DriverError> {
+        if num_streams == 0 {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE));
+        }
+        let streams = (0..num_streams)
+            .map(|_| self.new_stream())
+            .collect::<Result<_, _>>()?;
+        Ok(StreamPool {
+            streams,
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    /// Creates a [StreamPool] with one new stream per entry of `priorities`, see
+    /// [CudaContext::new_stream_with_priority()].
+    ///
+    /// Returns [sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE] if `priorities` is empty.
+    pub fn new_stream_pool_with_priorities(
+        self: &Arc<Self>,
+        priorities: &[i32],
+    ) -> Result<StreamPool, DriverError> {
+        if priorities.is_empty() {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE));
+        }
+        let streams = priorities
+            .iter()
+            .map(|&priority| self.new_stream_with_priority(priority))
+            .collect::<Result<_, _>>()?;
+        Ok(StreamPool {
+            streams,
+            next: AtomicUsize::new(0),
+        })
+    }
+}
+
+impl StreamPool {
+    /// Returns the next stream, cycling through all of them in order.
+    pub fn next(&self) -> &Arc<CudaStream> {
+        let i = self.next.fetch_add(1, Ordering::Relaxed);
+        &self.streams[i % self.streams.len()]
+    }
+
+    /// All the streams in the pool.
+    pub fn streams(&self) -> &[Arc<CudaStream>] {
+        &self.streams
+    }
+
+    /// Number of streams in the pool. This is never 0.
+    pub fn len(&self) -> usize {
+        self.streams.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.streams.is_empty()
+    }
+
+    /// Blocks until all work on all streams in the pool has completed.
+    pub fn synchronize_all(&self) -> Result<(), DriverError> {
+        for stream in self.streams.iter() {
+            stream.synchronize()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_pool_round_robin() {
+        let ctx = CudaContext::new(0).unwrap();
+        assert!(ctx.new_stream_pool(0).is_err());
+
+        let pool = ctx.new_stream_pool(4).unwrap();
+        assert_eq!(pool.len(), 4);
+        let first: Vec<_> = (0..4).map(|_| pool.next().cu_stream()).collect();
+        for i in 0..4 {
+            assert_eq!(first[i], pool.streams()[i].cu_stream());
+            for j in 0..i {
+                assert_ne!(first[i], first[j]);
+            }
+        }
+        assert_eq!(pool.next().cu_stream(), first[0]);
+
+        let slices: Vec<_> = (0..8u32)
+            .map(|i| pool.next().memcpy_stod(&[i; 256]).unwrap())
+            .collect();
+        pool.synchronize_all().unwrap();
+        for (i, slice) in slices.iter().enumerate() {
+            let host = slice.stream().memcpy_dtov(slice).unwrap();
+            assert_eq!(host, [i as u32; 256]);
+        }
+    }
+
+    #[test]
+    fn test_stream_pool_with_priorities() {
+        let ctx = CudaContext::new(0).unwrap();
+        assert!(ctx.new_stream_pool_with_priorities(&[]).is_err());
+        let range = ctx.stream_priority_range().unwrap();
+        let pool = ctx
+            .new_stream_pool_with_priorities(&[range.start, range.end - 1])
+            .unwrap();
+        assert_eq!(pool.streams()[0].priority().unwrap(), range.start);
+        assert_eq!(pool.streams()[1].priority().unwrap(), range.end - 1);
+    }
+}
@@ -0,0 +1,140 @@
+use std::sync::Arc;
+
+use super::{CudaArray, CudaContext, LaunchArgs, PushKernelArg};
+use crate::driver::{result, sys, DriverError};
+
+/// A surface object (`cudaSurfaceObject_t` in kernels) that reads and writes a [CudaArray],
+/// e.g. with `surf2Dwrite()` to generate an image.
+///
+/// Create with [CudaContext::new_surface()], and pass to kernels with [LaunchArgs::arg()].
+/// The surface mutably borrows the array, since kernels can write to it.
+///
+/// The surface object is destroyed on drop.
+#[derive(Debug)]
+pub struct SurfaceObject<'a> {
+    cu_surf_object: sys::CUsurfObject,
+    array: &'a mut CudaArray,
+    ctx: Arc<CudaContext>,
+}
+
+impl Drop for SurfaceObject<'_> {
+    fn drop(&mut self) {
+        let ctx = &self.ctx;
+        ctx.record_err(ctx.bind_to_thread());
+        ctx.record_err(unsafe { result::surface::destroy(self.cu_surf_object) });
+    }
+}
+
+impl CudaContext {
+    /// Creates a [SurfaceObject] over `array`.
+    ///
+    /// Returns [sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE] if `array` was not allocated
+    /// with the [sys::CUDA_ARRAY3D_SURFACE_LDST] flag.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__SURFOBJECT.html)
+    pub fn new_surface<'a>(
+        self: &Arc<Self>,
+        array: &'a mut CudaArray,
+    ) -> Result<SurfaceObject<'a>, DriverError> {
+        if array.desc.flags & sys::CUDA_ARRAY3D_SURFACE_LDST == 0 {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE));
+        }
+        let res_desc = sys::CUDA_RESOURCE_DESC {
+            resType: sys::CUresourcetype::CU_RESOURCE_TYPE_ARRAY,
+            res: sys::CUDA_RESOURCE_DESC_st__bindgen_ty_1 {
+                array: sys::CUDA_RESOURCE_DESC_st__bindgen_ty_1__bindgen_ty_1 {
+                    hArray: array.cu_array,
+                },
+            },
+            flags: 0,
+        };
+        self.bind_to_thread()?;
+        let cu_surf_object = unsafe { result::surface::create(&res_desc) }?;
+        Ok(SurfaceObject {
+            cu_surf_object,
+            array,
+            ctx: self.clone(),
+        })
+    }
+}
+
+impl SurfaceObject<'_> {
+    /// The underlying [sys::CUsurfObject].
+    pub fn cu_surf_object(&self) -> sys::CUsurfObject {
+        self.cu_surf_object
+    }
+}
+
+unsafe impl<'a, 'b: 'a, 'c: 'b> PushKernelArg<&'b SurfaceObject<'c>> for LaunchArgs<'a> {
+    #[inline(always)]
+    fn arg(&mut self, arg: &'b SurfaceObject<'c>) -> &mut Self {
+        self.waits.push(&arg.array.event);
+        self.records.push(&arg.array.event);
+        self.args
+            .push((&arg.cu_surf_object) as *const sys::CUsurfObject as _);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver::{ArrayDescriptor, LaunchConfig};
+    use crate::nvrtc::compile_ptx;
+
+    const SURF_CU: &str = "
+extern \"C\" __global__ void gradient(cudaSurfaceObject_t surf, int width, int height) {
+    int x = blockIdx.x * blockDim.x + threadIdx.x;
+    int y = blockIdx.y * blockDim.y + threadIdx.y;
+    if (x < width && y < height) {
+        // the x coordinate of surface functions is in bytes
+        surf2Dwrite<unsigned int>(y * width + x, surf, x * sizeof(unsigned int), y);
+    }
+}
+";
+
+    #[test]
+    fn test_surface_write() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let module = ctx.load_module(compile_ptx(SURF_CU).unwrap()).unwrap();
+        let f = module.load_function("gradient").unwrap();
+
+        let (width, height) = (37i32, 11i32);
+        let mut desc = ArrayDescriptor {
+            width: width as usize,
+            height: height as usize,
+            depth: 0,
+            format: sys::CUarray_format::CU_AD_FORMAT_UNSIGNED_INT32,
+            num_channels: 1,
+            flags: 0,
+        };
+        let mut array = ctx.alloc_array(desc).unwrap();
+        assert!(ctx.new_surface(&mut array).is_err());
+
+        desc.flags = sys::CUDA_ARRAY3D_SURFACE_LDST;
+        let mut array = ctx.alloc_array(desc).unwrap();
+        {
+            let surf = ctx.new_surface(&mut array).unwrap();
+            let cfg = LaunchConfig {
+                grid_dim: ((width as u32).div_ceil(16), (height as u32).div_ceil(16), 1),
+                block_dim: (16, 16, 1),
+                shared_mem_bytes: 0,
+            };
+            unsafe {
+                stream
+                    .launch_builder(&f)
+                    .arg(&surf)
+                    .arg(&width)
+                    .arg(&height)
+                    .launch(cfg)
+            }
+            .unwrap();
+        }
+
+        let mut host = vec![0u32; desc.len()];
+        stream.memcpy_atoh(&array, &mut host).unwrap();
+        stream.synchronize().unwrap();
+        assert_eq!(host, (0..desc.len() as u32).collect::<Vec<_>>());
+    }
+}
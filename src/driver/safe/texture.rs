@@ -0,0 +1,419 @@
+use std::sync::Arc;
+
+use super::{
+    CudaArray, CudaContext, CudaSlice, DeviceRepr, LaunchArgs, PitchedSlice, PushKernelArg,
+};
+use crate::driver::{result, sys, DriverError};
+
+/// How texture coordinates outside of the texture are handled.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AddressMode {
+    /// Coordinates are clamped to the edge of the texture.
+    #[default]
+    Clamp,
+    /// Coordinates wrap around, only supported with normalized coordinates.
+    Wrap,
+    /// Coordinates are mirrored at the edges, only supported with normalized coordinates.
+    Mirror,
+    /// Coordinates outside of the texture read zero.
+    Border,
+}
+
+impl From<AddressMode> for sys::CUaddress_mode {
+    fn from(mode: AddressMode) -> Self {
+        match mode {
+            AddressMode::Clamp => sys::CUaddress_mode::CU_TR_ADDRESS_MODE_CLAMP,
+            AddressMode::Wrap => sys::CUaddress_mode::CU_TR_ADDRESS_MODE_WRAP,
+            AddressMode::Mirror => sys::CUaddress_mode::CU_TR_ADDRESS_MODE_MIRROR,
+            AddressMode::Border => sys::CUaddress_mode::CU_TR_ADDRESS_MODE_BORDER,
+        }
+    }
+}
+
+/// How a texture is sampled between texels.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FilterMode {
+    /// Returns the nearest texel.
+    #[default]
+    Point,
+    /// Linearly interpolates the nearest texels. Only supported when reading floats.
+    Linear,
+}
+
+impl From<FilterMode> for sys::CUfilter_mode {
+    fn from(mode: FilterMode) -> Self {
+        match mode {
+            FilterMode::Point => sys::CUfilter_mode::CU_TR_FILTER_MODE_POINT,
+            FilterMode::Linear => sys::CUfilter_mode::CU_TR_FILTER_MODE_LINEAR,
+        }
+    }
+}
+
+/// How a [TextureObject] is sampled, see [CudaContext::new_texture_2d()].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextureDesc {
+    /// The address mode of the `x` and `y` coordinates.
+    pub address_mode: [AddressMode; 2],
+    pub filter_mode: FilterMode,
+    /// Whether coordinates are in `[0, 1)` instead of `[0, width)`/`[0, height)`.
+    pub normalized_coords: bool,
+    /// Whether 8 and 16 bit integer texels are read as floats in `[0, 1]` (or `[-1, 1]` for signed
+    /// types) instead of as integers. Required for [FilterMode::Linear] with integer texels.
+    pub normalized_float: bool,
+}
+
+impl From<TextureDesc> for sys::CUDA_TEXTURE_DESC {
+    fn from(desc: TextureDesc) -> Self {
+        let [x, y] = desc.address_mode;
+        let mut flags = 0;
+        if desc.normalized_coords {
+            flags |= sys::CU_TRSF_NORMALIZED_COORDINATES;
+        }
+        if !desc.normalized_float {
+            flags |= sys::CU_TRSF_READ_AS_INTEGER;
+        }
+        sys::CUDA_TEXTURE_DESC {
+            addressMode: [x.into(), y.into(), x.into()],
+            filterMode: desc.filter_mode.into(),
+            flags,
+            maxAnisotropy: 0,
+            mipmapFilterMode: sys::CUfilter_mode::CU_TR_FILTER_MODE_POINT,
+            mipmapLevelBias: 0.0,
+            minMipmapLevelClamp: 0.0,
+            maxMipmapLevelClamp: 0.0,
+            borderColor: [0.0; 4],
+            reserved: [0; 12],
+        }
+    }
+}
+
+/// Element types that textures can be created over.
+///
+/// # Safety
+/// [TexelFormat::FORMAT] and [TexelFormat::NUM_CHANNELS] must describe the layout of `Self`.
+pub unsafe trait TexelFormat: DeviceRepr {
+    const FORMAT: sys::CUarray_format;
+    /// Either 1, 2 or 4.
+    const NUM_CHANNELS: u32;
+}
+
+macro_rules! texel_format {
+    ($T:ty, $Format:ident) => {
+        unsafe impl TexelFormat for $T {
+            const FORMAT: sys::CUarray_format = sys::CUarray_format::$Format;
+            const NUM_CHANNELS: u32 = 1;
+        }
+        unsafe impl TexelFormat for [$T; 2] {
+            const FORMAT: sys::CUarray_format = sys::CUarray_format::$Format;
+            const NUM_CHANNELS: u32 = 2;
+        }
+        unsafe impl TexelFormat for [$T; 4] {
+            const FORMAT: sys::CUarray_format = sys::CUarray_format::$Format;
+            const NUM_CHANNELS: u32 = 4;
+        }
+    };
+}
+
+texel_format!(u8, CU_AD_FORMAT_UNSIGNED_INT8);
+texel_format!(u16, CU_AD_FORMAT_UNSIGNED_INT16);
+texel_format!(u32, CU_AD_FORMAT_UNSIGNED_INT32);
+texel_format!(i8, CU_AD_FORMAT_SIGNED_INT8);
+texel_format!(i16, CU_AD_FORMAT_SIGNED_INT16);
+texel_format!(i32, CU_AD_FORMAT_SIGNED_INT32);
+texel_format!(f32, CU_AD_FORMAT_FLOAT);
+#[cfg(feature = "f16")]
+texel_format!(half::f16, CU_AD_FORMAT_HALF);
+
+/// A texture object (`cudaTextureObject_t` in kernels) that reads from a [CudaSlice], a [PitchedSlice]
+/// or a [CudaArray] through the texture cache, with hardware filtering and address modes.
+///
+/// Create with [CudaContext::new_texture()], [CudaContext::new_texture_2d()] or
+/// [CudaContext::new_texture_from_array()], and pass to kernels with [LaunchArgs::arg()]. The texture borrows the memory it reads from, so the memory
+/// can't be written to while the texture exists.
+///
+/// The texture object is destroyed on drop.
+#[derive(Debug)]
+pub struct TextureObject<'a, T> {
+    cu_tex_object: sys::CUtexObject,
+    src: TextureSource<'a, T>,
+    ctx: Arc<CudaContext>,
+}
+
+#[derive(Debug)]
+enum TextureSource<'a, T> {
+    Slice(&'a CudaSlice<T>),
+    Array(&'a CudaArray),
+}
+
+impl<T> Drop for TextureObject<'_, T> {
+    fn drop(&mut self) {
+        let ctx = &self.ctx;
+        ctx.record_err(ctx.bind_to_thread());
+        ctx.record_err(unsafe { result::texture::destroy(self.cu_tex_object) });
+    }
+}
+
+impl CudaContext {
+    /// Creates a 1D [TextureObject] over all of `src`, which kernels read with `tex1Dfetch()`.
+    ///
+    /// Textures over linear memory always use integer indices, so `desc` only controls
+    /// [TextureDesc::normalized_float], the other fields are ignored.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__TEXOBJECT.html)
+    pub fn new_texture<'a, T: TexelFormat>(
+        self: &Arc<Self>,
+        src: &'a CudaSlice<T>,
+        desc: TextureDesc,
+    ) -> Result<TextureObject<'a, T>, DriverError> {
+        let res_desc = sys::CUDA_RESOURCE_DESC {
+            resType: sys::CUresourcetype::CU_RESOURCE_TYPE_LINEAR,
+            res: sys::CUDA_RESOURCE_DESC_st__bindgen_ty_1 {
+                linear: sys::CUDA_RESOURCE_DESC_st__bindgen_ty_1__bindgen_ty_3 {
+                    devPtr: src.cu_device_ptr,
+                    format: T::FORMAT,
+                    numChannels: T::NUM_CHANNELS,
+                    sizeInBytes: src.num_bytes(),
+                },
+            },
+            flags: 0,
+        };
+        self.create_texture(TextureSource::Slice(src), &res_desc, desc)
+    }
+
+    /// Creates a 2D [TextureObject] over `src`, which kernels read with `tex2D()`.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__TEXOBJECT.html)
+    pub fn new_texture_2d<'a, T: TexelFormat>(
+        self: &Arc<Self>,
+        src: &'a PitchedSlice<T>,
+        desc: TextureDesc,
+    ) -> Result<TextureObject<'a, T>, DriverError> {
+        let res_desc = sys::CUDA_RESOURCE_DESC {
+            resType: sys::CUresourcetype::CU_RESOURCE_TYPE_PITCH2D,
+            res: sys::CUDA_RESOURCE_DESC_st__bindgen_ty_1 {
+                pitch2D: sys::CUDA_RESOURCE_DESC_st__bindgen_ty_1__bindgen_ty_4 {
+                    devPtr: src.as_slice().cu_device_ptr,
+                    format: T::FORMAT,
+                    numChannels: T::NUM_CHANNELS,
+                    width: src.width(),
+                    height: src.height(),
+                    pitchInBytes: src.pitch(),
+                },
+            },
+            flags: 0,
+        };
+        self.create_texture(TextureSource::Slice(src.as_slice()), &res_desc, desc)
+    }
+
+    /// Creates a [TextureObject] over a 1D, 2D or 3D [CudaArray], which kernels read with `tex1D()`,
+    /// `tex2D()` or `tex3D()`. The `z` coordinate of 3D textures uses the address mode of `x`.
+    ///
+    /// Returns [sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE] if the format of `src` does not match `T`.
+    ///
+    /// See [cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__TEXOBJECT.html)
+    pub fn new_texture_from_array<'a, T: TexelFormat>(
+        self: &Arc<Self>,
+        src: &'a CudaArray,
+        desc: TextureDesc,
+    ) -> Result<TextureObject<'a, T>, DriverError> {
+        let array_desc = src.descriptor();
+        if array_desc.format != T::FORMAT || array_desc.num_channels != T::NUM_CHANNELS {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_INVALID_VALUE));
+        }
+        let res_desc = sys::CUDA_RESOURCE_DESC {
+            resType: sys::CUresourcetype::CU_RESOURCE_TYPE_ARRAY,
+            res: sys::CUDA_RESOURCE_DESC_st__bindgen_ty_1 {
+                array: sys::CUDA_RESOURCE_DESC_st__bindgen_ty_1__bindgen_ty_1 {
+                    hArray: src.cu_array,
+                },
+            },
+            flags: 0,
+        };
+        self.create_texture(TextureSource::Array(src), &res_desc, desc)
+    }
+
+    fn create_texture<'a, T>(
+        self: &Arc<Self>,
+        src: TextureSource<'a, T>,
+        res_desc: &sys::CUDA_RESOURCE_DESC,
+        desc: TextureDesc,
+    ) -> Result<TextureObject<'a, T>, DriverError> {
+        self.bind_to_thread()?;
+        let cu_tex_object = unsafe { result::texture::create(res_desc, &desc.into()) }?;
+        Ok(TextureObject {
+            cu_tex_object,
+            src,
+            ctx: self.clone(),
+        })
+    }
+}
+
+impl<T> TextureObject<'_, T> {
+    /// The underlying [sys::CUtexObject].
+    pub fn cu_tex_object(&self) -> sys::CUtexObject {
+        self.cu_tex_object
+    }
+}
+
+unsafe impl<'a, 'b: 'a, 'c: 'b, T> PushKernelArg<&'b TextureObject<'c, T>> for LaunchArgs<'a> {
+    #[inline(always)]
+    fn arg(&mut self, arg: &'b TextureObject<'c, T>) -> &mut Self {
+        match arg.src {
+            TextureSource::Slice(slice) => {
+                if self.stream.context().is_in_multi_stream_mode() {
+                    if let Some(write) = slice.write.as_ref() {
+                        self.waits.push(write);
+                    }
+                    if let Some(read) = slice.read.as_ref() {
+                        self.records.push(read);
+                    }
+                }
+            }
+            TextureSource::Array(array) => {
+                self.waits.push(&array.event);
+                self.records.push(&array.event);
+            }
+        }
+        self.args
+            .push((&arg.cu_tex_object) as *const sys::CUtexObject as _);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver::{ArrayDescriptor, LaunchConfig};
+    use crate::nvrtc::compile_ptx;
+
+    const TEX_CU: &str = "
+extern \"C\" __global__ void fetch_1d(cudaTextureObject_t tex, float *out, int n) {
+    int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i < n) {
+        out[i] = tex1Dfetch<float>(tex, i);
+    }
+}
+
+extern \"C\" __global__ void sample_3d(cudaTextureObject_t tex, float *out, float x, float y, float z) {
+    out[0] = tex3D<float>(tex, x, y, z);
+}
+
+extern \"C\" __global__ void sample_2d(cudaTextureObject_t tex, float *out, float x, float y) {
+    out[0] = tex2D<float>(tex, x, y);
+}
+";
+
+    #[test]
+    fn test_texture_1d() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let module = ctx.load_module(compile_ptx(TEX_CU).unwrap()).unwrap();
+        let f = module.load_function("fetch_1d").unwrap();
+
+        let host: Vec<f32> = (0..100).map(|i| i as f32).collect();
+        let src = stream.memcpy_stod(&host).unwrap();
+        let tex = ctx.new_texture(&src, Default::default()).unwrap();
+        let mut out = stream.alloc_zeros::<f32>(100).unwrap();
+        let n = 100i32;
+        unsafe {
+            stream
+                .launch_builder(&f)
+                .arg(&tex)
+                .arg(&mut out)
+                .arg(&n)
+                .launch(LaunchConfig::for_num_elems(100))
+        }
+        .unwrap();
+        assert_eq!(stream.memcpy_dtov(&out).unwrap(), host);
+    }
+
+    #[test]
+    fn test_texture_2d_linear_filter() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let module = ctx.load_module(compile_ptx(TEX_CU).unwrap()).unwrap();
+        let f = module.load_function("sample_2d").unwrap();
+
+        let (w, h) = (4, 2);
+        let host = [0.0f32, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+        let mut src = stream.alloc_pitched_zeros::<f32>(w, h).unwrap();
+        stream.memcpy_2d_htod(&host, w, &mut src).unwrap();
+
+        let sample = |desc: TextureDesc, x: f32, y: f32| {
+            let tex = ctx.new_texture_2d(&src, desc).unwrap();
+            let mut out = stream.alloc_zeros::<f32>(1).unwrap();
+            unsafe {
+                stream
+                    .launch_builder(&f)
+                    .arg(&tex)
+                    .arg(&mut out)
+                    .arg(&x)
+                    .arg(&y)
+                    .launch(LaunchConfig::for_num_elems(1))
+            }
+            .unwrap();
+            stream.memcpy_dtov(&out).unwrap()[0]
+        };
+
+        // texel centers are at +0.5
+        let point = TextureDesc::default();
+        assert_eq!(sample(point, 1.5, 1.5), 5.0);
+        assert_eq!(sample(point, 10.0, 0.5), 3.0);
+
+        let linear = TextureDesc {
+            filter_mode: FilterMode::Linear,
+            ..Default::default()
+        };
+        assert!((sample(linear, 2.0, 0.5) - 1.5).abs() < 1e-2);
+        assert!((sample(linear, 1.5, 1.0) - 3.0).abs() < 1e-2);
+
+        let wrap = TextureDesc {
+            address_mode: [AddressMode::Wrap; 2],
+            normalized_coords: true,
+            ..Default::default()
+        };
+        assert_eq!(sample(wrap, 1.125, 0.25), 0.0);
+    }
+
+    #[test]
+    fn test_texture_from_array() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let module = ctx.load_module(compile_ptx(TEX_CU).unwrap()).unwrap();
+        let f = module.load_function("sample_3d").unwrap();
+
+        let desc = ArrayDescriptor {
+            width: 4,
+            height: 3,
+            depth: 2,
+            format: sys::CUarray_format::CU_AD_FORMAT_FLOAT,
+            num_channels: 1,
+            flags: 0,
+        };
+        let mut array = ctx.alloc_array(desc).unwrap();
+        let host: Vec<f32> = (0..desc.len()).map(|i| i as f32).collect();
+        stream.memcpy_htoa(&host, &mut array).unwrap();
+
+        assert!(ctx
+            .new_texture_from_array::<u32>(&array, Default::default())
+            .is_err());
+        let tex = ctx
+            .new_texture_from_array::<f32>(&array, Default::default())
+            .unwrap();
+        let mut out = stream.alloc_zeros::<f32>(1).unwrap();
+        let (x, y, z) = (2.5f32, 1.5f32, 1.5f32);
+        unsafe {
+            stream
+                .launch_builder(&f)
+                .arg(&tex)
+                .arg(&mut out)
+                .arg(&x)
+                .arg(&y)
+                .arg(&z)
+                .launch(LaunchConfig::for_num_elems(1))
+        }
+        .unwrap();
+        // element (2, 1, 1)
+        assert_eq!(stream.memcpy_dtov(&out).unwrap(), [(12 + 4 + 2) as f32]);
+    }
+}
@@ -23,6 +23,11 @@ use super::{
 /// 2. [sys::CUmemAttach_flags::CU_MEM_ATTACH_GLOBAL] - any device/stream can access it.
 /// 3. [sys::CUmemAttach_flags::CU_MEM_ATTACH_SINGLE] - only the stream you attach it to can access it. Additionally, accessing on the CPU synchronizes the associated stream.
 ///
+/// Note that no explicit copies are needed to move data between host and device - the driver migrates pages
+/// on demand. Copying a [UnifiedSlice] into a [super::CudaSlice] before launching a kernel just duplicates the data.
+/// To avoid page faults on first access, you can migrate ahead of time with [UnifiedSlice::prefetch_to_device()]/[UnifiedSlice::prefetch_to_host()],
+/// and give the driver hints with [UnifiedSlice::mem_advise()].
+///
 /// See [cuda docs for Unified Addressing/Unified Memory](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__UNIFIED.html#group__CUDA__UNIFIED)
 ///
 /// # Thread safety
@@ -128,6 +133,7 @@ impl<T> UnifiedSlice<T> {
         flags: sys::CUmemAttach_flags,
     ) -> Result<(), DriverError> {
         self.event.synchronize()?;
+        stream.bind_to_thread()?;
         self.stream = stream.clone();
         self.attach_mode = flags;
         unsafe {
@@ -171,6 +177,7 @@ impl<T> UnifiedSlice<T> {
                 }
             }
         };
+        self.stream.bind_to_thread()?;
         unsafe {
             result::mem_prefetch_async(
                 self.cu_device_ptr,
@@ -181,6 +188,112 @@ impl<T> UnifiedSlice<T> {
         }
     }
 
+    /// Migrates the memory to the device associated with `stream`, ordered on `stream`.
+    ///
+    /// If the device has zero [sys::CUdevice_attribute::CU_DEVICE_ATTRIBUTE_CONCURRENT_MANAGED_ACCESS],
+    /// this returns [sys::cudaError_enum::CUDA_ERROR_NOT_PERMITTED].
+    ///
+    /// See [cuMemPrefetchAsync_v2 cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__UNIFIED.html#group__CUDA__UNIFIED_1gaf4f188a71891ad6a71fdd2850c8d638)
+    #[cfg(not(any(
+        feature = "cuda-11040",
+        feature = "cuda-11050",
+        feature = "cuda-11060",
+        feature = "cuda-11070",
+        feature = "cuda-11080",
+        feature = "cuda-12000",
+        feature = "cuda-12010"
+    )))]
+    pub fn prefetch_to_device(&self, stream: &CudaStream) -> Result<(), DriverError> {
+        let concurrent_managed_access = if self.stream.context() != stream.context() {
+            stream
+                .context()
+                .attribute(sys::CUdevice_attribute::CU_DEVICE_ATTRIBUTE_CONCURRENT_MANAGED_ACCESS)?
+                != 0
+        } else {
+            self.concurrent_managed_access
+        };
+        if !concurrent_managed_access {
+            return Err(DriverError(sys::cudaError_enum::CUDA_ERROR_NOT_PERMITTED));
+        }
+        let location = sys::CUmemLocation {
+            type_: sys::CUmemLocationType::CU_MEM_LOCATION_TYPE_DEVICE,
+            id: stream.ctx.ordinal as i32,
+        };
+        self.prefetch_to(location, stream)
+    }
+
+    /// Migrates the memory to the host NUMA node closest to the calling thread, ordered on `stream`.
+    ///
+    /// See [cuMemPrefetchAsync_v2 cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__UNIFIED.html#group__CUDA__UNIFIED_1gaf4f188a71891ad6a71fdd2850c8d638)
+    #[cfg(not(any(
+        feature = "cuda-11040",
+        feature = "cuda-11050",
+        feature = "cuda-11060",
+        feature = "cuda-11070",
+        feature = "cuda-11080",
+        feature = "cuda-12000",
+        feature = "cuda-12010"
+    )))]
+    pub fn prefetch_to_host(&self, stream: &CudaStream) -> Result<(), DriverError> {
+        let location = sys::CUmemLocation {
+            type_: sys::CUmemLocationType::CU_MEM_LOCATION_TYPE_HOST_NUMA_CURRENT,
+            id: 0, // NOTE: ignored
+        };
+        self.prefetch_to(location, stream)
+    }
+
+    #[cfg(not(any(
+        feature = "cuda-11040",
+        feature = "cuda-11050",
+        feature = "cuda-11060",
+        feature = "cuda-11070",
+        feature = "cuda-11080",
+        feature = "cuda-12000",
+        feature = "cuda-12010"
+    )))]
+    fn prefetch_to(
+        &self,
+        location: sys::CUmemLocation,
+        stream: &CudaStream,
+    ) -> Result<(), DriverError> {
+        stream.ctx.bind_to_thread()?;
+        stream.wait(&self.event)?;
+        unsafe {
+            result::mem_prefetch_async(
+                self.cu_device_ptr,
+                self.num_bytes(),
+                location,
+                stream.cu_stream,
+            )
+        }?;
+        self.event.record(stream)
+    }
+
+    /// Advise the driver about how this memory will be used. E.g.
+    /// - [sys::CUmem_advise::CU_MEM_ADVISE_SET_READ_MOSTLY] - the memory is mostly read, so the driver may
+    ///   create read-only copies on each accessing processor. `location` is ignored.
+    /// - [sys::CUmem_advise::CU_MEM_ADVISE_SET_PREFERRED_LOCATION] - the memory should preferably live at `location`, e.g.
+    ///   a [sys::CUmemLocationType::CU_MEM_LOCATION_TYPE_DEVICE] with `id` set to [CudaContext::ordinal()].
+    ///
+    /// See [cuMemAdvise_v2 cuda docs](https://docs.nvidia.com/cuda/cuda-driver-api/group__CUDA__UNIFIED.html#group__CUDA__UNIFIED_1g27608c857a9254789c13f3e3b72029e2)
+    #[cfg(not(any(
+        feature = "cuda-11040",
+        feature = "cuda-11050",
+        feature = "cuda-11060",
+        feature = "cuda-11070",
+        feature = "cuda-11080",
+        feature = "cuda-12000",
+        feature = "cuda-12010"
+    )))]
+    pub fn mem_advise(
+        &self,
+        advice: sys::CUmem_advise,
+        location: sys::CUmemLocation,
+    ) -> Result<(), DriverError> {
+        self.stream.ctx.bind_to_thread()?;
+        unsafe { result::mem_advise(self.cu_device_ptr, self.num_bytes(), advice, location) }
+    }
+
     pub fn check_host_access(&self) -> Result<(), DriverError> {
         match self.attach_mode {
             sys::CUmemAttach_flags_enum::CU_MEM_ATTACH_GLOBAL => {
@@ -434,6 +547,43 @@ extern \"C\" __global__ void kernel(float *buf) {
         Ok(())
     }
 
+    #[test]
+    #[cfg(not(any(
+        feature = "cuda-11040",
+        feature = "cuda-11050",
+        feature = "cuda-11060",
+        feature = "cuda-11070",
+        feature = "cuda-11080",
+        feature = "cuda-12000",
+        feature = "cuda-12010"
+    )))]
+    fn test_unified_memory_prefetch_and_advise() -> Result<(), DriverError> {
+        let ctx = CudaContext::new(0)?;
+        let stream = ctx.new_stream()?;
+
+        let mut a = unsafe { ctx.alloc_unified::<f32>(100, true) }?;
+        a.as_mut_slice()?.fill(1.0);
+
+        a.mem_advise(
+            sys::CUmem_advise::CU_MEM_ADVISE_SET_READ_MOSTLY,
+            sys::CUmemLocation {
+                type_: sys::CUmemLocationType::CU_MEM_LOCATION_TYPE_DEVICE,
+                id: ctx.ordinal() as i32,
+            },
+        )?;
+        if ctx.attribute(sys::CUdevice_attribute::CU_DEVICE_ATTRIBUTE_CONCURRENT_MANAGED_ACCESS)?
+            != 0
+        {
+            a.prefetch_to_device(&stream)?;
+        }
+        assert_eq!(stream.memcpy_dtov(&a)?, [1.0; 100]);
+
+        a.prefetch_to_host(&stream)?;
+        assert_eq!(a.as_slice()?, [1.0; 100]);
+
+        Ok(())
+    }
+
     #[test]
     fn test_unified_memory_host() -> Result<(), DriverError> {
         let ctx = CudaContext::new(0)?;
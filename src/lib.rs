@@ -78,6 +78,10 @@ extern crate alloc;
 #[cfg(feature = "no-std")]
 extern crate no_std_compat as std;
 
+// Lets `cudarc-derive` refer to `::cudarc` from within this crate.
+#[cfg(feature = "derive")]
+extern crate self as cudarc;
+
 #[cfg(feature = "cublas")]
 pub mod cublas;
 #[cfg(feature = "cublaslt")]
@@ -96,6 +100,11 @@ pub mod driver;
 pub mod nccl;
 #[cfg(feature = "nvrtc")]
 pub mod nvrtc;
+#[cfg(feature = "nvtx")]
+pub mod nvtx;
+/// NVTX range markers for profilers, the same as [nvtx].
+#[cfg(feature = "nvtx")]
+pub use nvtx as profiler;
 #[cfg(feature = "runtime")]
 pub mod runtime;
 
@@ -66,6 +66,18 @@ pub unsafe fn comm_abort(comm: sys::ncclComm_t) -> Result<NcclStatus, NcclError>
     sys::ncclCommAbort(comm).result()
 }
 
+/// Returns the error of asynchronous operations (e.g. a network failure of a peer) on `comm`,
+/// which is `ncclSuccess` if there is none. The outer result is whether the query itself succeeded.
+///
+/// See [cuda docs](https://docs.nvidia.com/deeplearning/nccl/user-guide/docs/api/comms.html?ncclcommgetasyncerror)
+/// # Safety
+/// User is in charge of sending valid pointers.
+pub unsafe fn comm_get_async_error(comm: sys::ncclComm_t) -> Result<sys::ncclResult_t, NcclError> {
+    let mut async_error = MaybeUninit::uninit();
+    sys::ncclCommGetAsyncError(comm, async_error.as_mut_ptr()).result()?;
+    Ok(async_error.assume_init())
+}
+
 /// See [cuda docs](https://docs.nvidia.com/deeplearning/nccl/user-guide/docs/api/comms.html?ncclcommcount)
 pub fn get_nccl_version() -> Result<::core::ffi::c_int, NcclError> {
     let mut version: ::core::ffi::c_int = 0;
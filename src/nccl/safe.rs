@@ -1,6 +1,6 @@
 use super::{result, sys};
 use crate::driver::{CudaContext, CudaStream, DevicePtr, DevicePtrMut};
-use std::{mem::MaybeUninit, sync::Arc, vec, vec::Vec};
+use std::{cell::Cell, mem::MaybeUninit, sync::Arc, vec, vec::Vec};
 
 pub use result::{group_end, group_start};
 
@@ -10,19 +10,39 @@ pub struct Comm {
     stream: Arc<CudaStream>,
     rank: usize,
     world_size: usize,
+    aborted: Cell<bool>,
 }
 
+/// The unique id of a communicator, which all ranks pass to [Comm::from_rank()].
+///
+/// Create it with [Id::new()] on one process (usually rank 0), then send it to all
+/// other ranks out-of-band (e.g. over TCP or MPI) with [Id::to_bytes()]/[Id::from_bytes()].
+/// It is plain data, so it is safe to send over the network as is.
 #[derive(Debug, Clone, Copy)]
+#[repr(C)]
 pub struct Id {
     id: sys::ncclUniqueId,
 }
 
 impl Id {
+    /// Generates a new unique id. Only **one** process should call this per communicator.
+    ///
+    /// See [nccl docs](https://docs.nvidia.com/deeplearning/nccl/user-guide/docs/api/comms.html#ncclgetuniqueid)
     pub fn new() -> Result<Self, result::NcclError> {
         let id = result::get_uniqueid()?;
         Ok(Self { id })
     }
 
+    /// The 128 bytes of this id, to send to other ranks. See [Id::from_bytes()].
+    pub fn to_bytes(&self) -> [u8; 128] {
+        self.id.internal.map(|c| c as u8)
+    }
+
+    /// Recreates an id from [Id::to_bytes()], e.g. after receiving it from rank 0.
+    pub fn from_bytes(bytes: [u8; 128]) -> Self {
+        Self::uninit(bytes.map(|b| b as ::core::ffi::c_char))
+    }
+
     pub fn uninit(internal: [::core::ffi::c_char; 128usize]) -> Self {
         let id = sys::ncclUniqueId { internal };
         Self { id }
@@ -54,8 +74,10 @@ fn convert_to_nccl_reduce_op(op: &ReduceOp) -> sys::ncclRedOp_t {
 impl Drop for Comm {
     fn drop(&mut self) {
         // TODO(thenerdstation): Shoule we instead do finalize then destory?
-        unsafe {
-            result::comm_abort(self.comm).expect("Error when aborting Comm.");
+        if !self.aborted.get() {
+            unsafe {
+                result::comm_abort(self.comm).expect("Error when aborting Comm.");
+            }
         }
     }
 }
@@ -129,6 +151,7 @@ impl Comm {
                 stream,
                 rank,
                 world_size: n_streams,
+                aborted: Cell::new(false),
             })
             .collect();
 
@@ -155,49 +178,64 @@ impl Comm {
         self.world_size
     }
 
-    /// Primitive to create new communication link on each process (threads are possible but not
-    /// recommended).
+    /// Creates the communicator for `rank` out of `world_size` ranks, which may be spread over
+    /// multiple processes and machines. The GPU used is the device of `stream`.
+    ///
+    /// **This is a collective call**: every rank in `0..world_size` must call it with the same `id`
+    /// (created once with [Id::new()] and shared out-of-band) and the same `world_size`, and it
+    /// blocks until all of them have joined. Ranks must be unique.
     ///
-    /// WARNING: If using threads, uou are likely to get limited throughput using a single core
+    /// Returns `ncclInvalidArgument` if `rank >= world_size`.
+    ///
+    /// WARNING: If using threads, you are likely to get limited throughput using a single core
     /// to control multiple GPUs. Cuda drivers effectively use a global mutex thrashing
     /// performance on multi threaded multi GPU (see issue #169).
-    /// ```
-    /// # use cudarc::driver::safe::{CudaDevice};
+    /// ```no_run
+    /// # use cudarc::driver::CudaContext;
     /// # use cudarc::nccl::safe::{Comm, Id, ReduceOp};
-    /// let n = 2;
-    /// let n_devices = 1; // This is to simplify this example.
-    /// // Spawn this only on rank 0
-    /// let id = Id::new().unwrap();
-    /// // Send id.internal() to other ranks
-    /// // let id = Id::uninit(id.internal().clone()); on other ranks
+    /// # fn send_to_all_ranks(_: [u8; 128]) {}
+    /// # fn receive_from_rank_0() -> [u8; 128] { [0; 128] }
+    /// let (rank, world_size) = (0, 2);
+    /// let id = if rank == 0 {
+    ///     let id = Id::new().unwrap();
+    ///     send_to_all_ranks(id.to_bytes());
+    ///     id
+    /// } else {
+    ///     Id::from_bytes(receive_from_rank_0())
+    /// };
     ///
-    /// let rank = 0;
-    /// let dev = CudaDevice::new(rank).unwrap();
-    /// let comm = Comm::from_rank(dev.clone(), rank, n_devices, id).unwrap();
-    /// let slice = dev.htod_copy(vec![(rank + 1) as f32 * 1.0; n]).unwrap();
-    /// let mut slice_receive = dev.alloc_zeros::<f32>(n).unwrap();
+    /// let ctx = CudaContext::new(0).unwrap();
+    /// let stream = ctx.default_stream();
+    /// let comm = Comm::from_rank(stream.clone(), rank, world_size, id).unwrap();
+    /// let slice = stream.memcpy_stod(&[(rank + 1) as f32; 2]).unwrap();
+    /// let mut slice_receive = stream.alloc_zeros::<f32>(2).unwrap();
     /// comm.all_reduce(&slice, &mut slice_receive, &ReduceOp::Sum)
     ///     .unwrap();
-    /// let out = dev.dtoh_sync_copy(&slice_receive).unwrap();
-    /// assert_eq!(out, vec![(n_devices * (n_devices + 1)) as f32 / 2.0; n]);
+    /// let out = stream.memcpy_dtov(&slice_receive).unwrap();
+    /// assert_eq!(out, [3.0; 2]);
     /// ```
+    ///
+    /// See [nccl docs](https://docs.nvidia.com/deeplearning/nccl/user-guide/docs/api/comms.html#ncclcomminitrank)
     pub fn from_rank(
         stream: Arc<CudaStream>,
         rank: usize,
         world_size: usize,
         id: Id,
     ) -> Result<Self, result::NcclError> {
-        let mut comm = MaybeUninit::uninit();
+        let invalid = result::NcclError(sys::ncclResult_t::ncclInvalidArgument);
+        if rank >= world_size {
+            return Err(invalid);
+        }
+        let nranks = world_size.try_into().map_err(|_| invalid)?;
+        // NOTE: nccl creates the communicator on the current device
+        stream
+            .context()
+            .bind_to_thread()
+            .map_err(|_| result::NcclError(sys::ncclResult_t::ncclUnhandledCudaError))?;
 
+        let mut comm = MaybeUninit::uninit();
         let comm = unsafe {
-            result::comm_init_rank(
-                comm.as_mut_ptr(),
-                world_size
-                    .try_into()
-                    .expect("World_size cannot be casted to i32"),
-                id.id,
-                rank.try_into().expect("Rank cannot be cast to i32"),
-            )?;
+            result::comm_init_rank(comm.as_mut_ptr(), nranks, id.id, rank as i32)?;
             comm.assume_init()
         };
         Ok(Self {
@@ -205,16 +243,115 @@ impl Comm {
             stream,
             rank,
             world_size,
+            aborted: Cell::new(false),
         })
     }
 }
 
 impl Comm {
+    /// Starts a group of NCCL calls, which are only launched at the matching [Comm::group_end()].
+    ///
+    /// See [nccl docs](https://docs.nvidia.com/deeplearning/nccl/user-guide/docs/api/group.html#ncclgroupstart)
+    pub fn group_start() -> Result<result::NcclStatus, result::NcclError> {
+        result::group_start()
+    }
+
+    /// Ends a group started with [Comm::group_start()], launching all the calls made inside of it.
+    ///
+    /// See [nccl docs](https://docs.nvidia.com/deeplearning/nccl/user-guide/docs/api/group.html#ncclgroupend)
+    pub fn group_end() -> Result<result::NcclStatus, result::NcclError> {
+        result::group_end()
+    }
+
+    /// The raw communicator, or `ncclInvalidUsage` after [Comm::abort()].
+    fn comm(&self) -> Result<sys::ncclComm_t, result::NcclError> {
+        if self.aborted.get() {
+            return Err(result::NcclError(sys::ncclResult_t::ncclInvalidUsage));
+        }
+        Ok(self.comm)
+    }
+
+    /// Returns the error of any asynchronous operation on this communicator, e.g. a network error
+    /// because a peer crashed. Such errors are not reported by the collective calls themselves,
+    /// which only enqueue work, so a collective waiting on a dead rank hangs forever unless
+    /// this is polled and the communicator is [aborted](Comm::abort()).
+    ///
+    /// Returns `ncclInvalidUsage` after [Comm::abort()].
+    ///
+    /// See [nccl docs](https://docs.nvidia.com/deeplearning/nccl/user-guide/docs/api/comms.html#ncclcommgetasyncerror)
+    pub fn check_async_error(&self) -> Result<(), result::NcclError> {
+        let async_error = unsafe { result::comm_get_async_error(self.comm()?) }?;
+        async_error.result().map(|_| ())
+    }
+
+    /// Aborts all uncompleted operations of this communicator and frees its resources,
+    /// so work on [Comm::stream()] that was waiting on other ranks can finish instead of hanging.
+    ///
+    /// Every later call on this communicator returns `ncclInvalidUsage`, so it should be dropped
+    /// (and usually the whole job restarted, since the other ranks can't complete their collectives
+    /// either). Calling this more than once does nothing.
+    ///
+    /// [Comm] is neither [Send] nor [Sync], so this can't be called from a separate watchdog thread
+    /// while another thread is blocked. Since NCCL calls only enqueue work on [Comm::stream()],
+    /// the waiting happens when synchronizing the stream instead, so the recommended pattern is
+    /// to wait for the stream with a timeout on the same thread while polling
+    /// [Comm::check_async_error()], and abort on either:
+    /// ```no_run
+    /// # use cudarc::nccl::safe::{Comm, ReduceOp};
+    /// # use cudarc::driver::CudaSlice;
+    /// # use std::time::{Duration, Instant};
+    /// # fn run(comm: &Comm, send: &CudaSlice<f32>, recv: &mut CudaSlice<f32>) -> Result<(), Box<dyn std::error::Error>> {
+    /// comm.all_reduce(send, recv, &ReduceOp::Sum)
+    ///     .map_err(|e| format!("{e:?}"))?;
+    /// let deadline = Instant::now() + Duration::from_secs(60);
+    /// while !comm.stream().synchronize_timeout(Duration::from_millis(100))? {
+    ///     let failed = comm.check_async_error().is_err();
+    ///     if failed || Instant::now() > deadline {
+    ///         comm.abort().map_err(|e| format!("{e:?}"))?;
+    ///         return Err("all_reduce failed or timed out, aborted the communicator".into());
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// See [nccl docs](https://docs.nvidia.com/deeplearning/nccl/user-guide/docs/api/comms.html#ncclcommabort)
+    pub fn abort(&self) -> Result<(), result::NcclError> {
+        if self.aborted.replace(true) {
+            return Ok(());
+        }
+        unsafe { result::comm_abort(self.comm) }?;
+        Ok(())
+    }
+
+    fn check_rank(&self, rank: i32) -> Result<(), result::NcclError> {
+        if rank < 0 || rank as usize >= self.world_size {
+            return Err(result::NcclError(sys::ncclResult_t::ncclInvalidArgument));
+        }
+        Ok(())
+    }
+
+    /// Sends `data` to rank `peer`, which must call [Comm::recv()] with a buffer of the same length.
+    ///
+    /// **Sends block until the matching receive is posted**, so two ranks that exchange data
+    /// with each other must issue their send and recv inside a single
+    /// [Comm::group_start()]/[Comm::group_end()] scope, otherwise they deadlock:
+    /// ```ignore
+    /// Comm::group_start()?;
+    /// comm.send(&send_buf, peer)?;
+    /// comm.recv(&mut recv_buf, peer)?;
+    /// Comm::group_end()?;
+    /// ```
+    ///
+    /// Returns [sys::ncclResult_t::ncclInvalidArgument] if `peer` is not in `0..world_size`.
+    ///
+    /// See [nccl docs](https://docs.nvidia.com/deeplearning/nccl/user-guide/docs/usage/p2p.html)
     pub fn send<S: DevicePtr<T>, T: NcclType>(
         &self,
         data: &S,
         peer: i32,
     ) -> Result<(), result::NcclError> {
+        self.check_rank(peer)?;
         let (src, _record_src) = data.device_ptr(&self.stream);
         unsafe {
             result::send(
@@ -222,18 +359,26 @@ impl Comm {
                 data.len(),
                 T::as_nccl_type(),
                 peer,
-                self.comm,
+                self.comm()?,
                 self.stream.cu_stream as _,
             )
         }?;
         Ok(())
     }
 
+    /// Receives data sent with [Comm::send()] from rank `peer` into `buff`.
+    /// See [Comm::send()] for why this usually needs to be inside a
+    /// [Comm::group_start()]/[Comm::group_end()] scope.
+    ///
+    /// Returns [sys::ncclResult_t::ncclInvalidArgument] if `peer` is not in `0..world_size`.
+    ///
+    /// See [nccl docs](https://docs.nvidia.com/deeplearning/nccl/user-guide/docs/usage/p2p.html)
     pub fn recv<R: DevicePtrMut<T>, T: NcclType>(
         &self,
         buff: &mut R,
         peer: i32,
     ) -> Result<result::NcclStatus, result::NcclError> {
+        self.check_rank(peer)?;
         let count = buff.len();
         let (dst, _record_dst) = buff.device_ptr_mut(&self.stream);
         unsafe {
@@ -242,7 +387,7 @@ impl Comm {
                 count,
                 T::as_nccl_type(),
                 peer,
-                self.comm,
+                self.comm()?,
                 self.stream.cu_stream as _,
             )
         }
@@ -250,9 +395,11 @@ impl Comm {
 
     /// Broadcasts a value from `root` rank to every other ranks `recvbuff`.
     /// sendbuff is ignored on ranks other than `root`, so you can pass `None`
-    /// on non-root ranks.
+    /// on non-root ranks. Use [Comm::broadcast_in_place()] to broadcast from
+    /// `recvbuff` on the root rank.
     ///
-    /// sendbuff must be Some on root rank!
+    /// Returns [sys::ncclResult_t::ncclInvalidArgument] if `root` is not in `0..world_size`,
+    /// or if this is the root rank and sendbuff is `None` or has a different length than `recvbuff`.
     ///
     /// See [nccl docs](https://docs.nvidia.com/deeplearning/nccl/user-guide/docs/usage/collectives.html#broadcast)
     pub fn broadcast<S: DevicePtr<T>, R: DevicePtrMut<T>, T: NcclType>(
@@ -261,8 +408,11 @@ impl Comm {
         recvbuff: &mut R,
         root: i32,
     ) -> Result<result::NcclStatus, result::NcclError> {
-        debug_assert!(sendbuff.is_some() || self.rank != root as usize);
+        self.check_rank(root)?;
         let count = recvbuff.len();
+        if self.rank == root as usize && sendbuff.map_or(true, |b| b.len() != count) {
+            return Err(result::NcclError(sys::ncclResult_t::ncclInvalidArgument));
+        }
         let (src, _record_src) = sendbuff.map(|b| b.device_ptr(&self.stream)).unzip();
         let (dst, _record_dst) = recvbuff.device_ptr_mut(&self.stream);
         unsafe {
@@ -272,19 +422,21 @@ impl Comm {
                 count,
                 T::as_nccl_type(),
                 root,
-                self.comm,
+                self.comm()?,
                 self.stream.cu_stream as _,
             )
         }
     }
 
-    /// In place version of [Comm::broadcast()].
+    /// In place version of [Comm::broadcast()]: the root rank sends its `recvbuff`,
+    /// which NCCL allows by passing the same pointer as send and receive buffer.
     /// See [nccl docs](https://docs.nvidia.com/deeplearning/nccl/user-guide/docs/usage/collectives.html#broadcast)
     pub fn broadcast_in_place<R: DevicePtrMut<T>, T: NcclType>(
         &self,
         recvbuff: &mut R,
         root: i32,
     ) -> Result<result::NcclStatus, result::NcclError> {
+        self.check_rank(root)?;
         let count = recvbuff.len();
         let (dst, _record_dst) = recvbuff.device_ptr_mut(&self.stream);
         unsafe {
@@ -294,18 +446,25 @@ impl Comm {
                 count,
                 T::as_nccl_type(),
                 root,
-                self.comm,
+                self.comm()?,
                 self.stream.cu_stream as _,
             )
         }
     }
 
+    /// Gathers `sendbuff` from every rank into `recvbuff`, ordered by rank.
+    ///
+    /// Returns [sys::ncclResult_t::ncclInvalidArgument] if `recvbuff.len() != sendbuff.len() * world_size`.
+    ///
     /// See [nccl docs](https://docs.nvidia.com/deeplearning/nccl/user-guide/docs/usage/collectives.html#allgather)
     pub fn all_gather<S: DevicePtr<T>, R: DevicePtrMut<T>, T: NcclType>(
         &self,
         sendbuff: &S,
         recvbuff: &mut R,
     ) -> Result<result::NcclStatus, result::NcclError> {
+        if recvbuff.len() != sendbuff.len() * self.world_size {
+            return Err(result::NcclError(sys::ncclResult_t::ncclInvalidArgument));
+        }
         let (src, _record_src) = sendbuff.device_ptr(&self.stream);
         let (dst, _record_dst) = recvbuff.device_ptr_mut(&self.stream);
         unsafe {
@@ -314,7 +473,7 @@ impl Comm {
                 dst as _,
                 sendbuff.len(),
                 T::as_nccl_type(),
-                self.comm,
+                self.comm()?,
                 self.stream.cu_stream as _,
             )
         }
@@ -336,7 +495,7 @@ impl Comm {
                 sendbuff.len(),
                 T::as_nccl_type(),
                 convert_to_nccl_reduce_op(reduce_op),
-                self.comm,
+                self.comm()?,
                 self.stream.cu_stream as _,
             )
         }
@@ -358,16 +517,18 @@ impl Comm {
                 count,
                 T::as_nccl_type(),
                 convert_to_nccl_reduce_op(reduce_op),
-                self.comm,
+                self.comm()?,
                 self.stream.cu_stream as _,
             )
         }
     }
 
     /// Reduces the sendbuff from all ranks into the recvbuff on the
-    /// `root` rank.
+    /// `root` rank. recvbuff is ignored on ranks other than `root`, so you
+    /// can pass `None` on non-root ranks.
     ///
-    /// recvbuff must be Some on the root rank!
+    /// Returns [sys::ncclResult_t::ncclInvalidArgument] if `root` is not in `0..world_size`,
+    /// or if this is the root rank and recvbuff is `None` or has a different length than `sendbuff`.
     ///
     /// See [nccl docs](https://docs.nvidia.com/deeplearning/nccl/user-guide/docs/usage/collectives.html#reduce)
     pub fn reduce<S: DevicePtr<T>, R: DevicePtrMut<T>, T: NcclType>(
@@ -377,7 +538,14 @@ impl Comm {
         reduce_op: &ReduceOp,
         root: i32,
     ) -> Result<result::NcclStatus, result::NcclError> {
-        debug_assert!(recvbuff.is_some() || self.rank != root as usize);
+        self.check_rank(root)?;
+        if self.rank == root as usize
+            && recvbuff
+                .as_ref()
+                .map_or(true, |b| b.len() != sendbuff.len())
+        {
+            return Err(result::NcclError(sys::ncclResult_t::ncclInvalidArgument));
+        }
 
         let (src, _record_src) = sendbuff.device_ptr(&self.stream);
         let (dst, _record_dst) = recvbuff.map(|b| b.device_ptr_mut(&self.stream)).unzip();
@@ -389,7 +557,7 @@ impl Comm {
                 T::as_nccl_type(),
                 convert_to_nccl_reduce_op(reduce_op),
                 root,
-                self.comm,
+                self.comm()?,
                 self.stream.cu_stream as _,
             )
         }
@@ -403,6 +571,7 @@ impl Comm {
         reduce_op: &ReduceOp,
         root: i32,
     ) -> Result<result::NcclStatus, result::NcclError> {
+        self.check_rank(root)?;
         let count = recvbuff.len();
         let (dst, _record_dst) = recvbuff.device_ptr_mut(&self.stream);
         unsafe {
@@ -413,12 +582,17 @@ impl Comm {
                 T::as_nccl_type(),
                 convert_to_nccl_reduce_op(reduce_op),
                 root,
-                self.comm,
+                self.comm()?,
                 self.stream.cu_stream as _,
             )
         }
     }
 
+    /// Reduces `sendbuff` across all ranks, and scatters the result so that each rank
+    /// receives its `rank`-th block in `recvbuff`.
+    ///
+    /// Returns [sys::ncclResult_t::ncclInvalidArgument] if `sendbuff.len() != recvbuff.len() * world_size`.
+    ///
     /// See [nccl docs](https://docs.nvidia.com/deeplearning/nccl/user-guide/docs/usage/collectives.html#reducescatter)
     pub fn reduce_scatter<S: DevicePtr<T>, R: DevicePtrMut<T>, T: NcclType>(
         &self,
@@ -426,6 +600,9 @@ impl Comm {
         recvbuff: &mut R,
         reduce_op: &ReduceOp,
     ) -> Result<result::NcclStatus, result::NcclError> {
+        if sendbuff.len() != recvbuff.len() * self.world_size {
+            return Err(result::NcclError(sys::ncclResult_t::ncclInvalidArgument));
+        }
         let count = recvbuff.len();
         let (src, _record_src) = sendbuff.device_ptr(&self.stream);
         let (dst, _record_dst) = recvbuff.device_ptr_mut(&self.stream);
@@ -436,7 +613,7 @@ impl Comm {
                 count,
                 T::as_nccl_type(),
                 convert_to_nccl_reduce_op(reduce_op),
-                self.comm,
+                self.comm()?,
                 self.stream.cu_stream as _,
             )
         }
@@ -459,6 +636,7 @@ macro_rules! group {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::driver::CudaSlice;
     #[cfg(feature = "no-std")]
     use no_std_compat::println;
 
@@ -491,6 +669,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_id_bytes_round_trip() {
+        let id = Id::new().unwrap();
+        let bytes = id.to_bytes();
+        assert_eq!(Id::from_bytes(bytes).internal(), id.internal());
+        assert_eq!(std::mem::size_of::<Id>(), 128);
+
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let comm = Comm::from_rank(stream.clone(), 0, 1, Id::from_bytes(bytes)).unwrap();
+        assert_eq!((comm.rank(), comm.world_size()), (0, 1));
+        assert!(Comm::from_rank(stream, 1, 1, id).is_err());
+    }
+
+    #[test]
+    fn test_abort_single_rank() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let comm = Comm::from_rank(stream.clone(), 0, 1, Id::new().unwrap()).unwrap();
+        comm.check_async_error().unwrap();
+
+        let slice = stream.memcpy_stod(&[1.0f32; 4]).unwrap();
+        let mut out = stream.alloc_zeros::<f32>(4).unwrap();
+        comm.all_reduce(&slice, &mut out, &ReduceOp::Sum).unwrap();
+        stream.synchronize().unwrap();
+
+        comm.abort().unwrap();
+        comm.abort().unwrap();
+        let invalid_usage = result::NcclError(sys::ncclResult_t::ncclInvalidUsage);
+        assert_eq!(comm.check_async_error(), Err(invalid_usage.clone()));
+        assert_eq!(
+            comm.all_reduce(&slice, &mut out, &ReduceOp::Sum).err(),
+            Some(invalid_usage)
+        );
+        drop(comm);
+    }
+
     #[test]
     fn test_all_reduce_views() {
         let n = 2;
@@ -522,4 +737,93 @@ mod tests {
             t.join().unwrap()
         }
     }
+
+    #[test]
+    fn test_all_gather_reduce_scatter_single_rank() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let id = Id::new().unwrap();
+        let comm = Comm::from_rank(stream.clone(), 0, 1, id).unwrap();
+
+        let send = stream.memcpy_stod(&[1.0f32, 2.0, 3.0]).unwrap();
+        let mut recv = stream.alloc_zeros::<f32>(3).unwrap();
+        comm.all_gather(&send, &mut recv).unwrap();
+        assert_eq!(stream.memcpy_dtov(&recv).unwrap(), [1.0, 2.0, 3.0]);
+
+        let mut recv = stream.alloc_zeros::<f32>(3).unwrap();
+        comm.reduce_scatter(&send, &mut recv, &ReduceOp::Sum)
+            .unwrap();
+        assert_eq!(stream.memcpy_dtov(&recv).unwrap(), [1.0, 2.0, 3.0]);
+
+        let mut wrong = stream.alloc_zeros::<f32>(2).unwrap();
+        assert_eq!(
+            comm.all_gather(&send, &mut wrong).err(),
+            Some(result::NcclError(sys::ncclResult_t::ncclInvalidArgument))
+        );
+        assert_eq!(
+            comm.reduce_scatter(&send, &mut wrong, &ReduceOp::Sum).err(),
+            Some(result::NcclError(sys::ncclResult_t::ncclInvalidArgument))
+        );
+    }
+
+    #[test]
+    fn test_send_recv_single_rank() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let id = Id::new().unwrap();
+        let comm = Comm::from_rank(stream.clone(), 0, 1, id).unwrap();
+
+        let send = stream.memcpy_stod(&[1.0f32, 2.0]).unwrap();
+        let mut recv = stream.alloc_zeros::<f32>(2).unwrap();
+        Comm::group_start().unwrap();
+        comm.send(&send, 0).unwrap();
+        comm.recv(&mut recv, 0).unwrap();
+        Comm::group_end().unwrap();
+        assert_eq!(stream.memcpy_dtov(&recv).unwrap(), [1.0, 2.0]);
+
+        assert_eq!(
+            comm.send(&send, 1).err(),
+            Some(result::NcclError(sys::ncclResult_t::ncclInvalidArgument))
+        );
+        assert_eq!(
+            comm.recv(&mut recv, -1).err(),
+            Some(result::NcclError(sys::ncclResult_t::ncclInvalidArgument))
+        );
+    }
+
+    #[test]
+    fn test_broadcast_reduce_single_rank() {
+        let ctx = CudaContext::new(0).unwrap();
+        let stream = ctx.default_stream();
+        let id = Id::new().unwrap();
+        let comm = Comm::from_rank(stream.clone(), 0, 1, id).unwrap();
+
+        let send = stream.memcpy_stod(&[1.0f32, 2.0]).unwrap();
+        let mut recv = stream.alloc_zeros::<f32>(2).unwrap();
+        comm.broadcast(Some(&send), &mut recv, 0).unwrap();
+        assert_eq!(stream.memcpy_dtov(&recv).unwrap(), [1.0, 2.0]);
+
+        let mut buf = stream.memcpy_stod(&[3.0f32, 4.0]).unwrap();
+        comm.broadcast_in_place(&mut buf, 0).unwrap();
+        assert_eq!(stream.memcpy_dtov(&buf).unwrap(), [3.0, 4.0]);
+
+        let mut recv = stream.alloc_zeros::<f32>(2).unwrap();
+        comm.reduce(&send, Some(&mut recv), &ReduceOp::Sum, 0)
+            .unwrap();
+        assert_eq!(stream.memcpy_dtov(&recv).unwrap(), [1.0, 2.0]);
+
+        assert_eq!(
+            comm.broadcast(None::<&CudaSlice<f32>>, &mut recv, 0).err(),
+            Some(result::NcclError(sys::ncclResult_t::ncclInvalidArgument))
+        );
+        assert_eq!(
+            comm.reduce(&send, None::<&mut CudaSlice<f32>>, &ReduceOp::Sum, 0)
+                .err(),
+            Some(result::NcclError(sys::ncclResult_t::ncclInvalidArgument))
+        );
+        assert_eq!(
+            comm.broadcast_in_place(&mut recv, 1).err(),
+            Some(result::NcclError(sys::ncclResult_t::ncclInvalidArgument))
+        );
+    }
 }
@@ -46,15 +46,54 @@ impl std::error::Error for NvrtcError {}
 /// references to `src` and `name`. The memory containing the compiled
 /// code and name must not be dropped until the `nvrtcProgram` is destroyed.
 pub fn create_program(src: &CStr, name: Option<&CStr>) -> Result<sys::nvrtcProgram, NvrtcError> {
+    create_program_with_headers(src, name, &[], &[])
+}
+
+/// Same as [create_program()], but also passes in-memory headers to the program.
+/// `headers[i]` is the source of the header that can be included with `#include "include_names[i]"`.
+///
+/// See [nvrtcCreateProgram() docs](https://docs.nvidia.com/cuda/nvrtc/index.html#group__compilation_1g9ae65f68911d1cf0adda2af4ad8cb458)
+///
+/// Example:
+/// ```rust
+/// # use cudarc::nvrtc::result::*;
+/// let prog = create_program_with_headers(
+///     c"#include \"one.h\"\nextern \"C\" __global__ void kernel(float *out) { out[0] = one(); }",
+///     None,
+///     &[c"__device__ float one() { return 1.0f; }"],
+///     &[c"one.h"],
+/// ).unwrap();
+/// ```
+///
+/// # Panics
+///
+/// If `headers` and `include_names` have different lengths.
+pub fn create_program_with_headers(
+    src: &CStr,
+    name: Option<&CStr>,
+    headers: &[&CStr],
+    include_names: &[&CStr],
+) -> Result<sys::nvrtcProgram, NvrtcError> {
+    assert_eq!(headers.len(), include_names.len());
+    let headers: Vec<*const c_char> = headers.iter().map(|h| h.as_ptr()).collect();
+    let include_names: Vec<*const c_char> = include_names.iter().map(|n| n.as_ptr()).collect();
     let mut prog = MaybeUninit::uninit();
     unsafe {
         sys::nvrtcCreateProgram(
             prog.as_mut_ptr(),
             src.as_ptr(),
             name.map(|n| n.as_ptr()).unwrap_or(std::ptr::null()),
-            0,
-            std::ptr::null(),
-            std::ptr::null(),
+            headers.len() as c_int,
+            if headers.is_empty() {
+                std::ptr::null()
+            } else {
+                headers.as_ptr()
+            },
+            if include_names.is_empty() {
+                std::ptr::null()
+            } else {
+                include_names.as_ptr()
+            },
         )
         .result()?;
         Ok(prog.assume_init())
@@ -91,6 +130,39 @@ pub unsafe fn compile_program<O: Clone + Into<Vec<u8>>>(
     sys::nvrtcCompileProgram(prog, opts.len() as c_int, opts.as_ptr()).result()
 }
 
+/// Registers a name expression (e.g. `"kernel<float>"`) whose lowered (mangled) name can be
+/// retrieved with [get_lowered_name()] after compilation. Call this before [compile_program()].
+///
+/// See [nvrtcAddNameExpression() docs](https://docs.nvidia.com/cuda/nvrtc/index.html#group__compilation)
+///
+/// # Safety
+///
+/// `prog` must be created from [create_program()] and not have been freed by [destroy_program()].
+pub unsafe fn add_name_expression(
+    prog: sys::nvrtcProgram,
+    name_expression: &CStr,
+) -> Result<(), NvrtcError> {
+    sys::nvrtcAddNameExpression(prog, name_expression.as_ptr()).result()
+}
+
+/// Gets the lowered (mangled) name of a name expression registered with [add_name_expression()].
+///
+/// See [nvrtcGetLoweredName() docs](https://docs.nvidia.com/cuda/nvrtc/index.html#group__compilation)
+///
+/// # Safety
+///
+/// `prog` must be created from [create_program()], compiled with [compile_program()],
+/// and not have been freed by [destroy_program()].
+pub unsafe fn get_lowered_name(
+    prog: sys::nvrtcProgram,
+    name_expression: &CStr,
+) -> Result<CString, NvrtcError> {
+    let mut lowered_name = MaybeUninit::uninit();
+    sys::nvrtcGetLoweredName(prog, name_expression.as_ptr(), lowered_name.as_mut_ptr()).result()?;
+    // NOTE: the lowered name is owned by `prog`, so we copy it out.
+    Ok(CStr::from_ptr(lowered_name.assume_init()).to_owned())
+}
+
 /// Releases resources associated with `prog`.
 ///
 /// See [nvrtcDestroyProgram() docs](https://docs.nvidia.com/cuda/nvrtc/index.html#group__compilation_1gaa237c59615b7d4f48d5b308b5c9b140).
@@ -121,6 +193,25 @@ pub unsafe fn get_ptx(prog: sys::nvrtcProgram) -> Result<Vec<c_char>, NvrtcError
     Ok(ptx_src)
 }
 
+/// Extract the cubin associated with `prog`. Call [compile_program()] before this,
+/// with a real architecture (e.g. `--gpu-architecture=sm_80`), otherwise this returns an error.
+///
+/// See [nvrtcGetCUBIN() docs](https://docs.nvidia.com/cuda/nvrtc/index.html#group__compilation)
+///
+/// # Safety
+///
+/// `prog` must be created from [create_program()] and not have been freed by [destroy_program()].
+#[allow(clippy::slow_vector_initialization)]
+pub unsafe fn get_cubin(prog: sys::nvrtcProgram) -> Result<Vec<u8>, NvrtcError> {
+    let mut size: usize = 0;
+    sys::nvrtcGetCUBINSize(prog, &mut size as *mut _).result()?;
+
+    let mut cubin: Vec<u8> = Vec::with_capacity(size);
+    cubin.resize(size, 0);
+    sys::nvrtcGetCUBIN(prog, cubin.as_mut_ptr() as *mut c_char).result()?;
+    Ok(cubin)
+}
+
 /// Extract log from a compiled program.
 ///
 /// See [nvrtcGetProgramLog() docs](https://docs.nvidia.com/cuda/nvrtc/index.html#group__compilation_1g74c550e5cab81efbd59e4f72579edbd1)
@@ -140,6 +231,16 @@ pub unsafe fn get_program_log(prog: sys::nvrtcProgram) -> Result<Vec<c_char>, Nv
     Ok(log_src)
 }
 
+/// Returns the `(major, minor)` version of the loaded nvrtc library.
+///
+/// See [nvrtcVersion() docs](https://docs.nvidia.com/cuda/nvrtc/index.html#group__query)
+pub fn version() -> Result<(i32, i32), NvrtcError> {
+    let mut major = 0;
+    let mut minor = 0;
+    unsafe { sys::nvrtcVersion(&mut major, &mut minor) }.result()?;
+    Ok((major, minor))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
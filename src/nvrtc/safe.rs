@@ -6,30 +6,64 @@ use super::{result, sys};
 
 use core::ffi::{c_char, CStr};
 use std::ffi::CString;
-use std::{borrow::ToOwned, path::PathBuf, string::String, vec::Vec};
+use std::{
+    borrow::ToOwned,
+    path::{Path, PathBuf},
+    string::String,
+    vec::Vec,
+};
 
 /// An opaque structure representing a compiled PTX program
 /// output from [compile_ptx()] or [compile_ptx_with_opts()].
 ///
 /// Can also be created from a [Ptx::from_file] and [Ptx::from_src]
 #[derive(Debug, Clone)]
-pub struct Ptx(pub(crate) PtxKind);
+pub struct Ptx {
+    pub(crate) kind: PtxKind,
+    pub(crate) lowered_names: Vec<(String, String)>,
+    pub(crate) log: String,
+}
 
 impl Ptx {
+    pub(crate) fn new(kind: PtxKind) -> Self {
+        Self {
+            kind,
+            lowered_names: Vec::new(),
+            log: String::new(),
+        }
+    }
+
     /// Creates a Ptx from a pre-compiled .ptx file.
     pub fn from_file<P: Into<PathBuf>>(path: P) -> Self {
-        Self(PtxKind::File(path.into()))
+        Self::new(PtxKind::File(path.into()))
     }
 
     /// Creates a Ptx from the source string of a pre-compiled .ptx
     /// file.
     pub fn from_src<S: Into<String>>(src: S) -> Self {
-        Self(PtxKind::Src(src.into()))
+        Self::new(PtxKind::Src(src.into()))
+    }
+
+    /// The lowered (mangled) name of a name expression from [CompileOptions::name_expressions],
+    /// e.g. `"kernel<float>"`. Pass this to [crate::driver::CudaModule::load_function()].
+    ///
+    /// Returns `None` if `expr` was not registered.
+    pub fn lowered_name(&self, expr: &str) -> Option<&str> {
+        self.lowered_names
+            .iter()
+            .find(|(e, _)| e == expr)
+            .map(|(_, name)| name.as_str())
+    }
+
+    /// The compilation log from nvrtc, which contains any warnings (e.g. unused variables).
+    /// Empty if there were none, or this was not created by [compile_ptx()]/[compile_ptx_with_opts()].
+    pub fn compile_log(&self) -> &str {
+        &self.log
     }
 
     /// Get the compiled source as a string.
     pub fn to_src(&self) -> String {
-        match &self.0 {
+        match &self.kind {
             PtxKind::Image(bytes) => unsafe { CStr::from_ptr(bytes.as_ptr()) }
                 .to_str()
                 .expect("Unable to convert bytes to str.")
@@ -88,10 +122,197 @@ pub fn compile_ptx_with_opts<S: AsRef<str>>(
     src: S,
     opts: CompileOptions,
 ) -> Result<Ptx, CompileError> {
-    let prog = Program::create(src, opts.name.as_deref())?;
+    let prog = Program::create(src, opts.name.as_deref(), &opts.headers)?;
     prog.compile(opts)
 }
 
+/// Like [compile_ptx_with_opts()], but caches the ptx as a file in `cache_dir`, so compiling the same
+/// source again (e.g. on the next program start) only reads the file.
+///
+/// The cache key is a hash of `src`, `opts` (including [CompileOptions::headers]) and the nvrtc version,
+/// so changing any of them, or upgrading the toolkit, compiles again. The ptx is still JIT compiled by
+/// the driver when loaded with [crate::driver::CudaContext::load_module()], which has its own cache of
+/// JIT results. See [compile_cubin_cached()] to skip that too.
+///
+/// Failing to read or write the cache is not an error, the source is just compiled. `opts` with
+/// [CompileOptions::name_expressions] or [CompileOptions::include_paths] are never cached, since the
+/// lowered names are not stored, and headers on disk can change without changing the key.
+///
+/// Example:
+/// ```rust
+/// # use cudarc::nvrtc::*;
+/// let cache_dir = std::env::temp_dir().join("my-app-kernels");
+/// let src = "extern \"C\" __global__ void kernel() { }";
+/// let ptx = compile_ptx_cached(src, Default::default(), &cache_dir).unwrap();
+/// ```
+pub fn compile_ptx_cached<S: AsRef<str>, P: AsRef<Path>>(
+    src: S,
+    opts: CompileOptions,
+    cache_dir: P,
+) -> Result<Ptx, CompileError> {
+    compile_cached(
+        src.as_ref(),
+        opts,
+        cache_dir.as_ref(),
+        "ptx",
+        |src, opts| compile_ptx_with_opts(src, opts),
+        |ptx| ptx.to_src().into_bytes(),
+        |bytes| String::from_utf8(bytes).ok().map(Ptx::from_src),
+    )
+}
+
+/// Like [compile_cubin()], but caches the cubin as a file in `cache_dir`. See [compile_ptx_cached()]
+/// for how the cache key is computed and which `opts` are not cached.
+///
+/// The architecture is part of `opts`, so the same source compiled for different devices is cached
+/// separately.
+pub fn compile_cubin_cached<S: AsRef<str>, P: AsRef<Path>>(
+    src: S,
+    opts: CompileOptions,
+    cache_dir: P,
+) -> Result<Cubin, CompileError> {
+    compile_cached(
+        src.as_ref(),
+        opts,
+        cache_dir.as_ref(),
+        "cubin",
+        |src, opts| compile_cubin(src, opts),
+        |cubin| cubin.as_bytes().to_vec(),
+        |bytes| Some(Cubin::from_bytes(bytes)),
+    )
+}
+
+fn compile_cached<T>(
+    src: &str,
+    opts: CompileOptions,
+    cache_dir: &Path,
+    extension: &str,
+    compile: impl FnOnce(&str, CompileOptions) -> Result<T, CompileError>,
+    to_bytes: impl FnOnce(&T) -> Vec<u8>,
+    from_bytes: impl FnOnce(Vec<u8>) -> Option<T>,
+) -> Result<T, CompileError> {
+    let Some(path) = cache_path(src, &opts, cache_dir, extension) else {
+        return compile(src, opts);
+    };
+    if let Some(cached) = std::fs::read(&path).ok().and_then(from_bytes) {
+        return Ok(cached);
+    }
+    let compiled = compile(src, opts)?;
+    // write to a temporary file unique to this call first, so concurrent processes or threads
+    // compiling the same source never write to the same file or read a partial one
+    static TMP_COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    let tmp = path.with_extension(std::format!(
+        "{extension}.{}.{}.tmp",
+        std::process::id(),
+        TMP_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+    ));
+    let written = std::fs::create_dir_all(cache_dir)
+        .and_then(|_| std::fs::write(&tmp, to_bytes(&compiled)))
+        .and_then(|_| std::fs::rename(&tmp, &path));
+    if written.is_err() {
+        std::fs::remove_file(&tmp).ok();
+    }
+    Ok(compiled)
+}
+
+fn cache_path(
+    src: &str,
+    opts: &CompileOptions,
+    cache_dir: &Path,
+    extension: &str,
+) -> Option<PathBuf> {
+    use std::hash::{Hash, Hasher};
+    if !opts.name_expressions.is_empty() || !opts.include_paths.is_empty() {
+        return None;
+    }
+    let version = result::version().ok()?;
+    let mut hasher = Fnv1a64::default();
+    (src, opts, version).hash(&mut hasher);
+    Some(cache_dir.join(std::format!("{:016x}.{extension}", hasher.finish())))
+}
+
+/// 64-bit FNV-1a. Unlike [std::collections::hash_map::DefaultHasher] its output is fixed,
+/// so cache keys stay the same across Rust releases.
+struct Fnv1a64(u64);
+
+impl Default for Fnv1a64 {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl std::hash::Hasher for Fnv1a64 {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+/// Compiles `src` to a cubin with the given `opts`. `src` is the source string of a `.cu` file.
+///
+/// `opts.arch` must be a real architecture like `"sm_80"` (it is passed as `--gpu-architecture`),
+/// otherwise this returns [CompileError::GetCubinError].
+///
+/// Unlike PTX, a cubin is not JIT compiled when loaded, which makes [crate::driver::CudaContext::load_cubin()]
+/// faster than [crate::driver::CudaContext::load_module()]. However a cubin is **not** forward compatible:
+/// it can only be loaded on devices of the same major architecture and same or newer minor version.
+///
+/// Example:
+/// ```rust
+/// # use cudarc::nvrtc::*;
+/// let opts = CompileOptions {
+///     arch: Some("sm_80"),
+///     ..Default::default()
+/// };
+/// let cubin = compile_cubin("extern \"C\" __global__ void kernel() { }", opts).unwrap();
+/// ```
+pub fn compile_cubin<S: AsRef<str>>(src: S, opts: CompileOptions) -> Result<Cubin, CompileError> {
+    let prog = Program::create(src, opts.name.as_deref(), &opts.headers)?;
+    prog.compile_cubin(opts)
+}
+
+/// A compiled cubin output from [compile_cubin()], that can be loaded with [crate::driver::CudaContext::load_cubin()].
+#[derive(Debug, Clone)]
+pub struct Cubin {
+    pub(crate) image: Vec<u8>,
+    pub(crate) lowered_names: Vec<(String, String)>,
+    pub(crate) log: String,
+}
+
+impl Cubin {
+    /// Creates a Cubin from the bytes of a pre-compiled .cubin file.
+    pub fn from_bytes(image: Vec<u8>) -> Self {
+        Self {
+            image,
+            lowered_names: Vec::new(),
+            log: String::new(),
+        }
+    }
+
+    /// The raw bytes of the cubin.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.image
+    }
+
+    /// See [Ptx::lowered_name()].
+    pub fn lowered_name(&self, expr: &str) -> Option<&str> {
+        self.lowered_names
+            .iter()
+            .find(|(e, _)| e == expr)
+            .map(|(_, name)| name.as_str())
+    }
+
+    /// See [Ptx::compile_log()].
+    pub fn compile_log(&self) -> &str {
+        &self.log
+    }
+}
+
 pub(crate) struct Program {
     prog: sys::nvrtcProgram,
 
@@ -99,24 +320,62 @@ pub(crate) struct Program {
     // dropped until after the nvrtcProgram.
     _src: CString,
     _name: Option<CString>,
+    _headers: Vec<(CString, CString)>,
 }
 
 impl Program {
-    pub(crate) fn create<S: AsRef<str>>(src: S, name: Option<&str>) -> Result<Self, CompileError> {
+    pub(crate) fn create<S: AsRef<str>>(
+        src: S,
+        name: Option<&str>,
+        headers: &[(String, String)],
+    ) -> Result<Self, CompileError> {
         let src = CString::new(src.as_ref().as_bytes())
             .expect("program code cannot contain null terminators");
         let name =
             name.map(|s| CString::new(s).expect("program name cannot contain null terminators"));
-        let prog =
-            result::create_program(&src, name.as_deref()).map_err(CompileError::CreationError)?;
+        let headers: Vec<(CString, CString)> = headers
+            .iter()
+            .map(|(include_name, header)| {
+                (
+                    CString::new(include_name.as_str())
+                        .expect("header names cannot contain null terminators"),
+                    CString::new(header.as_str())
+                        .expect("header code cannot contain null terminators"),
+                )
+            })
+            .collect();
+        let include_names: Vec<&CStr> = headers.iter().map(|(n, _)| n.as_c_str()).collect();
+        let header_srcs: Vec<&CStr> = headers.iter().map(|(_, h)| h.as_c_str()).collect();
+        let prog = result::create_program_with_headers(
+            &src,
+            name.as_deref(),
+            &header_srcs,
+            &include_names,
+        )
+        .map_err(CompileError::CreationError)?;
         Ok(Self {
             prog,
             _src: src,
             _name: name,
+            _headers: headers,
         })
     }
 
-    pub(crate) fn compile(self, opts: CompileOptions) -> Result<Ptx, CompileError> {
+    /// Compiles the program, returning the lowered names of [CompileOptions::name_expressions] and the log.
+    #[allow(clippy::type_complexity)]
+    fn run(
+        &self,
+        mut opts: CompileOptions,
+    ) -> Result<(Vec<(String, String)>, String), CompileError> {
+        let name_expressions: Vec<CString> = std::mem::take(&mut opts.name_expressions)
+            .into_iter()
+            .map(|e| CString::new(e).expect("name expressions cannot contain null terminators"))
+            .collect();
+        for expr in name_expressions.iter() {
+            unsafe { result::add_name_expression(self.prog, expr) }
+                .map_err(CompileError::NameExpressionError)?;
+        }
+
         let options = opts.build();
 
         unsafe { result::compile_program(self.prog, &options) }.map_err(|e| {
@@ -130,9 +389,43 @@ impl Program {
             }
         })?;
 
+        let log_raw =
+            unsafe { result::get_program_log(self.prog) }.map_err(CompileError::GetLogError)?;
+        let log = unsafe { CStr::from_ptr(log_raw.as_ptr()) }
+            .to_string_lossy()
+            .into_owned();
+
+        let mut lowered_names = Vec::with_capacity(name_expressions.len());
+        for expr in name_expressions {
+            let lowered = unsafe { result::get_lowered_name(self.prog, &expr) }
+                .map_err(CompileError::NameExpressionError)?;
+            lowered_names.push((
+                expr.into_string().unwrap(),
+                lowered.into_string().expect("lowered names are valid utf8"),
+            ));
+        }
+
+        Ok((lowered_names, log))
+    }
+
+    pub(crate) fn compile(self, opts: CompileOptions) -> Result<Ptx, CompileError> {
+        let (lowered_names, log) = self.run(opts)?;
         let image = unsafe { result::get_ptx(self.prog) }.map_err(CompileError::GetPtxError)?;
+        Ok(Ptx {
+            kind: PtxKind::Image(image),
+            lowered_names,
+            log,
+        })
+    }
 
-        Ok(Ptx(PtxKind::Image(image)))
+    pub(crate) fn compile_cubin(self, opts: CompileOptions) -> Result<Cubin, CompileError> {
+        let (lowered_names, log) = self.run(opts)?;
+        let image = unsafe { result::get_cubin(self.prog) }.map_err(CompileError::GetCubinError)?;
+        Ok(Cubin {
+            image,
+            lowered_names,
+            log,
+        })
     }
 }
 
@@ -164,8 +457,14 @@ pub enum CompileError {
     /// Error happened during [result::get_ptx()]
     GetPtxError(result::NvrtcError),
 
+    /// Error happened during [result::get_cubin()]
+    GetCubinError(result::NvrtcError),
+
     /// Error happened during [result::destroy_program()]
     DestroyError(result::NvrtcError),
+
+    /// Error happened during [result::add_name_expression()] or [result::get_lowered_name()]
+    NameExpressionError(result::NvrtcError),
 }
 
 #[cfg(feature = "std")]
@@ -198,21 +497,98 @@ impl std::error::Error for CompileError {}
 ///     ..Default::default()
 /// };
 /// ```
+///
+/// The common flags can also be set with builder methods:
+/// ```rust
+/// # use cudarc::nvrtc::*;
+/// let opts = CompileOptions::default()
+///     .use_fast_math(true)
+///     .maxrregcount(64)
+///     .optimization_level(3)
+///     .option("--std=c++17");
+/// ```
 #[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
 pub struct CompileOptions {
     pub ftz: Option<bool>,
     pub prec_sqrt: Option<bool>,
     pub prec_div: Option<bool>,
     pub fmad: Option<bool>,
+    /// Any other flags, passed through as is after all the others (e.g. `"--std=c++17"`).
     pub options: Vec<String>,
+    /// `--use_fast_math`, which implies `--ftz=true --prec-div=false --prec-sqrt=false --fmad=true`.
     pub use_fast_math: Option<bool>,
     pub maxrregcount: Option<usize>,
+    /// `--relocatable-device-code`, needed to call device functions defined in another
+    /// translation unit. The resulting ptx has to be linked before it can be loaded.
+    pub relocatable_device_code: Option<bool>,
+    /// Optimization level of ptxas, `0..=3`, passed as `--ptxas-options=-O{level}`.
+    /// Level 0 also turns off device code optimization with `--dopt=off`, which needs nvrtc 11.7 or newer.
+    ///
+    /// This applies when compiling to a cubin (e.g. [compile_cubin()]); ptx is optimized
+    /// by the driver when it is loaded.
+    pub optimization_level: Option<u8>,
+    /// Directories to search for `#include`s, passed as `--include-path` (the same as `-I`).
     pub include_paths: Vec<String>,
     pub arch: Option<&'static str>,
     pub name: Option<String>,
+    /// Name expressions (e.g. `"kernel<float>"`) to retrieve lowered names for with [Ptx::lowered_name()].
+    /// These are not passed as compiler flags.
+    pub name_expressions: Vec<String>,
+    /// In-memory headers as `(include_name, source)` pairs. A kernel can use them
+    /// with `#include "include_name"`. These are not passed as compiler flags.
+    pub headers: Vec<(String, String)>,
 }
 
 impl CompileOptions {
+    /// Sets [CompileOptions::use_fast_math].
+    pub fn use_fast_math(mut self, enabled: bool) -> Self {
+        self.use_fast_math = Some(enabled);
+        self
+    }
+
+    /// Sets [CompileOptions::ftz] (`--ftz`), flushing denormal floats to zero.
+    pub fn ftz(mut self, enabled: bool) -> Self {
+        self.ftz = Some(enabled);
+        self
+    }
+
+    /// Sets [CompileOptions::prec_div] (`--prec-div`), using IEEE round-to-nearest division.
+    pub fn prec_div(mut self, enabled: bool) -> Self {
+        self.prec_div = Some(enabled);
+        self
+    }
+
+    /// Sets [CompileOptions::relocatable_device_code].
+    pub fn relocatable_device_code(mut self, enabled: bool) -> Self {
+        self.relocatable_device_code = Some(enabled);
+        self
+    }
+
+    /// Sets [CompileOptions::maxrregcount] (`--maxrregcount`), the maximum number of registers per thread.
+    pub fn maxrregcount(mut self, count: u32) -> Self {
+        self.maxrregcount = Some(count as usize);
+        self
+    }
+
+    /// Sets [CompileOptions::optimization_level].
+    ///
+    /// # Panics
+    /// If `level` is not in `0..=3`.
+    pub fn optimization_level(mut self, level: u8) -> Self {
+        assert!(
+            level <= 3,
+            "optimization level must be in 0..=3, got {level}"
+        );
+        self.optimization_level = Some(level);
+        self
+    }
+
+    /// Appends a raw flag to [CompileOptions::options], for anything without a builder method.
+    pub fn option<S: Into<String>>(mut self, flag: S) -> Self {
+        self.options.push(flag.into());
+        self
+    }
+
     pub(crate) fn build(self) -> Vec<String> {
         let mut options: Vec<String> = Vec::new();
 
@@ -233,13 +609,24 @@ impl CompileOptions {
         }
 
         if let Some(true) = self.use_fast_math {
-            options.push("--fmad=true".into());
+            options.push("--use_fast_math".into());
         }
 
         if let Some(count) = self.maxrregcount {
             options.push(std::format!("--maxrregcount={count}"));
         }
 
+        if let Some(v) = self.relocatable_device_code {
+            options.push(std::format!("--relocatable-device-code={v}"));
+        }
+
+        if let Some(level) = self.optimization_level {
+            if level == 0 {
+                options.push("--dopt=off".into());
+            }
+            options.push(std::format!("--ptxas-options=-O{level}"));
+        }
+
         for path in self.include_paths {
             options.push(std::format!("--include-path={path}"));
         }
@@ -260,6 +647,143 @@ impl CompileOptions {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_build_options() {
+        let opts = CompileOptions {
+            ftz: Some(true),
+            prec_div: Some(false),
+            use_fast_math: Some(true),
+            maxrregcount: Some(32),
+            relocatable_device_code: Some(true),
+            arch: Some("sm_80"),
+            options: vec!["--std=c++17".into()],
+            ..Default::default()
+        };
+        assert_eq!(
+            opts.build(),
+            [
+                "--ftz=true",
+                "--prec-div=false",
+                "--use_fast_math",
+                "--maxrregcount=32",
+                "--relocatable-device-code=true",
+                "--gpu-architecture=sm_80",
+                "--std=c++17",
+            ]
+        );
+        assert!(CompileOptions::default().build().is_empty());
+        let opts = CompileOptions {
+            use_fast_math: Some(false),
+            ..Default::default()
+        };
+        assert!(opts.build().is_empty());
+    }
+
+    #[test]
+    fn test_build_options_builder() {
+        let opts = CompileOptions::default()
+            .use_fast_math(true)
+            .ftz(true)
+            .prec_div(false)
+            .relocatable_device_code(false)
+            .maxrregcount(64)
+            .optimization_level(3)
+            .option("--std=c++17");
+        assert_eq!(
+            opts.build(),
+            [
+                "--ftz=true",
+                "--prec-div=false",
+                "--use_fast_math",
+                "--maxrregcount=64",
+                "--relocatable-device-code=false",
+                "--ptxas-options=-O3",
+                "--std=c++17",
+            ]
+        );
+        assert_eq!(
+            CompileOptions::default().optimization_level(0).build(),
+            ["--dopt=off", "--ptxas-options=-O0"]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_optimization_level_out_of_range() {
+        let _ = CompileOptions::default().optimization_level(4);
+    }
+
+    #[test]
+    fn test_compile_fast_math() {
+        const SRC: &str = "extern \"C\" __global__ void kernel(float *x) {
+            x[threadIdx.x] = __fdividef(sinf(x[threadIdx.x]), 3.0f);
+        }";
+        let opts = CompileOptions {
+            use_fast_math: Some(true),
+            relocatable_device_code: Some(true),
+            ..Default::default()
+        };
+        compile_ptx_with_opts(SRC, opts).unwrap();
+    }
+
+    #[test]
+    fn test_cache_key_hasher_is_fixed() {
+        use std::hash::Hasher;
+        let mut hasher = Fnv1a64::default();
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn test_compile_ptx_cached() {
+        const SRC: &str =
+            "extern \"C\" __global__ void kernel(float *x) { x[threadIdx.x] = 1.0f; }";
+        let cache_dir =
+            std::env::temp_dir().join(std::format!("cudarc-ptx-cache-{}", std::process::id()));
+        std::fs::remove_dir_all(&cache_dir).ok();
+
+        let first = compile_ptx_cached(SRC, Default::default(), &cache_dir).unwrap();
+        let entries = || std::fs::read_dir(&cache_dir).unwrap().count();
+        assert_eq!(entries(), 1);
+        let second = compile_ptx_cached(SRC, Default::default(), &cache_dir).unwrap();
+        assert_eq!(entries(), 1);
+        assert_eq!(first.to_src(), second.to_src());
+
+        let opts = CompileOptions {
+            ftz: Some(true),
+            ..Default::default()
+        };
+        compile_ptx_cached(SRC, opts, &cache_dir).unwrap();
+        assert_eq!(entries(), 2);
+
+        let opts = CompileOptions {
+            name_expressions: vec!["kernel".into()],
+            ..Default::default()
+        };
+        compile_ptx_cached(SRC, opts, &cache_dir).unwrap();
+        assert_eq!(entries(), 2);
+
+        // headers on disk aren't part of the key, so they must not be cached
+        let opts = CompileOptions {
+            include_paths: vec![cache_dir.to_string_lossy().into()],
+            ..Default::default()
+        };
+        compile_ptx_cached(SRC, opts, &cache_dir).unwrap();
+        assert_eq!(entries(), 2);
+
+        let opts = CompileOptions {
+            arch: Some("sm_80"),
+            ..Default::default()
+        };
+        let cubin = compile_cubin_cached(SRC, opts.clone(), &cache_dir).unwrap();
+        assert_eq!(entries(), 3);
+        let cached = compile_cubin_cached(SRC, opts, &cache_dir).unwrap();
+        assert_eq!(entries(), 3);
+        assert_eq!(cubin.as_bytes(), cached.as_bytes());
+
+        std::fs::remove_dir_all(&cache_dir).unwrap();
+    }
+
     #[test]
     fn test_compile_no_opts() {
         const SRC: &str =
@@ -272,6 +796,70 @@ mod tests {
         compile_ptx_with_opts(SRC, Default::default()).unwrap();
     }
 
+    #[test]
+    fn test_compile_name_expressions() {
+        const SRC: &str = "template <typename T> __global__ void kernel(T *out) { out[0] = T(1); }";
+        let opts = CompileOptions {
+            name_expressions: std::vec!["kernel<float>".into(), "kernel<int>".into()],
+            ..Default::default()
+        };
+        let ptx = compile_ptx_with_opts(SRC, opts).unwrap();
+        assert_eq!(ptx.lowered_name("kernel<float>"), Some("_Z6kernelIfEvPT_"));
+        assert_eq!(ptx.lowered_name("kernel<int>"), Some("_Z6kernelIiEvPT_"));
+        assert_eq!(ptx.lowered_name("kernel<double>"), None);
+    }
+
+    #[test]
+    fn test_compile_log_on_success() {
+        const SRC: &str = "extern \"C\" __global__ void kernel(float *out) { int unused = 0; }";
+        let ptx = compile_ptx(SRC).unwrap();
+        assert!(
+            ptx.compile_log().contains("unused"),
+            "{}",
+            ptx.compile_log()
+        );
+    }
+
+    #[test]
+    fn test_compile_with_headers() {
+        const SRC: &str = "#include \"helpers.h\"
+        extern \"C\" __global__ void kernel(float *out) { out[0] = twice(out[0]); }";
+        let opts = CompileOptions {
+            headers: std::vec![(
+                "helpers.h".into(),
+                "__device__ float twice(float x) { return 2.0f * x; }".into(),
+            )],
+            ..Default::default()
+        };
+        compile_ptx_with_opts(SRC, opts).unwrap();
+
+        let missing = compile_ptx(SRC);
+        assert!(matches!(missing, Err(CompileError::CompileError { .. })));
+    }
+
+    #[test]
+    fn test_compile_cubin() {
+        const SRC: &str = "extern \"C\" __global__ void kernel(float *out) { out[0] = 1.0f; }";
+        let cubin = compile_cubin(
+            SRC,
+            CompileOptions {
+                arch: Some("sm_80"),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(!cubin.as_bytes().is_empty());
+
+        let virtual_arch = compile_cubin(
+            SRC,
+            CompileOptions {
+                arch: Some("compute_80"),
+                ..Default::default()
+            },
+        );
+        assert!(matches!(virtual_arch, Err(CompileError::GetCubinError(_))));
+    }
+
     #[test]
     fn test_compile_options_build_none() {
         let opts: CompileOptions = Default::default();
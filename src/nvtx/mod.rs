@@ -0,0 +1,16 @@
+//! Wrappers around the [NVTX API](https://nvidia.github.io/NVTX/doxygen/index.html)
+//! in three levels. See crate documentation for description of each.
+//!
+//! Annotates the timeline of profilers like Nsight Systems with named ranges, see
+//! [NvtxRange] and [nvtx_range_push()]. Also available as `cudarc::profiler`.
+//!
+//! When no profiler is attached these calls do nothing, but the NVTX library (`libnvToolsExt`)
+//! must still be installed: with the `dynamic-loading` feature, the first call **panics** if it
+//! can't be found.
+
+pub mod result;
+pub mod safe;
+#[allow(warnings)]
+pub mod sys;
+
+pub use safe::*;
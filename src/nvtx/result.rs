@@ -0,0 +1,30 @@
+//! A thin wrapper around [sys].
+//!
+//! Unlike the other apis, NVTX functions do not report errors. Push/pop return the nesting
+//! level of the range, which is negative when no profiler is attached.
+
+use super::sys;
+use core::ffi::CStr;
+
+/// Starts a nested range on the calling thread, returning the zero-based nesting level of the
+/// range (or a negative value if no profiler is recording ranges).
+///
+/// See [NVTX docs](https://nvidia.github.io/NVTX/doxygen/group___r_a_n_g_e_s.html)
+pub fn range_push(message: &CStr) -> i32 {
+    unsafe { sys::nvtxRangePushA(message.as_ptr()) }
+}
+
+/// Ends the innermost range started with [range_push()] on the calling thread, returning the
+/// nesting level of the ended range (or a negative value if no profiler is recording ranges).
+///
+/// See [NVTX docs](https://nvidia.github.io/NVTX/doxygen/group___r_a_n_g_e_s.html)
+pub fn range_pop() -> i32 {
+    unsafe { sys::nvtxRangePop() }
+}
+
+/// Marks an instantaneous event.
+///
+/// See [NVTX docs](https://nvidia.github.io/NVTX/doxygen/group___m_a_r_k_e_r_s___a_n_d___r_a_n_g_e_s.html)
+pub fn mark(message: &CStr) {
+    unsafe { sys::nvtxMarkA(message.as_ptr()) }
+}
@@ -0,0 +1,77 @@
+use super::result;
+use std::{ffi::CString, marker::PhantomData};
+
+/// Starts a named range on the calling thread, which shows up in profilers like Nsight Systems.
+/// Each push must be matched by a [nvtx_range_pop()] on the same thread. Prefer [NvtxRange],
+/// which pops on drop.
+///
+/// Panics if `label` contains a nul byte.
+pub fn nvtx_range_push(label: &str) {
+    let label = CString::new(label).unwrap();
+    result::range_push(&label);
+}
+
+/// Ends the innermost range started with [nvtx_range_push()] on the calling thread.
+pub fn nvtx_range_pop() {
+    result::range_pop();
+}
+
+/// Marks an instantaneous named event on the timeline.
+///
+/// Panics if `label` contains a nul byte.
+pub fn nvtx_mark(label: &str) {
+    let label = CString::new(label).unwrap();
+    result::mark(&label);
+}
+
+/// A named range that is pushed on creation and popped on drop, e.g.
+///
+/// ```ignore
+/// {
+///     let _range = NvtxRange::new("forward");
+///     // ... launch kernels ...
+/// }
+/// ```
+///
+/// Ranges are per thread, so this is neither [Send] nor [Sync]. Ranges nest, and should be
+/// dropped in reverse order of creation.
+#[derive(Debug)]
+pub struct NvtxRange {
+    marker: PhantomData<*const ()>,
+}
+
+impl NvtxRange {
+    /// Pushes a range named `label`, see [nvtx_range_push()].
+    pub fn new(label: &str) -> Self {
+        nvtx_range_push(label);
+        Self {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl Drop for NvtxRange {
+    fn drop(&mut self) {
+        nvtx_range_pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_ranges() {
+        let outer = NvtxRange::new("outer");
+        {
+            let _inner = NvtxRange::new("inner");
+            nvtx_mark("inside");
+        }
+        nvtx_range_push("manual");
+        nvtx_range_pop();
+        drop(outer);
+        drop(crate::profiler::NvtxRange::new(
+            "through the profiler alias",
+        ));
+    }
+}
@@ -0,0 +1,86 @@
+#![cfg_attr(feature = "no-std", no_std)]
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+#![allow(dead_code)]
+#[cfg(feature = "no-std")]
+extern crate alloc;
+#[cfg(feature = "no-std")]
+extern crate no_std_compat as std;
+#[cfg(not(feature = "dynamic-loading"))]
+extern "C" {
+    pub fn nvtxMarkA(message: *const ::core::ffi::c_char);
+    pub fn nvtxRangePop() -> ::core::ffi::c_int;
+    pub fn nvtxRangePushA(message: *const ::core::ffi::c_char) -> ::core::ffi::c_int;
+}
+#[cfg(feature = "dynamic-loading")]
+mod loaded {
+    use super::*;
+    pub unsafe fn nvtxMarkA(message: *const ::core::ffi::c_char) {
+        (culib().nvtxMarkA)(message)
+    }
+    pub unsafe fn nvtxRangePop() -> ::core::ffi::c_int {
+        (culib().nvtxRangePop)()
+    }
+    pub unsafe fn nvtxRangePushA(message: *const ::core::ffi::c_char) -> ::core::ffi::c_int {
+        (culib().nvtxRangePushA)(message)
+    }
+    pub struct Lib {
+        __library: ::libloading::Library,
+        pub nvtxMarkA: unsafe extern "C" fn(message: *const ::core::ffi::c_char),
+        pub nvtxRangePop: unsafe extern "C" fn() -> ::core::ffi::c_int,
+        pub nvtxRangePushA:
+            unsafe extern "C" fn(message: *const ::core::ffi::c_char) -> ::core::ffi::c_int,
+    }
+    impl Lib {
+        pub unsafe fn new<P>(path: P) -> Result<Self, ::libloading::Error>
+        where
+            P: AsRef<::std::ffi::OsStr>,
+        {
+            let library = ::libloading::Library::new(path)?;
+            Self::from_library(library)
+        }
+        pub unsafe fn from_library<L>(library: L) -> Result<Self, ::libloading::Error>
+        where
+            L: Into<::libloading::Library>,
+        {
+            let __library = library.into();
+            let nvtxMarkA = __library
+                .get(b"nvtxMarkA\0")
+                .map(|sym| *sym)
+                .expect("Expected symbol in library");
+            let nvtxRangePop = __library
+                .get(b"nvtxRangePop\0")
+                .map(|sym| *sym)
+                .expect("Expected symbol in library");
+            let nvtxRangePushA = __library
+                .get(b"nvtxRangePushA\0")
+                .map(|sym| *sym)
+                .expect("Expected symbol in library");
+            Ok(Self {
+                __library,
+                nvtxMarkA,
+                nvtxRangePop,
+                nvtxRangePushA,
+            })
+        }
+    }
+    pub unsafe fn culib() -> &'static Lib {
+        static LIB: std::sync::OnceLock<Lib> = std::sync::OnceLock::new();
+        LIB.get_or_init(|| {
+            let lib_names = std::vec!["nvToolsExt"];
+            let choices: std::vec::Vec<_> = lib_names
+                .iter()
+                .map(|l| crate::get_lib_name_candidates(l))
+                .flatten()
+                .collect();
+            for choice in choices.iter() {
+                if let Ok(lib) = Lib::new(choice) {
+                    return lib;
+                }
+            }
+            crate::panic_no_lib_found(lib_names[0], &choices);
+        })
+    }
+}
+#[cfg(feature = "dynamic-loading")]
+pub use loaded::*;
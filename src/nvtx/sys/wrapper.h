@@ -0,0 +1 @@
+#include "nvToolsExt.h"
\ No newline at end of file